@@ -0,0 +1,28 @@
+-- Per-platform listing content (overrides the generic object data)
+-- item_specifics is stored as a JSON object {"name": "value"}
+ALTER TABLE object_platforms ADD COLUMN title TEXT;
+ALTER TABLE object_platforms ADD COLUMN description TEXT;
+ALTER TABLE object_platforms ADD COLUMN price REAL;
+ALTER TABLE object_platforms ADD COLUMN currency TEXT DEFAULT 'EUR';
+ALTER TABLE object_platforms ADD COLUMN condition TEXT;
+ALTER TABLE object_platforms ADD COLUMN shipping_profile TEXT;
+ALTER TABLE object_platforms ADD COLUMN platform_category_id TEXT;
+ALTER TABLE object_platforms ADD COLUMN item_specifics TEXT;
+
+-- Listing rules for each platform, checked before publishing
+ALTER TABLE platforms ADD COLUMN title_max_length INTEGER;
+ALTER TABLE platforms ADD COLUMN description_max_length INTEGER;
+ALTER TABLE platforms ADD COLUMN max_photos INTEGER;
+ALTER TABLE platforms ADD COLUMN max_photo_dimension INTEGER;
+ALTER TABLE platforms ADD COLUMN requires_category_id BOOLEAN DEFAULT 0;
+ALTER TABLE platforms ADD COLUMN requires_condition BOOLEAN DEFAULT 0;
+
+-- Default rules for the built-in platforms. Applied once only: migrations run at every start and
+-- the user may clear a limit
+UPDATE platforms SET title_max_length = 80, description_max_length = 500000, max_photos = 24, max_photo_dimension = 1600, requires_category_id = 1, requires_condition = 1
+WHERE name = 'eBay' AND title_max_length IS NULL
+AND NOT EXISTS (SELECT 1 FROM settings WHERE key = 'platform_listing_rules_seeded');
+UPDATE platforms SET title_max_length = 70, description_max_length = 4000, max_photos = 10, max_photo_dimension = 1200, requires_category_id = 1, requires_condition = 0
+WHERE name = 'Leboncoin' AND title_max_length IS NULL
+AND NOT EXISTS (SELECT 1 FROM settings WHERE key = 'platform_listing_rules_seeded');
+INSERT OR IGNORE INTO settings (key, value) VALUES ('platform_listing_rules_seeded', '1');
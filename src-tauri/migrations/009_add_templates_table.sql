@@ -0,0 +1,22 @@
+-- Listing description templates with {{placeholders}}
+-- platform_id NULL means the template can be used for any platform
+CREATE TABLE IF NOT EXISTS templates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    platform_id INTEGER,
+    title_template TEXT,
+    body TEXT NOT NULL,
+    is_default BOOLEAN DEFAULT 0,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (platform_id) REFERENCES platforms(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_templates_platform_id ON templates(platform_id);
+
+-- Generic default template used when a platform has no default of its own. Seeded once only:
+-- migrations run at every start and the user may rename or delete it
+INSERT OR IGNORE INTO templates (name, platform_id, title_template, body, is_default)
+SELECT 'Default', NULL, '{{name}}', '{{#if description_txt}}{{description_txt}}{{else}}{{description}}{{/if}}', 1
+WHERE NOT EXISTS (SELECT 1 FROM settings WHERE key = 'default_template_seeded');
+INSERT OR IGNORE INTO settings (key, value) VALUES ('default_template_seeded', '1');
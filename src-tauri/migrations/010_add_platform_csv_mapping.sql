@@ -0,0 +1,3 @@
+-- Column mapping used for CSV bulk exports, stored per platform as JSON
+-- [{"header": "*Title", "source": "title"}, {"header": "*Format", "value": "FixedPrice"}]
+ALTER TABLE platforms ADD COLUMN csv_mapping TEXT;
@@ -0,0 +1,4 @@
+-- Reference of the object in external spreadsheets, used to update objects on re-import
+ALTER TABLE objects ADD COLUMN external_sku TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_objects_external_sku ON objects(external_sku);
@@ -0,0 +1,16 @@
+-- SKU generated for the object (or entered by hand), printed on labels
+ALTER TABLE objects ADD COLUMN sku TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_objects_sku ON objects(sku);
+
+-- Prefix used in the SKU of the objects of a category
+ALTER TABLE categories ADD COLUMN sku_prefix TEXT;
+
+-- Last sequence number used for each SKU prefix
+CREATE TABLE IF NOT EXISTS sku_sequences (
+    prefix TEXT PRIMARY KEY,
+    last_value INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT OR IGNORE INTO settings (key, value) VALUES ('sku_pattern', '{prefix}-{yy}{mm}-{seq:4}');
+INSERT OR IGNORE INTO settings (key, value) VALUES ('sku_default_prefix', 'SL');
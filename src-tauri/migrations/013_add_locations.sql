@@ -0,0 +1,35 @@
+-- Physical storage locations (warehouse -> shelf -> box)
+CREATE TABLE IF NOT EXISTS locations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL DEFAULT 'box',
+    parent_id INTEGER,
+    notes TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (parent_id) REFERENCES locations(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_locations_parent_id ON locations(parent_id);
+
+ALTER TABLE objects ADD COLUMN location_id INTEGER REFERENCES locations(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_objects_location_id ON objects(location_id);
+
+-- Every move of an object between locations. Paths are kept so the history stays readable
+-- after a location is renamed or deleted.
+CREATE TABLE IF NOT EXISTS object_location_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    object_id INTEGER NOT NULL,
+    from_location_id INTEGER,
+    to_location_id INTEGER,
+    from_path TEXT,
+    to_path TEXT,
+    note TEXT,
+    moved_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (object_id) REFERENCES objects(id) ON DELETE CASCADE,
+    FOREIGN KEY (from_location_id) REFERENCES locations(id) ON DELETE SET NULL,
+    FOREIGN KEY (to_location_id) REFERENCES locations(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_object_location_history_object_id ON object_location_history(object_id);
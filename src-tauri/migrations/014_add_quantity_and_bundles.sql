@@ -0,0 +1,58 @@
+-- Number of identical items in stock for an object
+ALTER TABLE objects ADD COLUMN quantity INTEGER NOT NULL DEFAULT 1;
+
+-- Lots grouping several objects that are listed and sold together
+CREATE TABLE IF NOT EXISTS bundles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    description TEXT,
+    price REAL,
+    currency TEXT DEFAULT 'EUR',
+    status TEXT NOT NULL DEFAULT 'draft',
+    platform_id INTEGER,
+    platform_url TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (platform_id) REFERENCES platforms(id) ON DELETE SET NULL
+);
+
+-- Objects of a bundle. `share` weights the part of the sale proceeds going to the object.
+CREATE TABLE IF NOT EXISTS bundle_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    bundle_id INTEGER NOT NULL,
+    object_id INTEGER NOT NULL,
+    quantity INTEGER NOT NULL DEFAULT 1,
+    share REAL,
+    FOREIGN KEY (bundle_id) REFERENCES bundles(id) ON DELETE CASCADE,
+    FOREIGN KEY (object_id) REFERENCES objects(id) ON DELETE CASCADE,
+    UNIQUE(bundle_id, object_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_bundle_items_object_id ON bundle_items(object_id);
+
+-- Sales of objects or bundles
+CREATE TABLE IF NOT EXISTS sales (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    bundle_id INTEGER,
+    platform_id INTEGER,
+    total_price REAL NOT NULL,
+    currency TEXT NOT NULL DEFAULT 'EUR',
+    notes TEXT,
+    sold_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (bundle_id) REFERENCES bundles(id) ON DELETE SET NULL,
+    FOREIGN KEY (platform_id) REFERENCES platforms(id) ON DELETE SET NULL
+);
+
+-- Objects sold in a sale with their part of the proceeds
+CREATE TABLE IF NOT EXISTS sale_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    sale_id INTEGER NOT NULL,
+    object_id INTEGER,
+    quantity INTEGER NOT NULL DEFAULT 1,
+    amount REAL NOT NULL,
+    FOREIGN KEY (sale_id) REFERENCES sales(id) ON DELETE CASCADE,
+    FOREIGN KEY (object_id) REFERENCES objects(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sale_items_sale_id ON sale_items(sale_id);
+CREATE INDEX IF NOT EXISTS idx_sale_items_object_id ON sale_items(object_id);
@@ -0,0 +1,37 @@
+-- Built-in descriptive fields required by most marketplaces
+ALTER TABLE objects ADD COLUMN condition TEXT;
+ALTER TABLE objects ADD COLUMN brand TEXT;
+ALTER TABLE objects ADD COLUMN length REAL;
+ALTER TABLE objects ADD COLUMN width REAL;
+ALTER TABLE objects ADD COLUMN height REAL;
+ALTER TABLE objects ADD COLUMN dimension_unit TEXT DEFAULT 'cm';
+
+CREATE INDEX IF NOT EXISTS idx_objects_brand ON objects(brand);
+
+-- Custom attributes, defined per category (or for every object when category_id is NULL)
+CREATE TABLE IF NOT EXISTS attribute_definitions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    category_id INTEGER,
+    name TEXT NOT NULL,
+    attribute_type TEXT NOT NULL DEFAULT 'text',
+    options TEXT,
+    display_order INTEGER DEFAULT 0,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_attribute_definitions_category_id ON attribute_definitions(category_id);
+
+-- Values of the custom attributes, stored as text (numbers and booleans normalized)
+CREATE TABLE IF NOT EXISTS object_attributes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    object_id INTEGER NOT NULL,
+    attribute_id INTEGER NOT NULL,
+    value TEXT NOT NULL,
+    FOREIGN KEY (object_id) REFERENCES objects(id) ON DELETE CASCADE,
+    FOREIGN KEY (attribute_id) REFERENCES attribute_definitions(id) ON DELETE CASCADE,
+    UNIQUE(object_id, attribute_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_object_attributes_attribute_id ON object_attributes(attribute_id);
@@ -0,0 +1,18 @@
+-- Nested categories: the folder of a sub-category lives inside the folder of its parent
+ALTER TABLE categories ADD COLUMN parent_id INTEGER REFERENCES categories(id);
+
+CREATE INDEX IF NOT EXISTS idx_categories_parent_id ON categories(parent_id);
+
+-- Marketplace category of each category (eBay category id, Leboncoin category...)
+CREATE TABLE IF NOT EXISTS category_platform_mappings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    category_id INTEGER NOT NULL,
+    platform_id INTEGER NOT NULL,
+    platform_category_id TEXT NOT NULL,
+    platform_category_name TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+    FOREIGN KEY (platform_id) REFERENCES platforms(id) ON DELETE CASCADE,
+    UNIQUE(category_id, platform_id)
+);
@@ -0,0 +1,16 @@
+-- Deleted files and folders kept in ~/.seller-library/trash until they are restored or purged
+CREATE TABLE IF NOT EXISTS trash_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    name TEXT NOT NULL,
+    original_path TEXT,
+    trash_path TEXT,
+    records TEXT NOT NULL DEFAULT '[]',
+    file_size INTEGER DEFAULT 0,
+    deleted_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_trash_entries_deleted_at ON trash_entries(deleted_at);
+
+-- Days before trash entries are purged automatically (0 keeps them forever)
+INSERT OR IGNORE INTO settings (key, value) VALUES ('trash_retention_days', '30');
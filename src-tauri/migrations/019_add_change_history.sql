@@ -0,0 +1,159 @@
+-- Change history of objects, listings, object photos and categories, filled by triggers so that
+-- every change is recorded whatever the command (edits, sales, imports, undo, trash restore...)
+-- The triggers list the tracked columns: they are dropped and recreated so that the latest
+-- definition replaces the stored one. A later migration adding a tracked column must drop and
+-- recreate the trigger of its table the same way, with the new column list
+CREATE TABLE IF NOT EXISTS change_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity_type TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    object_id INTEGER,
+    label TEXT,
+    action TEXT NOT NULL,
+    field TEXT,
+    old_value TEXT,
+    new_value TEXT,
+    changed_by TEXT,
+    changed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_change_history_object_id ON change_history(object_id);
+CREATE INDEX IF NOT EXISTS idx_change_history_entity ON change_history(entity_type, entity_id);
+
+-- Name of the user making the changes (first_name/last_name settings)
+CREATE VIEW IF NOT EXISTS history_user AS
+SELECT NULLIF(TRIM(
+    COALESCE((SELECT value FROM settings WHERE key = 'first_name'), '') || ' ' ||
+    COALESCE((SELECT value FROM settings WHERE key = 'last_name'), '')
+), '') AS name;
+
+-- objects
+DROP TRIGGER IF EXISTS history_objects_insert;
+CREATE TRIGGER history_objects_insert AFTER INSERT ON objects
+BEGIN
+    INSERT INTO change_history (entity_type, entity_id, object_id, label, action, field, old_value, new_value, changed_by)
+    VALUES ('object', NEW.id, NEW.id, NEW.name, 'create', NULL, NULL, NULL, (SELECT name FROM history_user));
+END;
+
+DROP TRIGGER IF EXISTS history_objects_update;
+CREATE TRIGGER history_objects_update AFTER UPDATE ON objects
+BEGIN
+    INSERT INTO change_history (entity_type, entity_id, object_id, label, action, field, old_value, new_value, changed_by)
+    SELECT 'object', NEW.id, NEW.id, NEW.name, 'update', field, old_value, new_value, (SELECT name FROM history_user)
+    FROM (
+        SELECT 'name' AS field, OLD.name AS old_value, NEW.name AS new_value
+        UNION ALL SELECT 'description', OLD.description, NEW.description
+        UNION ALL SELECT 'year', OLD.year, NEW.year
+        UNION ALL SELECT 'weight', OLD.weight, NEW.weight
+        UNION ALL SELECT 'category_id', OLD.category_id, NEW.category_id
+        UNION ALL SELECT 'external_sku', OLD.external_sku, NEW.external_sku
+        UNION ALL SELECT 'sku', OLD.sku, NEW.sku
+        UNION ALL SELECT 'location_id', OLD.location_id, NEW.location_id
+        UNION ALL SELECT 'quantity', OLD.quantity, NEW.quantity
+        UNION ALL SELECT 'condition', OLD.condition, NEW.condition
+        UNION ALL SELECT 'brand', OLD.brand, NEW.brand
+        UNION ALL SELECT 'length', OLD.length, NEW.length
+        UNION ALL SELECT 'width', OLD.width, NEW.width
+        UNION ALL SELECT 'height', OLD.height, NEW.height
+        UNION ALL SELECT 'dimension_unit', OLD.dimension_unit, NEW.dimension_unit
+    )
+    WHERE old_value IS NOT new_value;
+END;
+
+DROP TRIGGER IF EXISTS history_objects_delete;
+CREATE TRIGGER history_objects_delete AFTER DELETE ON objects
+BEGIN
+    INSERT INTO change_history (entity_type, entity_id, object_id, label, action, field, old_value, new_value, changed_by)
+    VALUES ('object', OLD.id, OLD.id, OLD.name, 'delete', NULL, NULL, NULL, (SELECT name FROM history_user));
+END;
+
+-- object_platforms
+DROP TRIGGER IF EXISTS history_object_platforms_insert;
+CREATE TRIGGER history_object_platforms_insert AFTER INSERT ON object_platforms
+BEGIN
+    INSERT INTO change_history (entity_type, entity_id, object_id, label, action, field, old_value, new_value, changed_by)
+    VALUES ('object_platform', NEW.id, NEW.object_id, COALESCE((SELECT name FROM platforms WHERE id = NEW.platform_id), 'platform #' || NEW.platform_id), 'create', NULL, NULL, NULL, (SELECT name FROM history_user));
+END;
+
+DROP TRIGGER IF EXISTS history_object_platforms_update;
+CREATE TRIGGER history_object_platforms_update AFTER UPDATE ON object_platforms
+BEGIN
+    INSERT INTO change_history (entity_type, entity_id, object_id, label, action, field, old_value, new_value, changed_by)
+    SELECT 'object_platform', NEW.id, NEW.object_id, COALESCE((SELECT name FROM platforms WHERE id = NEW.platform_id), 'platform #' || NEW.platform_id), 'update', field, old_value, new_value, (SELECT name FROM history_user)
+    FROM (
+        SELECT 'platform_url' AS field, OLD.platform_url AS old_value, NEW.platform_url AS new_value
+        UNION ALL SELECT 'status', OLD.status, NEW.status
+        UNION ALL SELECT 'listed_at', OLD.listed_at, NEW.listed_at
+        UNION ALL SELECT 'title', OLD.title, NEW.title
+        UNION ALL SELECT 'description', OLD.description, NEW.description
+        UNION ALL SELECT 'price', OLD.price, NEW.price
+        UNION ALL SELECT 'currency', OLD.currency, NEW.currency
+        UNION ALL SELECT 'condition', OLD.condition, NEW.condition
+        UNION ALL SELECT 'shipping_profile', OLD.shipping_profile, NEW.shipping_profile
+        UNION ALL SELECT 'platform_category_id', OLD.platform_category_id, NEW.platform_category_id
+        UNION ALL SELECT 'item_specifics', OLD.item_specifics, NEW.item_specifics
+    )
+    WHERE old_value IS NOT new_value;
+END;
+
+DROP TRIGGER IF EXISTS history_object_platforms_delete;
+CREATE TRIGGER history_object_platforms_delete AFTER DELETE ON object_platforms
+BEGIN
+    INSERT INTO change_history (entity_type, entity_id, object_id, label, action, field, old_value, new_value, changed_by)
+    VALUES ('object_platform', OLD.id, OLD.object_id, COALESCE((SELECT name FROM platforms WHERE id = OLD.platform_id), 'platform #' || OLD.platform_id), 'delete', NULL, NULL, NULL, (SELECT name FROM history_user));
+END;
+
+-- object_photos (the photo is already gone when its deletion removes the association)
+DROP TRIGGER IF EXISTS history_object_photos_insert;
+CREATE TRIGGER history_object_photos_insert AFTER INSERT ON object_photos
+BEGIN
+    INSERT INTO change_history (entity_type, entity_id, object_id, label, action, field, old_value, new_value, changed_by)
+    VALUES ('object_photo', NEW.id, NEW.object_id, COALESCE((SELECT file_name FROM photos WHERE id = NEW.photo_id), 'photo #' || NEW.photo_id), 'create', NULL, NULL, NULL, (SELECT name FROM history_user));
+END;
+
+DROP TRIGGER IF EXISTS history_object_photos_update;
+CREATE TRIGGER history_object_photos_update AFTER UPDATE ON object_photos
+BEGIN
+    INSERT INTO change_history (entity_type, entity_id, object_id, label, action, field, old_value, new_value, changed_by)
+    SELECT 'object_photo', NEW.id, NEW.object_id, COALESCE((SELECT file_name FROM photos WHERE id = NEW.photo_id), 'photo #' || NEW.photo_id), 'update', field, old_value, new_value, (SELECT name FROM history_user)
+    FROM (
+        SELECT 'photo_id' AS field, OLD.photo_id AS old_value, NEW.photo_id AS new_value
+        UNION ALL SELECT 'display_order', OLD.display_order, NEW.display_order
+    )
+    WHERE old_value IS NOT new_value;
+END;
+
+DROP TRIGGER IF EXISTS history_object_photos_delete;
+CREATE TRIGGER history_object_photos_delete AFTER DELETE ON object_photos
+BEGIN
+    INSERT INTO change_history (entity_type, entity_id, object_id, label, action, field, old_value, new_value, changed_by)
+    VALUES ('object_photo', OLD.id, OLD.object_id, COALESCE((SELECT file_name FROM photos WHERE id = OLD.photo_id), 'photo #' || OLD.photo_id), 'delete', NULL, NULL, NULL, (SELECT name FROM history_user));
+END;
+
+-- categories
+DROP TRIGGER IF EXISTS history_categories_insert;
+CREATE TRIGGER history_categories_insert AFTER INSERT ON categories
+BEGIN
+    INSERT INTO change_history (entity_type, entity_id, object_id, label, action, field, old_value, new_value, changed_by)
+    VALUES ('category', NEW.id, NULL, NEW.name, 'create', NULL, NULL, NULL, (SELECT name FROM history_user));
+END;
+
+DROP TRIGGER IF EXISTS history_categories_update;
+CREATE TRIGGER history_categories_update AFTER UPDATE ON categories
+BEGIN
+    INSERT INTO change_history (entity_type, entity_id, object_id, label, action, field, old_value, new_value, changed_by)
+    SELECT 'category', NEW.id, NULL, NEW.name, 'update', field, old_value, new_value, (SELECT name FROM history_user)
+    FROM (
+        SELECT 'name' AS field, OLD.name AS old_value, NEW.name AS new_value
+        UNION ALL SELECT 'parent_id', OLD.parent_id, NEW.parent_id
+        UNION ALL SELECT 'sku_prefix', OLD.sku_prefix, NEW.sku_prefix
+    )
+    WHERE old_value IS NOT new_value;
+END;
+
+DROP TRIGGER IF EXISTS history_categories_delete;
+CREATE TRIGGER history_categories_delete AFTER DELETE ON categories
+BEGIN
+    INSERT INTO change_history (entity_type, entity_id, object_id, label, action, field, old_value, new_value, changed_by)
+    VALUES ('category', OLD.id, NULL, OLD.name, 'delete', NULL, NULL, NULL, (SELECT name FROM history_user));
+END;
@@ -0,0 +1,26 @@
+-- Paths under the root folder are stored relative to it, so that the library can be moved
+-- or mounted elsewhere by only changing the root_folder setting (paths outside stay absolute)
+
+WITH root AS (SELECT rtrim(value, '/\') AS path FROM settings WHERE key = 'root_folder' AND value != '')
+UPDATE OR IGNORE folders SET path = substr(path, length((SELECT path FROM root)) + 2)
+WHERE substr(path, 1, length((SELECT path FROM root)) + 1) IN ((SELECT path FROM root) || '/', (SELECT path FROM root) || '\')
+  AND length(path) > length((SELECT path FROM root)) + 1;
+
+WITH root AS (SELECT rtrim(value, '/\') AS path FROM settings WHERE key = 'root_folder' AND value != '')
+UPDATE OR IGNORE photos SET file_path = substr(file_path, length((SELECT path FROM root)) + 2)
+WHERE substr(file_path, 1, length((SELECT path FROM root)) + 1) IN ((SELECT path FROM root) || '/', (SELECT path FROM root) || '\')
+  AND length(file_path) > length((SELECT path FROM root)) + 1;
+
+WITH root AS (SELECT rtrim(value, '/\') AS path FROM settings WHERE key = 'root_folder' AND value != '')
+UPDATE OR IGNORE photos SET original_path = substr(original_path, length((SELECT path FROM root)) + 2)
+WHERE substr(original_path, 1, length((SELECT path FROM root)) + 1) IN ((SELECT path FROM root) || '/', (SELECT path FROM root) || '\')
+  AND length(original_path) > length((SELECT path FROM root)) + 1;
+
+WITH root AS (SELECT rtrim(value, '/\') AS path FROM settings WHERE key = 'root_folder' AND value != '')
+UPDATE OR IGNORE text_files SET file_path = substr(file_path, length((SELECT path FROM root)) + 2)
+WHERE substr(file_path, 1, length((SELECT path FROM root)) + 1) IN ((SELECT path FROM root) || '/', (SELECT path FROM root) || '\')
+  AND length(file_path) > length((SELECT path FROM root)) + 1;
+
+-- Migration 004 derives folders from the photo paths: a relative photo stored directly in the
+-- root folder gives an empty folder path
+DELETE FROM folders WHERE path = '';
@@ -0,0 +1,5 @@
+-- SHA-256 of the photo file, recorded on import (filled in by the syncs for older rows) to
+-- match photos moved outside the application
+ALTER TABLE photos ADD COLUMN content_hash TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_photos_content_hash ON photos(content_hash);
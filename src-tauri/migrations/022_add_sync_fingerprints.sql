@@ -0,0 +1,5 @@
+-- Modification times (milliseconds) seen by the last sync: the incremental sync only reads the
+-- folders whose modification time changed and the files stored in them
+ALTER TABLE photos ADD COLUMN file_mtime INTEGER;
+ALTER TABLE text_files ADD COLUMN file_mtime INTEGER;
+ALTER TABLE folders ADD COLUMN mtime INTEGER;
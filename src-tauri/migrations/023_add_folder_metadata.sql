@@ -0,0 +1,4 @@
+-- Notes, colour label and tags of folders (tags: JSON array of strings)
+ALTER TABLE folders ADD COLUMN notes TEXT;
+ALTER TABLE folders ADD COLUMN color TEXT;
+ALTER TABLE folders ADD COLUMN tags TEXT;
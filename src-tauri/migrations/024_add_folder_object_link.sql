@@ -0,0 +1,3 @@
+-- Object whose photos and description.txt are in a folder ("one folder per item")
+ALTER TABLE folders ADD COLUMN object_id INTEGER REFERENCES objects(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_folders_object_id ON folders(object_id);
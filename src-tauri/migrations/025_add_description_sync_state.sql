@@ -0,0 +1,6 @@
+-- Last sync of a description.txt with the description of its linked object: time of the sync and
+-- text of both sides then. Each side changed since if its text differs from the synced one.
+-- Rows never synced (NULL time), including the ones synced before this migration, are compared
+-- like a folder linked with its own description.
+ALTER TABLE text_files ADD COLUMN description_synced_at TIMESTAMP;
+ALTER TABLE text_files ADD COLUMN synced_description TEXT;
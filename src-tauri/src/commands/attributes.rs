@@ -0,0 +1,471 @@
+use crate::models::{Object, AttributeDefinition, CreateAttributeDefinition, ObjectAttributeValue, UpdateAttributeDefinition};
+use sqlx::{SqliteConnection, SqlitePool};
+use std::collections::HashMap;
+use tauri::State;
+
+use super::categories;
+
+const ATTRIBUTE_TYPES: &[&str] = &["text", "number", "enum", "boolean"];
+
+pub(crate) const CONDITION_GRADES: &[&str] = &["new", "like_new", "very_good", "good", "acceptable", "for_parts"];
+
+pub(crate) const DIMENSION_UNITS: &[&str] = &["mm", "cm", "m", "in"];
+
+// ========== CUSTOM ATTRIBUTES COMMANDS ==========
+
+pub(crate) fn check_condition(condition: &str) -> Result<(), String> {
+    if condition.is_empty() || CONDITION_GRADES.contains(&condition) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unknown condition '{}' (expected one of: {})",
+            condition,
+            CONDITION_GRADES.join(", ")
+        ))
+    }
+}
+
+pub(crate) fn check_dimension_unit(unit: &str) -> Result<(), String> {
+    if DIMENSION_UNITS.contains(&unit) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unknown dimension unit '{}' (expected one of: {})",
+            unit,
+            DIMENSION_UNITS.join(", ")
+        ))
+    }
+}
+
+pub(crate) fn check_dimension(name: &str, value: Option<f64>) -> Result<(), String> {
+    match value {
+        Some(value) if value < 0.0 => Err(format!("{} can't be negative", name)),
+        _ => Ok(()),
+    }
+}
+
+// Dimensions of an object as shown in listings, e.g. "30 x 20 x 10 cm" (empty when unknown)
+pub(crate) fn format_dimensions(object: &Object) -> String {
+    let parts: Vec<String> = [object.length, object.width, object.height]
+        .iter()
+        .flatten()
+        .map(|value| value.to_string())
+        .collect();
+
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{} {}", parts.join(" x "), object.dimension_unit.as_deref().unwrap_or("cm"))
+    }
+}
+
+fn enum_options(definition: &AttributeDefinition) -> Vec<String> {
+    definition
+        .options
+        .as_ref()
+        .and_then(|options| serde_json::from_str::<Vec<String>>(options).ok())
+        .unwrap_or_default()
+}
+
+// Check a value against the type of its attribute and return it as stored in the database
+pub(crate) fn normalize_attribute_value(
+    definition: &AttributeDefinition,
+    value: &serde_json::Value,
+) -> Result<String, String> {
+    let invalid = || format!("Invalid value for '{}': {}", definition.name, value);
+
+    match definition.attribute_type.as_str() {
+        "number" => {
+            let number = match value {
+                serde_json::Value::Number(n) => n.as_f64(),
+                serde_json::Value::String(s) => s.trim().replace(',', ".").parse::<f64>().ok(),
+                _ => None,
+            }
+            .ok_or_else(invalid)?;
+            Ok(number.to_string())
+        }
+        "boolean" => match value {
+            serde_json::Value::Bool(b) => Ok(b.to_string()),
+            serde_json::Value::String(s) => match s.trim().to_lowercase().as_str() {
+                "true" | "yes" | "1" => Ok("true".to_string()),
+                "false" | "no" | "0" => Ok("false".to_string()),
+                _ => Err(invalid()),
+            },
+            _ => Err(invalid()),
+        },
+        "enum" => {
+            let text = value.as_str().ok_or_else(invalid)?;
+            let options = enum_options(definition);
+            options
+                .iter()
+                .find(|option| option.eq_ignore_ascii_case(text.trim()))
+                .cloned()
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid value for '{}': '{}' (expected one of: {})",
+                        definition.name,
+                        text,
+                        options.join(", ")
+                    )
+                })
+        }
+        _ => match value {
+            serde_json::Value::String(s) => Ok(s.trim().to_string()),
+            serde_json::Value::Number(n) => Ok(n.to_string()),
+            serde_json::Value::Bool(b) => Ok(b.to_string()),
+            _ => Err(invalid()),
+        },
+    }
+}
+
+pub(crate) async fn fetch_attribute_definition(
+    conn: &mut SqliteConnection,
+    id: i64,
+) -> Result<AttributeDefinition, String> {
+    sqlx::query_as::<_, AttributeDefinition>("SELECT * FROM attribute_definitions WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Attribute {} not found", id))
+}
+
+// Set (or remove, for null values) custom attribute values of an object.
+// Attributes must be global or belong to the category of the object (or one of its parents).
+pub(crate) async fn save_object_attributes(
+    conn: &mut SqliteConnection,
+    object_id: i64,
+    values: &HashMap<i64, serde_json::Value>,
+) -> Result<(), String> {
+    let category_id = sqlx::query_scalar::<_, Option<i64>>("SELECT category_id FROM objects WHERE id = ?")
+        .bind(object_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Object not found".to_string())?;
+
+    for (attribute_id, value) in values {
+        let definition = fetch_attribute_definition(conn, *attribute_id).await?;
+
+        if value.is_null() || value.as_str().is_some_and(|s| s.trim().is_empty()) {
+            sqlx::query("DELETE FROM object_attributes WHERE object_id = ? AND attribute_id = ?")
+                .bind(object_id)
+                .bind(attribute_id)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+            continue;
+        }
+
+        let applies = match (definition.category_id, category_id) {
+            (None, _) => true,
+            (Some(definition_category), Some(category_id)) => {
+                categories::category_ancestor_ids(conn, category_id).await?.contains(&definition_category)
+            }
+            (Some(_), None) => false,
+        };
+        if !applies {
+            return Err(format!(
+                "Attribute '{}' doesn't apply to the category of this object",
+                definition.name
+            ));
+        }
+
+        let stored = normalize_attribute_value(&definition, value)?;
+
+        sqlx::query(
+            "INSERT INTO object_attributes (object_id, attribute_id, value) VALUES (?, ?, ?)
+             ON CONFLICT(object_id, attribute_id) DO UPDATE SET value = excluded.value"
+        )
+        .bind(object_id)
+        .bind(attribute_id)
+        .bind(&stored)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn fetch_object_attributes(
+    conn: &mut SqliteConnection,
+    object_id: i64,
+) -> Result<Vec<ObjectAttributeValue>, String> {
+    sqlx::query_as::<_, ObjectAttributeValue>(
+        "SELECT ad.id AS attribute_id, ad.name, ad.attribute_type, oa.value
+         FROM object_attributes oa
+         INNER JOIN attribute_definitions ad ON ad.id = oa.attribute_id
+         WHERE oa.object_id = ?
+         ORDER BY ad.display_order ASC, ad.name ASC"
+    )
+    .bind(object_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+fn check_options(attribute_type: &str, options: &Option<Vec<String>>) -> Result<Option<String>, String> {
+    match (attribute_type, options) {
+        ("enum", Some(options)) => {
+            let options: Vec<String> = options
+                .iter()
+                .map(|o| o.trim().to_string())
+                .filter(|o| !o.is_empty())
+                .collect();
+            if options.is_empty() {
+                return Err("Enum attributes need at least one option".to_string());
+            }
+            serde_json::to_string(&options).map(Some).map_err(|e| e.to_string())
+        }
+        ("enum", None) => Err("Enum attributes need at least one option".to_string()),
+        (_, Some(_)) => Err("Only enum attributes have options".to_string()),
+        (_, None) => Ok(None),
+    }
+}
+
+// Attributes available for a category: the global ones and those of the category and its parents
+// (every attribute when no category is given)
+#[tauri::command]
+pub async fn list_attribute_definitions(
+    pool: State<'_, SqlitePool>,
+    category_id: Option<i64>,
+) -> Result<Vec<AttributeDefinition>, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+
+    let definitions = sqlx::query_as::<_, AttributeDefinition>(
+        "SELECT * FROM attribute_definitions ORDER BY display_order ASC, name ASC"
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let Some(category_id) = category_id else {
+        return Ok(definitions);
+    };
+
+    let category_ids = categories::category_ancestor_ids(&mut conn, category_id).await?;
+    Ok(definitions
+        .into_iter()
+        .filter(|d| d.category_id.is_none_or(|id| category_ids.contains(&id)))
+        .collect())
+}
+
+#[tauri::command]
+pub async fn create_attribute_definition(
+    pool: State<'_, SqlitePool>,
+    definition: CreateAttributeDefinition,
+) -> Result<AttributeDefinition, String> {
+    let name = definition.name.trim();
+    if name.is_empty() {
+        return Err("Attribute name is required".to_string());
+    }
+    if !ATTRIBUTE_TYPES.contains(&definition.attribute_type.as_str()) {
+        return Err(format!(
+            "Unknown attribute type '{}' (expected one of: {})",
+            definition.attribute_type,
+            ATTRIBUTE_TYPES.join(", ")
+        ));
+    }
+    let options = check_options(&definition.attribute_type, &definition.options)?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM attribute_definitions
+         WHERE name = ? COLLATE NOCASE AND (category_id IS ? OR category_id IS NULL OR ? IS NULL)"
+    )
+    .bind(name)
+    .bind(definition.category_id)
+    .bind(definition.category_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    if exists > 0 {
+        return Err(format!("An attribute named '{}' already exists", name));
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO attribute_definitions (category_id, name, attribute_type, options, display_order)
+         VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(definition.category_id)
+    .bind(name)
+    .bind(&definition.attribute_type)
+    .bind(&options)
+    .bind(definition.display_order.unwrap_or(0))
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let created = fetch_attribute_definition(&mut tx, result.last_insert_rowid()).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(created)
+}
+
+// The type and category of an attribute can't change, as existing values depend on them
+#[tauri::command]
+pub async fn update_attribute_definition(
+    pool: State<'_, SqlitePool>,
+    id: i64,
+    definition: UpdateAttributeDefinition,
+) -> Result<AttributeDefinition, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let mut current = fetch_attribute_definition(&mut tx, id).await?;
+
+    if let Some(name) = definition.name {
+        if name.trim().is_empty() {
+            return Err("Attribute name is required".to_string());
+        }
+        current.name = name.trim().to_string();
+    }
+    if definition.options.is_some() {
+        current.options = check_options(&current.attribute_type, &definition.options)?;
+
+        // Values that are no longer allowed would be invalid
+        let options = enum_options(&current);
+        let values = sqlx::query_scalar::<_, String>("SELECT DISTINCT value FROM object_attributes WHERE attribute_id = ?")
+            .bind(id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Some(value) = values.iter().find(|v| !options.contains(v)) {
+            return Err(format!("Option '{}' is still used by some objects", value));
+        }
+    }
+    if let Some(display_order) = definition.display_order {
+        current.display_order = display_order;
+    }
+
+    sqlx::query(
+        "UPDATE attribute_definitions SET name = ?, options = ?, display_order = ?, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?"
+    )
+    .bind(&current.name)
+    .bind(&current.options)
+    .bind(current.display_order)
+    .bind(id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let updated = fetch_attribute_definition(&mut tx, id).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(updated)
+}
+
+// Deleting an attribute also deletes its values
+#[tauri::command]
+pub async fn delete_attribute_definition(pool: State<'_, SqlitePool>, id: i64) -> Result<(), String> {
+    let result = sqlx::query("DELETE FROM attribute_definitions WHERE id = ?")
+        .bind(id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if result.rows_affected() == 0 {
+        Err("Attribute not found".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+#[tauri::command]
+pub async fn get_object_attributes(
+    pool: State<'_, SqlitePool>,
+    object_id: i64,
+) -> Result<Vec<ObjectAttributeValue>, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    fetch_object_attributes(&mut conn, object_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn definition(attribute_type: &str, options: Option<&str>) -> AttributeDefinition {
+        AttributeDefinition {
+            id: 1,
+            category_id: None,
+            name: "Material".to_string(),
+            attribute_type: attribute_type.to_string(),
+            options: options.map(str::to_string),
+            display_order: 0,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn text_values() {
+        let text = definition("text", None);
+        assert_eq!(normalize_attribute_value(&text, &json!(" brass ")), Ok("brass".to_string()));
+        assert_eq!(normalize_attribute_value(&text, &json!(12)), Ok("12".to_string()));
+        assert_eq!(normalize_attribute_value(&text, &json!(true)), Ok("true".to_string()));
+        assert_eq!(normalize_attribute_value(&text, &json!(null)), Err("Invalid value for 'Material': null".to_string()));
+        assert!(normalize_attribute_value(&text, &json!(["brass"])).is_err());
+    }
+
+    #[test]
+    fn number_values() {
+        let number = definition("number", None);
+        assert_eq!(normalize_attribute_value(&number, &json!(12)), Ok("12".to_string()));
+        assert_eq!(normalize_attribute_value(&number, &json!(12.5)), Ok("12.5".to_string()));
+        // French decimal separator
+        assert_eq!(normalize_attribute_value(&number, &json!(" 12,5 ")), Ok("12.5".to_string()));
+        assert!(normalize_attribute_value(&number, &json!("12 cm")).is_err());
+        assert!(normalize_attribute_value(&number, &json!(true)).is_err());
+    }
+
+    #[test]
+    fn enum_values() {
+        let options = definition("enum", Some(r#"["Brass", "Silver"]"#));
+        assert_eq!(normalize_attribute_value(&options, &json!(" brass ")), Ok("Brass".to_string()));
+        assert_eq!(
+            normalize_attribute_value(&options, &json!("Gold")),
+            Err("Invalid value for 'Material': 'Gold' (expected one of: Brass, Silver)".to_string())
+        );
+        assert!(normalize_attribute_value(&options, &json!(1)).is_err());
+        // Unreadable options allow no value
+        let broken = definition("enum", Some("Brass, Silver"));
+        assert!(normalize_attribute_value(&broken, &json!("Brass")).is_err());
+    }
+
+    #[test]
+    fn boolean_values() {
+        let boolean = definition("boolean", None);
+        assert_eq!(normalize_attribute_value(&boolean, &json!(false)), Ok("false".to_string()));
+        for value in ["yes", " TRUE ", "1"] {
+            assert_eq!(normalize_attribute_value(&boolean, &json!(value)), Ok("true".to_string()));
+        }
+        for value in ["no", "False", "0"] {
+            assert_eq!(normalize_attribute_value(&boolean, &json!(value)), Ok("false".to_string()));
+        }
+        assert!(normalize_attribute_value(&boolean, &json!("maybe")).is_err());
+        assert!(normalize_attribute_value(&boolean, &json!(1)).is_err());
+    }
+
+    #[test]
+    fn object_fields() {
+        assert!(check_condition("very_good").is_ok());
+        // An empty condition clears it
+        assert!(check_condition("").is_ok());
+        assert!(check_condition("Very good").unwrap_err().starts_with("Unknown condition 'Very good'"));
+
+        assert!(check_dimension("Length", Some(0.0)).is_ok());
+        assert!(check_dimension("Length", None).is_ok());
+        assert_eq!(check_dimension("Width", Some(-1.0)), Err("Width can't be negative".to_string()));
+
+        assert!(check_dimension_unit("cm").is_ok());
+        assert_eq!(
+            check_dimension_unit("CM"),
+            Err("Unknown dimension unit 'CM' (expected one of: mm, cm, m, in)".to_string())
+        );
+    }
+}
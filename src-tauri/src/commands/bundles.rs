@@ -0,0 +1,233 @@
+use crate::models::{Bundle, BundleItem, BundleItemInput, CreateBundle, UpdateBundle};
+use sqlx::{SqliteConnection, SqlitePool};
+use std::collections::HashSet;
+use tauri::State;
+
+// ========== BUNDLES COMMANDS ==========
+
+async fn fetch_bundle(conn: &mut SqliteConnection, id: i64) -> Result<Bundle, String> {
+    sqlx::query_as::<_, Bundle>("SELECT * FROM bundles WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Bundle not found".to_string())
+}
+
+pub(crate) async fn fetch_bundle_items(
+    conn: &mut SqliteConnection,
+    bundle_id: i64,
+) -> Result<Vec<BundleItem>, String> {
+    sqlx::query_as::<_, BundleItem>("SELECT * FROM bundle_items WHERE bundle_id = ? ORDER BY id ASC")
+        .bind(bundle_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Replace the objects of a bundle
+async fn save_bundle_items(
+    conn: &mut SqliteConnection,
+    bundle_id: i64,
+    items: &[BundleItemInput],
+) -> Result<(), String> {
+    if items.len() < 2 {
+        return Err("A bundle needs at least two objects".to_string());
+    }
+
+    let mut seen = HashSet::new();
+    if let Some(item) = items.iter().find(|item| !seen.insert(item.object_id)) {
+        return Err(format!("Object {} is listed twice in the bundle", item.object_id));
+    }
+
+    sqlx::query("DELETE FROM bundle_items WHERE bundle_id = ?")
+        .bind(bundle_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    for item in items {
+        let quantity = item.quantity.unwrap_or(1);
+        if quantity < 1 {
+            return Err("Bundle quantities must be at least 1".to_string());
+        }
+        if item.share.is_some_and(|share| share < 0.0) {
+            return Err("Proceeds shares can't be negative".to_string());
+        }
+
+        let exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM objects WHERE id = ?")
+            .bind(item.object_id)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        if exists == 0 {
+            return Err(format!("Object {} not found", item.object_id));
+        }
+
+        sqlx::query("INSERT INTO bundle_items (bundle_id, object_id, quantity, share) VALUES (?, ?, ?, ?)")
+            .bind(bundle_id)
+            .bind(item.object_id)
+            .bind(quantity)
+            .bind(item.share)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_bundles(pool: State<'_, SqlitePool>) -> Result<Vec<Bundle>, String> {
+    sqlx::query_as::<_, Bundle>("SELECT * FROM bundles ORDER BY created_at DESC")
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_bundle_items(
+    pool: State<'_, SqlitePool>,
+    bundle_id: i64,
+) -> Result<Vec<BundleItem>, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    fetch_bundle_items(&mut conn, bundle_id).await
+}
+
+// Bundles an object belongs to
+#[tauri::command]
+pub async fn get_object_bundles(
+    pool: State<'_, SqlitePool>,
+    object_id: i64,
+) -> Result<Vec<Bundle>, String> {
+    sqlx::query_as::<_, Bundle>(
+        "SELECT b.* FROM bundles b
+         INNER JOIN bundle_items bi ON bi.bundle_id = b.id
+         WHERE bi.object_id = ?
+         ORDER BY b.created_at DESC"
+    )
+    .bind(object_id)
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_bundle(
+    pool: State<'_, SqlitePool>,
+    bundle: CreateBundle,
+) -> Result<Bundle, String> {
+    if bundle.name.trim().is_empty() {
+        return Err("Bundle name is required".to_string());
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let result = sqlx::query("INSERT INTO bundles (name, description, price, currency) VALUES (?, ?, ?, ?)")
+        .bind(bundle.name.trim())
+        .bind(&bundle.description)
+        .bind(bundle.price)
+        .bind(bundle.currency.unwrap_or_else(|| "EUR".to_string()).trim().to_uppercase())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let id = result.last_insert_rowid();
+    save_bundle_items(&mut tx, id, &bundle.items).await?;
+
+    let created = fetch_bundle(&mut tx, id).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(created)
+}
+
+#[tauri::command]
+pub async fn update_bundle(
+    pool: State<'_, SqlitePool>,
+    id: i64,
+    bundle: UpdateBundle,
+) -> Result<Bundle, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let mut current = fetch_bundle(&mut tx, id).await?;
+
+    if let Some(name) = bundle.name {
+        if name.trim().is_empty() {
+            return Err("Bundle name is required".to_string());
+        }
+        current.name = name.trim().to_string();
+    }
+    if bundle.description.is_some() {
+        current.description = bundle.description;
+    }
+    if bundle.price.is_some() {
+        current.price = bundle.price;
+    }
+    if let Some(currency) = bundle.currency {
+        current.currency = Some(currency.trim().to_uppercase());
+    }
+    if let Some(status) = bundle.status {
+        if current.status == "sold" && status != "sold" {
+            return Err("Cancel the sale of the bundle to make it available again".to_string());
+        }
+        match status.as_str() {
+            "draft" | "listed" => current.status = status,
+            "sold" if current.status == "sold" => {}
+            "sold" => return Err("Record a sale of the bundle to mark it as sold".to_string()),
+            _ => return Err(format!("Unknown bundle status: {}", status)),
+        }
+    }
+    if bundle.platform_id.is_some() {
+        current.platform_id = bundle.platform_id;
+    }
+    if bundle.platform_url.is_some() {
+        current.platform_url = bundle.platform_url;
+    }
+
+    if let Some(items) = &bundle.items {
+        if current.status == "sold" {
+            return Err("The objects of a sold bundle can't be changed".to_string());
+        }
+        save_bundle_items(&mut tx, id, items).await?;
+    }
+
+    sqlx::query(
+        "UPDATE bundles SET name = ?, description = ?, price = ?, currency = ?, status = ?,
+            platform_id = ?, platform_url = ?, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?"
+    )
+    .bind(&current.name)
+    .bind(&current.description)
+    .bind(current.price)
+    .bind(&current.currency)
+    .bind(&current.status)
+    .bind(current.platform_id)
+    .bind(&current.platform_url)
+    .bind(id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let updated = fetch_bundle(&mut tx, id).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(updated)
+}
+
+// Deleting a bundle keeps its objects and its past sales
+#[tauri::command]
+pub async fn delete_bundle(pool: State<'_, SqlitePool>, id: i64) -> Result<(), String> {
+    let result = sqlx::query("DELETE FROM bundles WHERE id = ?")
+        .bind(id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if result.rows_affected() == 0 {
+        Err("Bundle not found".to_string())
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,866 @@
+use crate::models::{Category, CategoryPlatformMapping, DeleteCategory, Folder, UpdateCategory};
+use serde::Serialize;
+use sqlx::{SqliteConnection, SqlitePool};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use super::journal::{self, JournalStep};
+use super::trash::{self, TrashedRecords};
+use super::transfers::ensure_folder_row;
+use super::{paths, replace_path_prefix};
+
+// ========== CATEGORY HIERARCHY ==========
+
+// Ids of a category and of its ancestors, from the category up to the top level
+pub(crate) async fn category_ancestor_ids(
+    conn: &mut SqliteConnection,
+    category_id: i64,
+) -> Result<Vec<i64>, String> {
+    sqlx::query_scalar::<_, i64>(
+        "WITH RECURSIVE ancestors(id, parent_id, depth) AS (
+            SELECT id, parent_id, 0 FROM categories WHERE id = ?
+            UNION ALL
+            SELECT c.id, c.parent_id, a.depth + 1
+            FROM categories c JOIN ancestors a ON c.id = a.parent_id
+         )
+         SELECT id FROM ancestors ORDER BY depth ASC"
+    )
+    .bind(category_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Folder of a category: CATEGORIES/<parent>/<child> under the root folder
+pub(crate) async fn category_folder_path(
+    conn: &mut SqliteConnection,
+    category_id: i64,
+    root_path: &str,
+) -> Result<PathBuf, String> {
+    let names = sqlx::query_scalar::<_, String>(
+        "WITH RECURSIVE ancestors(id, name, parent_id, depth) AS (
+            SELECT id, name, parent_id, 0 FROM categories WHERE id = ?
+            UNION ALL
+            SELECT c.id, c.name, c.parent_id, a.depth + 1
+            FROM categories c JOIN ancestors a ON c.id = a.parent_id
+         )
+         SELECT name FROM ancestors ORDER BY depth DESC"
+    )
+    .bind(category_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if names.is_empty() {
+        return Err("Category not found".to_string());
+    }
+
+    let mut path = Path::new(root_path).join("CATEGORIES");
+    for name in names {
+        path.push(name);
+    }
+    Ok(path)
+}
+
+fn check_category_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Category name is required".to_string());
+    }
+    if name.contains('/') || name.contains('\\') || name == "." || name == ".." {
+        return Err(format!("Invalid category name: {}", name));
+    }
+    Ok(())
+}
+
+// Rename a category and/or move it under another category. The folder of the category is
+// renamed/moved on disk and the paths of its folders, photos and text files are updated.
+#[tauri::command]
+pub async fn update_category(
+    pool: State<'_, SqlitePool>,
+    id: i64,
+    category: UpdateCategory,
+) -> Result<Category, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let mut current = sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Category not found".to_string())?;
+
+    let root_folder = sqlx::query_scalar::<_, String>("SELECT value FROM settings WHERE key = 'root_folder'")
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let old_folder = match &root_folder {
+        Some(root_path) => Some(category_folder_path(&mut tx, id, root_path).await?),
+        None => None,
+    };
+
+    if let Some(name) = category.name {
+        let name = name.trim().to_string();
+        check_category_name(&name)?;
+        current.name = name;
+    }
+
+    if category.clear_parent.unwrap_or(false) {
+        current.parent_id = None;
+    } else if let Some(parent_id) = category.parent_id {
+        // The new parent can't be the category itself or one of its sub-categories
+        if category_ancestor_ids(&mut tx, parent_id).await?.contains(&id) {
+            return Err("A category can't be moved inside itself".to_string());
+        }
+        let exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM categories WHERE id = ?")
+            .bind(parent_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        if exists == 0 {
+            return Err("Parent category not found".to_string());
+        }
+        current.parent_id = Some(parent_id);
+    }
+
+    sqlx::query("UPDATE categories SET name = ?, parent_id = ? WHERE id = ?")
+        .bind(&current.name)
+        .bind(current.parent_id)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("UNIQUE") {
+                format!("A category named '{}' already exists", current.name)
+            } else {
+                e.to_string()
+            }
+        })?;
+
+    // Move the folder of the category on disk, then update the stored paths
+    let mut moved_folder: Option<(PathBuf, PathBuf)> = None;
+
+    if let (Some(root_path), Some(old_folder)) = (&root_folder, old_folder) {
+        let new_folder = category_folder_path(&mut tx, id, root_path).await?;
+
+        if new_folder != old_folder {
+            if new_folder.exists() {
+                return Err(format!("Folder already exists: {}", new_folder.display()));
+            }
+
+            if old_folder.exists() {
+                if let Some(parent) = new_folder.parent() {
+                    fs::create_dir_all(parent).map_err(|e| format!("Failed to create folder: {}", e))?;
+                }
+                fs::rename(&old_folder, &new_folder)
+                    .map_err(|e| format!("Failed to move category folder: {}", e))?;
+                moved_folder = Some((old_folder.clone(), new_folder.clone()));
+            } else {
+                fs::create_dir_all(&new_folder)
+                    .map_err(|e| format!("Failed to create category folder: {}", e))?;
+            }
+
+            let result = update_category_folder_rows(&mut tx, &old_folder, &new_folder, &current.name).await;
+            if let Err(e) = result {
+                if let Some((old, new)) = &moved_folder {
+                    let _ = fs::rename(new, old);
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    let updated = sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE id = ?")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Err(e) = tx.commit().await {
+        // Put the folder back where the database expects it
+        if let Some((old, new)) = &moved_folder {
+            let _ = fs::rename(new, old);
+        }
+        return Err(e.to_string());
+    }
+
+    Ok(updated)
+}
+
+async fn update_category_folder_rows(
+    conn: &mut SqliteConnection,
+    old_folder: &Path,
+    new_folder: &Path,
+    name: &str,
+) -> Result<(), String> {
+    let old_path = old_folder.to_string_lossy().to_string();
+    let new_path = new_folder.to_string_lossy().to_string();
+
+    replace_path_prefix(conn, &old_path, &new_path).await?;
+    let root = paths::library_root(&mut *conn).await?;
+
+    // The folder row now hangs under the folder of the new parent category
+    let parent_folder_id = match new_folder.parent() {
+        Some(parent) => sqlx::query_scalar::<_, i64>("SELECT id FROM folders WHERE path = ?")
+            .bind(paths::to_stored(&root, &parent.to_string_lossy()))
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?,
+        None => None,
+    };
+
+    sqlx::query("UPDATE folders SET name = ?, parent_id = COALESCE(?, parent_id) WHERE path = ?")
+        .bind(name)
+        .bind(parent_folder_id)
+        .bind(paths::to_stored(&root, &new_path))
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// ========== CATEGORY DELETION ==========
+
+#[derive(Debug, Serialize)]
+pub struct CategoryDeletionPreview {
+    pub category: Category,
+    pub mode: String,
+    pub folder_path: Option<String>,
+    pub folder_exists: bool,
+    pub subcategory_count: i64,
+    pub object_count: i64,
+    pub attribute_count: i64,
+    pub folder_count: i64,
+    pub photo_count: i64,
+    pub associated_photo_count: i64,
+    pub text_file_count: i64,
+    pub total_size: u64,
+    // Where the content goes in 'move' mode
+    pub target_path: Option<String>,
+    // Entries that already exist in the target folder ('move' mode)
+    pub conflicts: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryDeletionResult {
+    pub mode: String,
+    pub moved_entries: usize,
+    // Trash entry to restore the category (and its files in 'delete' mode)
+    pub trash_entry_id: Option<i64>,
+    pub trash_path: Option<String>,
+    pub photos_removed: i64,
+    pub text_files_removed: i64,
+}
+
+fn deletion_mode(request: &Option<DeleteCategory>) -> Result<String, String> {
+    let mode = request
+        .as_ref()
+        .and_then(|r| r.mode.clone())
+        .unwrap_or_else(|| "detach".to_string());
+    match mode.as_str() {
+        "detach" | "move" | "delete" => Ok(mode),
+        _ => Err(format!("Unknown deletion mode: {}", mode)),
+    }
+}
+
+// Number of rows of a table whose path is the folder or lies inside it
+async fn count_under_folder(
+    conn: &mut SqliteConnection,
+    table: &str,
+    column: &str,
+    folder: &str,
+) -> Result<i64, String> {
+    let folder = &paths::to_stored(&paths::library_root(&mut *conn).await?, folder);
+    let children = format!("{}{}", folder, std::path::MAIN_SEPARATOR);
+    sqlx::query_scalar::<_, i64>(&format!(
+        "SELECT COUNT(*) FROM {table} WHERE {column} = ? OR substr({column}, 1, length(?)) = ?"
+    ))
+    .bind(folder)
+    .bind(&children)
+    .bind(&children)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+fn folder_size(path: &Path) -> u64 {
+    if path.is_dir() {
+        fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|entry| folder_size(&entry.path())).sum())
+            .unwrap_or(0)
+    } else {
+        fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+    }
+}
+
+// Folder receiving the content of a deleted category in 'move' mode
+async fn deletion_target(
+    conn: &mut SqliteConnection,
+    category_id: i64,
+    request: &DeleteCategory,
+    root_path: &str,
+) -> Result<PathBuf, String> {
+    if let Some(target_category_id) = request.target_category_id {
+        if target_category_id == category_id {
+            return Err("The content can't be moved to the category being deleted".to_string());
+        }
+        return category_folder_path(conn, target_category_id, root_path).await;
+    }
+
+    let target_folder_id = request
+        .target_folder_id
+        .ok_or_else(|| "A target category or folder is required to move the content".to_string())?;
+
+    let folder = sqlx::query_as::<_, Folder>("SELECT * FROM folders WHERE id = ?")
+        .bind(target_folder_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Target folder not found".to_string())?;
+
+    Ok(PathBuf::from(paths::to_absolute(root_path, &folder.path)))
+}
+
+async fn build_deletion_preview(
+    conn: &mut SqliteConnection,
+    id: i64,
+    request: &Option<DeleteCategory>,
+) -> Result<CategoryDeletionPreview, String> {
+    let mode = deletion_mode(request)?;
+
+    let category = sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Category not found".to_string())?;
+
+    let subcategory_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM categories WHERE parent_id = ?")
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let object_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM objects WHERE category_id = ?")
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let attribute_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM attribute_definitions WHERE category_id = ?")
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut preview = CategoryDeletionPreview {
+        category,
+        mode,
+        folder_path: None,
+        folder_exists: false,
+        subcategory_count,
+        object_count,
+        attribute_count,
+        folder_count: 0,
+        photo_count: 0,
+        associated_photo_count: 0,
+        text_file_count: 0,
+        total_size: 0,
+        target_path: None,
+        conflicts: Vec::new(),
+    };
+
+    let root_folder = sqlx::query_scalar::<_, String>("SELECT value FROM settings WHERE key = 'root_folder'")
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let Some(root_path) = root_folder else {
+        return Ok(preview);
+    };
+
+    let folder = category_folder_path(conn, id, &root_path).await?;
+    let folder_str = folder.to_string_lossy().to_string();
+    let stored_folder = paths::to_stored(&root_path, &folder_str);
+    let children = format!("{}{}", stored_folder, std::path::MAIN_SEPARATOR);
+
+    // The category folder itself is not counted
+    preview.folder_count = count_under_folder(conn, "folders", "path", &folder_str).await?
+        - sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM folders WHERE path = ?")
+            .bind(&stored_folder)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    preview.photo_count = count_under_folder(conn, "photos", "file_path", &folder_str).await?;
+    preview.text_file_count = count_under_folder(conn, "text_files", "file_path", &folder_str).await?;
+    preview.associated_photo_count = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(DISTINCT op.photo_id) FROM object_photos op
+         INNER JOIN photos p ON p.id = op.photo_id
+         WHERE substr(p.file_path, 1, length(?)) = ?"
+    )
+    .bind(&children)
+    .bind(&children)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    preview.folder_exists = folder.exists();
+    preview.total_size = folder_size(&folder);
+    preview.folder_path = Some(folder_str);
+
+    if let (Some(request), "move") = (request, preview.mode.as_str()) {
+        let target = deletion_target(conn, id, request, &root_path).await?;
+        if target.starts_with(&folder) {
+            return Err("The content can't be moved inside the folder being deleted".to_string());
+        }
+
+        if let Ok(entries) = fs::read_dir(&folder) {
+            for entry in entries.flatten() {
+                if target.join(entry.file_name()).exists() {
+                    preview.conflicts.push(entry.file_name().to_string_lossy().to_string());
+                }
+            }
+        }
+        preview.conflicts.sort();
+        preview.target_path = Some(target.to_string_lossy().to_string());
+    }
+
+    Ok(preview)
+}
+
+// What deleting a category would affect, for the given mode
+#[tauri::command]
+pub async fn preview_category_deletion(
+    pool: State<'_, SqlitePool>,
+    id: i64,
+    request: Option<DeleteCategory>,
+) -> Result<CategoryDeletionPreview, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    build_deletion_preview(&mut conn, id, &request).await
+}
+
+// Delete a category. Its objects lose their category (or get the target category in 'move' mode),
+// its folder is kept ('detach'), emptied into another folder ('move') or sent to the trash ('delete').
+#[tauri::command]
+pub async fn delete_category(
+    pool: State<'_, SqlitePool>,
+    id: i64,
+    request: Option<DeleteCategory>,
+) -> Result<CategoryDeletionResult, String> {
+    // The preview, the target folder row and the deletion share one transaction
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let preview = build_deletion_preview(&mut tx, id, &request).await?;
+
+    if preview.subcategory_count > 0 {
+        return Err(format!(
+            "Category '{}' still has {} sub-categories",
+            preview.category.name, preview.subcategory_count
+        ));
+    }
+
+    let mut result = CategoryDeletionResult {
+        mode: preview.mode.clone(),
+        moved_entries: 0,
+        trash_entry_id: None,
+        trash_path: None,
+        photos_removed: 0,
+        text_files_removed: 0,
+    };
+
+    let folder = preview.folder_path.as_ref().map(PathBuf::from);
+
+    match (preview.mode.as_str(), folder) {
+        ("move", Some(folder)) => {
+            if !preview.conflicts.is_empty() {
+                return Err(format!(
+                    "The target folder already contains: {}",
+                    preview.conflicts.join(", ")
+                ));
+            }
+            let target = PathBuf::from(preview.target_path.clone().unwrap_or_default());
+            let target_category_id = request.as_ref().and_then(|r| r.target_category_id);
+
+            let root_path = sqlx::query_scalar::<_, String>("SELECT value FROM settings WHERE key = 'root_folder'")
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            // Top-most folder created for the target, removed again if the deletion fails
+            let created_dir = target.ancestors().take_while(|dir| !dir.exists()).last().map(Path::to_path_buf);
+            fs::create_dir_all(&target).map_err(|e| format!("Failed to create target folder: {}", e))?;
+
+            let mut moved: Vec<(PathBuf, PathBuf)> = Vec::new();
+            let outcome = match move_category_content(
+                &mut tx,
+                &preview.category,
+                &folder,
+                &target,
+                &root_path,
+                target_category_id,
+                &mut moved,
+            )
+            .await
+            {
+                Ok(trash_entry_id) => tx.commit().await.map(|_| trash_entry_id).map_err(|e| e.to_string()),
+                Err(e) => Err(e),
+            };
+
+            let trash_entry_id = match outcome {
+                Ok(trash_entry_id) => trash_entry_id,
+                Err(e) => {
+                    for (source, destination) in moved.iter().rev() {
+                        let _ = trash::move_path(destination, source);
+                    }
+                    if let Some(created_dir) = created_dir {
+                        for dir in target.ancestors() {
+                            if fs::remove_dir(dir).is_err() || dir == created_dir {
+                                break;
+                            }
+                        }
+                    }
+                    return Err(e);
+                }
+            };
+
+            // The category folder is empty now
+            let _ = fs::remove_dir(&folder);
+
+            result.moved_entries = moved.len();
+            result.trash_entry_id = Some(trash_entry_id);
+        }
+        ("delete", Some(folder)) => {
+            let folder_str = folder.to_string_lossy().to_string();
+            let mut records = category_records(&mut tx, id).await?;
+            records.extend(trash::snapshot_path(&mut tx, &folder_str).await?);
+
+            // The folder goes to the trash before the rows are removed
+            let trash_path = trash::trash_if_exists(&folder)?;
+
+            let outcome: Result<(i64, i64, i64), String> = async {
+                let (photos_removed, text_files_removed) = trash::delete_path_rows(&mut tx, &folder_str).await?;
+                delete_category_row(&mut tx, id).await?;
+
+                let trash_entry_id = trash::add_trash_entry(
+                    &mut tx,
+                    "category",
+                    &preview.category.name,
+                    Some(&folder),
+                    trash_path.as_deref(),
+                    &records,
+                )
+                .await?;
+                journal::record_operation(
+                    &mut tx,
+                    "delete_category",
+                    &format!("Delete category '{}'", preview.category.name),
+                    vec![JournalStep::Trash { entry_id: trash_entry_id, restored: None }],
+                )
+                .await?;
+                Ok((photos_removed, text_files_removed, trash_entry_id))
+            }
+            .await;
+
+            let outcome = match outcome {
+                Ok(removed) => tx.commit().await.map(|_| removed).map_err(|e| e.to_string()),
+                Err(e) => Err(e),
+            };
+
+            let (photos_removed, text_files_removed, trash_entry_id) = match outcome {
+                Ok(removed) => removed,
+                Err(e) => {
+                    if let Some(trash_path) = &trash_path {
+                        let _ = trash::move_path(trash_path, &folder);
+                    }
+                    return Err(e);
+                }
+            };
+
+            result.photos_removed = photos_removed;
+            result.text_files_removed = text_files_removed;
+            result.trash_entry_id = Some(trash_entry_id);
+            result.trash_path = trash_path.map(|p| p.to_string_lossy().to_string());
+        }
+        _ => {
+            // 'detach' (or no root folder): files and folders stay where they are
+            let records = category_records(&mut tx, id).await?;
+            delete_category_row(&mut tx, id).await?;
+            let trash_entry_id =
+                trash::add_trash_entry(&mut tx, "category", &preview.category.name, None, None, &records).await?;
+            journal::record_operation(
+                &mut tx,
+                "delete_category",
+                &format!("Delete category '{}'", preview.category.name),
+                vec![JournalStep::Trash { entry_id: trash_entry_id, restored: None }],
+            )
+            .await?;
+            tx.commit().await.map_err(|e| e.to_string())?;
+            result.trash_entry_id = Some(trash_entry_id);
+        }
+    }
+
+    Ok(result)
+}
+
+// Rows of a category kept with its trash entry: the category, its mappings, its attributes
+// with their values and the objects it was assigned to
+async fn category_records(conn: &mut SqliteConnection, id: i64) -> Result<Vec<TrashedRecords>, String> {
+    let attribute_ids = sqlx::query_scalar::<_, i64>("SELECT id FROM attribute_definitions WHERE category_id = ?")
+        .bind(id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let object_ids = sqlx::query_scalar::<_, i64>("SELECT id FROM objects WHERE category_id = ?")
+        .bind(id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let object_categories = TrashedRecords {
+        table: "object_categories".to_string(),
+        rows: object_ids
+            .into_iter()
+            .map(|object_id| {
+                let mut row = serde_json::Map::new();
+                row.insert("id".to_string(), object_id.into());
+                row.insert("category_id".to_string(), id.into());
+                row
+            })
+            .collect(),
+    };
+
+    Ok(vec![
+        trash::snapshot_rows(conn, "categories", "id", &[id]).await?,
+        trash::snapshot_rows(conn, "category_platform_mappings", "category_id", &[id]).await?,
+        trash::snapshot_rows(conn, "attribute_definitions", "category_id", &[id]).await?,
+        trash::snapshot_rows(conn, "object_attributes", "attribute_id", &attribute_ids).await?,
+        object_categories,
+    ])
+}
+
+async fn delete_category_row(conn: &mut SqliteConnection, id: i64) -> Result<(), String> {
+    let deleted = sqlx::query("DELETE FROM categories WHERE id = ?")
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if deleted.rows_affected() == 0 {
+        Err("Category not found".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+// Move every entry of the category folder into the target folder and update the stored paths.
+// Runs inside the caller's transaction: the moved entries are listed in `moved` so that they can
+// be put back if the transaction fails. Returns the trash entry of the category.
+async fn move_category_content(
+    conn: &mut SqliteConnection,
+    category: &Category,
+    folder: &Path,
+    target: &Path,
+    root_path: &str,
+    target_category_id: Option<i64>,
+    moved: &mut Vec<(PathBuf, PathBuf)>,
+) -> Result<i64, String> {
+    let id = category.id;
+    let target_folder_id = ensure_folder_row(conn, target, root_path, &mut Vec::new())
+        .await?
+        .ok_or_else(|| format!("Invalid target folder: {}", target.display()))?;
+
+    // The objects keep no trace of the deleted category once they get the target one
+    let records = category_records(conn, id).await?;
+
+    // Rows touched by the move, for the journal
+    let folder_str = folder.to_string_lossy().to_string();
+    let folder_ids = trash::ids_under_path(conn, "folders", "path", &folder_str).await?;
+    let photo_ids = trash::ids_under_path(conn, "photos", "file_path", &folder_str).await?;
+    let text_file_ids = trash::ids_under_path(conn, "text_files", "file_path", &folder_str).await?;
+    let object_ids = sqlx::query_scalar::<_, i64>("SELECT id FROM objects WHERE category_id = ?")
+        .bind(id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    let touched = [
+        ("folders", &folder_ids),
+        ("photos", &photo_ids),
+        ("text_files", &text_file_ids),
+        ("objects", &object_ids),
+    ];
+    let mut before = Vec::new();
+    for (table, ids) in touched {
+        before.push(trash::snapshot_rows(conn, table, "id", ids).await?);
+    }
+
+    let root = paths::library_root(&mut *conn).await?;
+    let folder_id = sqlx::query_scalar::<_, i64>("SELECT id FROM folders WHERE path = ?")
+        .bind(paths::to_stored(&root, &folder_str))
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if folder.exists() {
+        let entries = fs::read_dir(folder).map_err(|e| format!("Failed to read category folder: {}", e))?;
+        for entry in entries.flatten() {
+            let source = entry.path();
+            let destination = target.join(entry.file_name());
+            trash::move_path(&source, &destination)?;
+            moved.push((source.clone(), destination.clone()));
+
+            replace_path_prefix(
+                &mut *conn,
+                &source.to_string_lossy(),
+                &destination.to_string_lossy(),
+            )
+            .await?;
+        }
+    }
+
+    if let Some(folder_id) = folder_id {
+        sqlx::query("UPDATE folders SET parent_id = ? WHERE parent_id = ?")
+            .bind(target_folder_id)
+            .bind(folder_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        sqlx::query("UPDATE photos SET folder_id = ? WHERE folder_id = ?")
+            .bind(target_folder_id)
+            .bind(folder_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        sqlx::query("UPDATE text_files SET folder_id = ? WHERE folder_id = ?")
+            .bind(target_folder_id)
+            .bind(folder_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        sqlx::query("DELETE FROM folders WHERE id = ?")
+            .bind(folder_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    if let Some(target_category_id) = target_category_id {
+        sqlx::query("UPDATE objects SET category_id = ?, updated_at = CURRENT_TIMESTAMP WHERE category_id = ?")
+            .bind(target_category_id)
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    delete_category_row(conn, id).await?;
+    let trash_entry_id = trash::add_trash_entry(conn, "category", &category.name, None, None, &records).await?;
+
+    let mut steps: Vec<JournalStep> = moved
+        .iter()
+        .map(|(from, to)| JournalStep::MovePath {
+            from: from.to_string_lossy().to_string(),
+            to: to.to_string_lossy().to_string(),
+        })
+        .collect();
+    steps.push(JournalStep::RemoveDir { path: folder_str.clone() });
+    for ((table, ids), before) in touched.into_iter().zip(before) {
+        let after = trash::snapshot_rows(conn, table, "id", ids).await?;
+        steps.push(JournalStep::rows("id", ids, before, after));
+    }
+    steps.push(JournalStep::Trash { entry_id: trash_entry_id, restored: None });
+    journal::record_operation(conn, "delete_category", &format!("Delete category '{}'", category.name), steps)
+        .await?;
+
+    Ok(trash_entry_id)
+}
+
+// ========== CATEGORY PLATFORM MAPPINGS ==========
+
+// Marketplace category id of a category on a platform, inherited from the closest mapped ancestor
+pub(crate) async fn mapped_platform_category_id(
+    conn: &mut SqliteConnection,
+    category_id: i64,
+    platform_id: i64,
+) -> Result<Option<String>, String> {
+    for ancestor_id in category_ancestor_ids(conn, category_id).await? {
+        let mapped = sqlx::query_scalar::<_, String>(
+            "SELECT platform_category_id FROM category_platform_mappings WHERE category_id = ? AND platform_id = ?"
+        )
+        .bind(ancestor_id)
+        .bind(platform_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if mapped.is_some() {
+            return Ok(mapped);
+        }
+    }
+
+    Ok(None)
+}
+
+#[tauri::command]
+pub async fn get_category_mappings(
+    pool: State<'_, SqlitePool>,
+    category_id: i64,
+) -> Result<Vec<CategoryPlatformMapping>, String> {
+    sqlx::query_as::<_, CategoryPlatformMapping>(
+        "SELECT * FROM category_platform_mappings WHERE category_id = ? ORDER BY platform_id ASC"
+    )
+    .bind(category_id)
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Map a category to a marketplace category (None removes the mapping)
+#[tauri::command]
+pub async fn set_category_mapping(
+    pool: State<'_, SqlitePool>,
+    category_id: i64,
+    platform_id: i64,
+    platform_category_id: Option<String>,
+    platform_category_name: Option<String>,
+) -> Result<Option<CategoryPlatformMapping>, String> {
+    let platform_category_id = platform_category_id
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty());
+
+    let Some(platform_category_id) = platform_category_id else {
+        sqlx::query("DELETE FROM category_platform_mappings WHERE category_id = ? AND platform_id = ?")
+            .bind(category_id)
+            .bind(platform_id)
+            .execute(pool.inner())
+            .await
+            .map_err(|e| e.to_string())?;
+        return Ok(None);
+    };
+
+    sqlx::query(
+        "INSERT INTO category_platform_mappings (category_id, platform_id, platform_category_id, platform_category_name)
+         VALUES (?, ?, ?, ?)
+         ON CONFLICT(category_id, platform_id) DO UPDATE SET
+            platform_category_id = excluded.platform_category_id,
+            platform_category_name = excluded.platform_category_name,
+            updated_at = CURRENT_TIMESTAMP"
+    )
+    .bind(category_id)
+    .bind(platform_id)
+    .bind(&platform_category_id)
+    .bind(&platform_category_name)
+    .execute(pool.inner())
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query_as::<_, CategoryPlatformMapping>(
+        "SELECT * FROM category_platform_mappings WHERE category_id = ? AND platform_id = ?"
+    )
+    .bind(category_id)
+    .bind(platform_id)
+    .fetch_optional(pool.inner())
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,490 @@
+use serde::Serialize;
+use sqlx::{SqliteConnection, SqlitePool};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use super::fileops::modified_millis;
+use super::paths;
+
+// ========== DESCRIPTION FILES ==========
+// The description.txt of a folder linked to an object and the description of the object are kept
+// in sync: saving one side writes the other. The text file row records the last sync: its time
+// (description_synced_at) and the text of both sides then (synced_description). Since then:
+// - the file changed if its text is another one (edited outside the application)
+// - the object changed if its description is another one (edited without writing the file, e.g.
+//   by a CSV import)
+// When both changed and the texts differ, nothing is written until the conflict is resolved.
+
+pub(crate) const DESCRIPTION_FILE: &str = "description.txt";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DescriptionSyncStatus {
+    pub object_id: i64,
+    pub folder_id: i64,
+    pub file_path: String,
+    // 'in_sync', 'object_changed', 'file_changed' or 'conflict'
+    pub state: String,
+    pub object_description: Option<String>,
+    // None when the file doesn't exist
+    pub file_description: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct DescriptionSyncResult {
+    // Files written from the object description
+    pub files_written: i32,
+    // Object descriptions updated from the file
+    pub objects_updated: i32,
+    pub conflicts: Vec<DescriptionSyncStatus>,
+    // Linked folders that couldn't be synced (e.g. unreadable file), the others are synced
+    pub errors: Vec<String>,
+}
+
+// Linked descriptions to look at
+#[derive(Clone, Copy)]
+pub(crate) enum Linked {
+    All,
+    Object(i64),
+    Folder(i64),
+    TextFile(i64),
+}
+
+// An object and the description.txt of a folder linked to it
+struct LinkedDescription {
+    object_id: i64,
+    object_description: Option<String>,
+    folder_id: i64,
+    file_path: PathBuf,
+    text_file_id: Option<i64>,
+    // None when never synced
+    synced_at: Option<String>,
+    synced_description: Option<String>,
+}
+
+// Same text on both sides (an empty or missing file and no description are the same)
+fn same_text(a: Option<&str>, b: Option<&str>) -> bool {
+    a.unwrap_or("").trim() == b.unwrap_or("").trim()
+}
+
+fn is_empty(text: Option<&str>) -> bool {
+    text.unwrap_or("").trim().is_empty()
+}
+
+impl LinkedDescription {
+    fn read_file(&self) -> Result<Option<String>, String> {
+        match fs::read_to_string(&self.file_path) {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Failed to read {}: {}", self.file_path.display(), e)),
+        }
+    }
+
+    // A missing file didn't change: it is written again from the object
+    fn file_changed(&self, file_description: Option<&str>) -> bool {
+        match (file_description, &self.synced_at) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(text), Some(_)) => !same_text(Some(text), self.synced_description.as_deref()),
+        }
+    }
+
+    fn object_changed(&self) -> bool {
+        self.synced_at.is_none() || !same_text(self.object_description.as_deref(), self.synced_description.as_deref())
+    }
+
+    fn state(&self, file_description: Option<&str>) -> &'static str {
+        let object_description = self.object_description.as_deref();
+        if same_text(object_description, file_description) {
+            return "in_sync";
+        }
+        match (self.object_changed(), self.file_changed(file_description)) {
+            (true, false) => "object_changed",
+            (false, true) => "file_changed",
+            // Both changed since the last sync, even when one was emptied on purpose
+            (true, true) if self.synced_at.is_some() => "conflict",
+            // Never synced (folder linked with its own description) or neither changed (undone
+            // edit): an empty side takes the other one
+            _ if is_empty(file_description) => "object_changed",
+            _ if is_empty(object_description) => "file_changed",
+            _ => "conflict",
+        }
+    }
+
+    fn status(&self, file_description: Option<String>) -> DescriptionSyncStatus {
+        DescriptionSyncStatus {
+            object_id: self.object_id,
+            folder_id: self.folder_id,
+            file_path: self.file_path.to_string_lossy().to_string(),
+            state: self.state(file_description.as_deref()).to_string(),
+            object_description: self.object_description.clone(),
+            file_description,
+        }
+    }
+
+    fn conflict_error(&self) -> String {
+        format!(
+            "{} was changed outside the application since the description was last saved: resolve the conflict first",
+            self.file_path.display()
+        )
+    }
+}
+
+async fn linked_descriptions(
+    conn: &mut SqliteConnection,
+    root: &str,
+    linked: Linked,
+) -> Result<Vec<LinkedDescription>, String> {
+    let (filter, id) = match linked {
+        Linked::All => ("all", 0),
+        Linked::Object(id) => ("object", id),
+        Linked::Folder(id) => ("folder", id),
+        Linked::TextFile(id) => ("text_file", id),
+    };
+
+    let rows = sqlx::query_as::<_, (i64, Option<String>, i64, String, Option<i64>, Option<String>, Option<String>, Option<String>)>(
+        "SELECT o.id, o.description, f.id, f.path, t.id, t.file_path, t.description_synced_at, t.synced_description
+         FROM folders f
+         INNER JOIN objects o ON o.id = f.object_id
+         LEFT JOIN text_files t ON t.id = (
+             SELECT id FROM text_files WHERE folder_id = f.id AND lower(file_name) = ?1 ORDER BY id LIMIT 1
+         )
+         WHERE CASE ?2 WHEN 'object' THEN o.id = ?3 WHEN 'folder' THEN f.id = ?3 WHEN 'text_file' THEN t.id = ?3 ELSE 1 END
+         ORDER BY o.id, f.id"
+    )
+    .bind(DESCRIPTION_FILE)
+    .bind(filter)
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(object_id, object_description, folder_id, folder_path, text_file_id, file_path, synced_at, synced_description)| {
+            let file_path = match file_path {
+                Some(file_path) => PathBuf::from(paths::to_absolute(root, &file_path)),
+                None => Path::new(&paths::to_absolute(root, &folder_path)).join(DESCRIPTION_FILE),
+            };
+            LinkedDescription {
+                object_id,
+                object_description,
+                folder_id,
+                file_path,
+                text_file_id,
+                synced_at,
+                synced_description,
+            }
+        })
+        .collect())
+}
+
+// The text file row records the sync: size and modification time of the file, time of the sync
+// and `description`, the text now on both sides
+async fn mark_synced(
+    conn: &mut SqliteConnection,
+    root: &str,
+    linked: &LinkedDescription,
+    description: Option<&str>,
+) -> Result<(), String> {
+    let Ok(metadata) = fs::metadata(&linked.file_path) else {
+        return Ok(());
+    };
+
+    match linked.text_file_id {
+        Some(id) => {
+            sqlx::query(
+                "UPDATE text_files SET file_size = ?, file_mtime = ?, description_synced_at = CURRENT_TIMESTAMP,
+                 synced_description = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?"
+            )
+            .bind(metadata.len() as i64)
+            .bind(modified_millis(&metadata))
+            .bind(description)
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+        None => {
+            sqlx::query(
+                "INSERT INTO text_files (file_path, file_name, folder_id, file_size, file_mtime, description_synced_at, synced_description)
+                 VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP, ?)"
+            )
+            .bind(paths::to_stored(root, &linked.file_path.to_string_lossy()))
+            .bind(DESCRIPTION_FILE)
+            .bind(linked.folder_id)
+            .bind(metadata.len() as i64)
+            .bind(modified_millis(&metadata))
+            .bind(description)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+// Write the object description to the file (no file is created for an empty description)
+async fn write_file(conn: &mut SqliteConnection, root: &str, linked: &LinkedDescription) -> Result<(), String> {
+    let description = linked.object_description.as_deref().unwrap_or("");
+    if description.trim().is_empty() && !linked.file_path.exists() {
+        return Ok(());
+    }
+
+    fs::write(&linked.file_path, description)
+        .map_err(|e| format!("Failed to write {}: {}", linked.file_path.display(), e))?;
+    mark_synced(conn, root, linked, Some(description)).await
+}
+
+// Copy the file content to the object description
+async fn update_object(
+    conn: &mut SqliteConnection,
+    root: &str,
+    linked: &LinkedDescription,
+    file_description: Option<&str>,
+) -> Result<(), String> {
+    let description = file_description.map(str::trim).filter(|text| !text.is_empty());
+
+    sqlx::query("UPDATE objects SET description = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(description)
+        .bind(linked.object_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    mark_synced(conn, root, linked, description).await
+}
+
+// Bring one linked description in sync, a conflict is left as it is
+async fn sync_one(
+    conn: &mut SqliteConnection,
+    root: &str,
+    linked: LinkedDescription,
+    result: &mut DescriptionSyncResult,
+) -> Result<(), String> {
+    let file_description = linked.read_file()?;
+    match linked.state(file_description.as_deref()) {
+        "object_changed" => {
+            write_file(conn, root, &linked).await?;
+            result.files_written += 1;
+        }
+        "file_changed" => {
+            update_object(conn, root, &linked, file_description.as_deref()).await?;
+            result.objects_updated += 1;
+        }
+        "conflict" => result.conflicts.push(linked.status(file_description)),
+        _ if linked.synced_at.is_none() || linked.file_changed(file_description.as_deref()) => {
+            mark_synced(conn, root, &linked, linked.object_description.as_deref()).await?
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+// Bring the linked descriptions in sync, leaving the conflicts. A folder that fails is recorded
+// in the errors and the next one is synced
+pub(crate) async fn sync_linked(
+    conn: &mut SqliteConnection,
+    root: &str,
+    linked: Linked,
+) -> Result<DescriptionSyncResult, String> {
+    let mut result = DescriptionSyncResult::default();
+
+    for linked in linked_descriptions(conn, root, linked).await? {
+        if let Err(e) = sync_one(conn, root, linked, &mut result).await {
+            result.errors.push(e);
+        }
+    }
+
+    Ok(result)
+}
+
+// Bring the description.txt of one folder in sync, failing when it can't be synced
+pub(crate) async fn sync_folder(conn: &mut SqliteConnection, root: &str, folder_id: i64) -> Result<(), String> {
+    match sync_linked(conn, root, Linked::Folder(folder_id)).await?.errors.pop() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+// After an object was saved (`previous` is its description before): write its description to
+// the files of its folders. A file changed outside the application is taken instead when the
+// description was not edited, and is a conflict otherwise.
+pub(crate) async fn object_saved(
+    conn: &mut SqliteConnection,
+    root: &str,
+    object_id: i64,
+    previous: Option<&str>,
+) -> Result<(), String> {
+    for linked in linked_descriptions(conn, root, Linked::Object(object_id)).await? {
+        let file_description = linked.read_file()?;
+        let description = linked.object_description.as_deref();
+
+        if same_text(description, file_description.as_deref()) {
+            mark_synced(conn, root, &linked, description).await?;
+        } else if linked.file_changed(file_description.as_deref()) && !same_text(previous, file_description.as_deref()) {
+            if !same_text(description, previous) {
+                return Err(linked.conflict_error());
+            }
+            update_object(conn, root, &linked, file_description.as_deref()).await?;
+        } else {
+            write_file(conn, root, &linked).await?;
+        }
+    }
+
+    Ok(())
+}
+
+// Before a description.txt is saved in the application: refuse to overwrite an object
+// description changed since the last sync
+pub(crate) async fn check_file_save(
+    conn: &mut SqliteConnection,
+    root: &str,
+    text_file_id: i64,
+    content: &str,
+) -> Result<(), String> {
+    for linked in linked_descriptions(conn, root, Linked::TextFile(text_file_id)).await? {
+        let previous = linked.read_file()?;
+        let description = linked.object_description.as_deref();
+        if linked.object_changed()
+            && !same_text(description, previous.as_deref())
+            && !same_text(description, Some(content))
+        {
+            return Err(format!(
+                "The description of object #{} was changed since {} was last saved: resolve the conflict first",
+                linked.object_id,
+                linked.file_path.display()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// After a description.txt was saved in the application: copy it to the linked objects
+pub(crate) async fn file_saved(conn: &mut SqliteConnection, root: &str, text_file_id: i64) -> Result<(), String> {
+    for linked in linked_descriptions(conn, root, Linked::TextFile(text_file_id)).await? {
+        let file_description = linked.read_file()?;
+        update_object(conn, root, &linked, file_description.as_deref()).await?;
+    }
+
+    Ok(())
+}
+
+// ========== DESCRIPTION FILE COMMANDS ==========
+
+// State of the description files of the folders linked to an object (all linked folders by default)
+#[tauri::command]
+pub async fn get_description_sync_status(
+    pool: State<'_, SqlitePool>,
+    object_id: Option<i64>,
+) -> Result<Vec<DescriptionSyncStatus>, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    let root = paths::library_root(&mut *conn).await?;
+
+    linked_descriptions(&mut conn, &root, object_id.map_or(Linked::All, Linked::Object))
+        .await?
+        .iter()
+        .map(|linked| Ok(linked.status(linked.read_file()?)))
+        .collect()
+}
+
+// Write the changes of either side to the other one, conflicts are returned unchanged
+#[tauri::command]
+pub async fn sync_descriptions(
+    pool: State<'_, SqlitePool>,
+    object_id: Option<i64>,
+) -> Result<DescriptionSyncResult, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let root = paths::library_root(&mut *tx).await?;
+
+    let result = sync_linked(&mut tx, &root, object_id.map_or(Linked::All, Linked::Object)).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(result)
+}
+
+// Resolve a conflict by keeping the description of the object ('object') or of the file ('file')
+#[tauri::command]
+pub async fn resolve_description_conflict(
+    pool: State<'_, SqlitePool>,
+    object_id: i64,
+    folder_id: i64,
+    keep: String,
+) -> Result<DescriptionSyncStatus, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let root = paths::library_root(&mut *tx).await?;
+
+    let linked = linked_descriptions(&mut tx, &root, Linked::Folder(folder_id))
+        .await?
+        .into_iter()
+        .find(|linked| linked.object_id == object_id)
+        .ok_or_else(|| "This folder is not linked to the object".to_string())?;
+
+    match keep.as_str() {
+        "object" => write_file(&mut tx, &root, &linked).await?,
+        "file" => update_object(&mut tx, &root, &linked, linked.read_file()?.as_deref()).await?,
+        _ => return Err(format!("Invalid choice: {} (expected 'object' or 'file')", keep)),
+    }
+
+    let linked = linked_descriptions(&mut tx, &root, Linked::Folder(folder_id))
+        .await?
+        .into_iter()
+        .find(|linked| linked.object_id == object_id)
+        .ok_or_else(|| "This folder is not linked to the object".to_string())?;
+    let status = linked.status(linked.read_file()?);
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linked(object_description: Option<&str>, synced_description: Option<Option<&str>>) -> LinkedDescription {
+        LinkedDescription {
+            object_id: 1,
+            object_description: object_description.map(str::to_string),
+            folder_id: 1,
+            file_path: PathBuf::from("/lib/item").join(DESCRIPTION_FILE),
+            text_file_id: Some(1),
+            synced_at: synced_description.map(|_| "2024-01-01 00:00:00".to_string()),
+            synced_description: synced_description.flatten().map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn same_texts_are_in_sync() {
+        assert_eq!(linked(Some("Lamp"), None).state(Some("Lamp\n")), "in_sync");
+        assert_eq!(linked(Some("Lamp"), Some(Some("Old"))).state(Some(" Lamp ")), "in_sync");
+        assert_eq!(linked(None, Some(Some("Old"))).state(None), "in_sync");
+        assert_eq!(linked(Some(" "), None).state(Some("")), "in_sync");
+    }
+
+    #[test]
+    fn changes_since_the_last_sync() {
+        let synced = || Some(Some("Lamp"));
+        assert_eq!(linked(Some("Brass lamp"), synced()).state(Some("Lamp")), "object_changed");
+        assert_eq!(linked(Some("Lamp"), synced()).state(Some("Brass lamp")), "file_changed");
+        assert_eq!(linked(Some("Brass lamp"), synced()).state(Some("Old lamp")), "conflict");
+        // A missing file is written again
+        assert_eq!(linked(Some("Lamp"), synced()).state(None), "object_changed");
+        // Both changed, one of them emptied: the emptying may be on purpose
+        assert_eq!(linked(None, synced()).state(Some("Brass lamp")), "conflict");
+        assert_eq!(linked(Some("Brass lamp"), synced()).state(Some("")), "conflict");
+        // Synced with no description
+        assert_eq!(linked(Some("Lamp"), Some(None)).state(Some("")), "object_changed");
+        assert_eq!(linked(None, Some(None)).state(Some("Lamp")), "file_changed");
+    }
+
+    #[test]
+    fn never_synced() {
+        assert_eq!(linked(Some("Lamp"), None).state(Some("Brass lamp")), "conflict");
+        assert_eq!(linked(Some("Lamp"), None).state(None), "object_changed");
+        assert_eq!(linked(Some("Lamp"), None).state(Some(" ")), "object_changed");
+        assert_eq!(linked(None, None).state(Some("Lamp")), "file_changed");
+    }
+}
@@ -0,0 +1,932 @@
+use crate::models::{Object, Photo, Platform, ObjectPlatform, CsvColumn};
+use super::{attributes, listings, paths, templates, EXPORTS_FOLDER};
+use super::paths::LibraryPaths;
+use sqlx::SqlitePool;
+use tauri::State;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::fs;
+use chrono::Local;
+
+// ========== LISTING PACKAGE EXPORT ==========
+
+// Replace characters that are not allowed in file names on macOS/Windows
+pub(crate) fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+    sanitized.trim().trim_matches('.').to_string()
+}
+
+// Photos of an object, in display order
+pub(crate) async fn fetch_object_photos(pool: &SqlitePool, object_id: i64) -> Result<Vec<Photo>, String> {
+    let root = paths::library_root(pool).await?;
+
+    sqlx::query_as::<_, Photo>(
+        "SELECT p.* FROM photos p
+         INNER JOIN object_photos op ON p.id = op.photo_id
+         WHERE op.object_id = ?
+         ORDER BY op.display_order, p.created_at"
+    )
+    .bind(object_id)
+    .fetch_all(pool)
+    .await
+    .map(|photos| photos.resolve_paths(&root))
+    .map_err(|e| e.to_string())
+}
+
+// Copy a photo into the package, downscaled to the platform's maximum dimension and re-encoded as JPEG.
+// Files that can't be decoded (HEIC, RAW...) are copied unchanged.
+fn export_photo(source: &Path, dest_dir: &Path, index: usize, max_dimension: Option<i64>) -> Result<(PathBuf, bool), String> {
+    match image::open(source) {
+        Ok(img) => {
+            let img = match max_dimension {
+                Some(max) if max > 0 && (img.width() as i64 > max || img.height() as i64 > max) => {
+                    img.resize(max as u32, max as u32, image::imageops::FilterType::Lanczos3)
+                }
+                _ => img,
+            };
+
+            let mut buffer = Vec::new();
+            let mut cursor = std::io::Cursor::new(&mut buffer);
+            img.to_rgb8()
+                .write_to(&mut cursor, image::ImageOutputFormat::Jpeg(90))
+                .map_err(|e| format!("Failed to encode {}: {}", source.display(), e))?;
+
+            let dest = dest_dir.join(format!("{:02}.jpg", index + 1));
+            fs::write(&dest, &buffer)
+                .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+            Ok((dest, true))
+        }
+        Err(_) => {
+            let extension = source.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+            let dest = if extension.is_empty() {
+                dest_dir.join(format!("{:02}", index + 1))
+            } else {
+                dest_dir.join(format!("{:02}.{}", index + 1, extension))
+            };
+            fs::copy(source, &dest)
+                .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+            Ok((dest, false))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ExportListingPackageRequest {
+    pub object_id: i64,
+    pub platform_id: i64,
+    // Parent folder of the package; defaults to <root_folder>/EXPORTS/<platform>
+    pub destination_path: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ExportListingPackageResult {
+    pub folder_path: String,
+    pub object_platform_id: i64,
+    pub files: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+// Listing exported for an object that has none on the platform yet
+fn draft_listing(object_id: i64, platform_id: i64) -> ObjectPlatform {
+    ObjectPlatform {
+        id: 0,
+        object_id,
+        platform_id,
+        platform_url: None,
+        status: "draft".to_string(),
+        listed_at: None,
+        title: None,
+        description: None,
+        price: None,
+        currency: Some("EUR".to_string()),
+        condition: None,
+        shipping_profile: None,
+        platform_category_id: None,
+        item_specifics: None,
+        created_at: String::new(),
+        updated_at: String::new(),
+    }
+}
+
+// Create the draft listing if needed, in a short transaction; returns the id of the listing
+async fn record_draft_listing(pool: &SqlitePool, object_id: i64, platform_id: i64) -> Result<i64, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("INSERT OR IGNORE INTO object_platforms (object_id, platform_id, status) VALUES (?, ?, 'draft')")
+        .bind(object_id)
+        .bind(platform_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let id = sqlx::query_scalar::<_, i64>("SELECT id FROM object_platforms WHERE object_id = ? AND platform_id = ?")
+        .bind(object_id)
+        .bind(platform_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn export_listing_package(
+    pool: State<'_, SqlitePool>,
+    request: ExportListingPackageRequest,
+) -> Result<ExportListingPackageResult, String> {
+    let object = sqlx::query_as::<_, Object>("SELECT * FROM objects WHERE id = ?")
+        .bind(request.object_id)
+        .fetch_optional(pool.inner())
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Object not found".to_string())?;
+
+    let platform = sqlx::query_as::<_, Platform>("SELECT * FROM platforms WHERE id = ?")
+        .bind(request.platform_id)
+        .fetch_optional(pool.inner())
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Platform not found".to_string())?;
+
+    // Without a listing on this platform, an empty draft is exported (and recorded at the end)
+    let listing = sqlx::query_as::<_, ObjectPlatform>(
+        "SELECT * FROM object_platforms WHERE object_id = ? AND platform_id = ?"
+    )
+    .bind(object.id)
+    .bind(platform.id)
+    .fetch_optional(pool.inner())
+    .await
+    .map_err(|e| e.to_string())?
+    .unwrap_or_else(|| draft_listing(object.id, platform.id));
+
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    let listing = listings::with_mapped_category(&mut conn, &listing, &object).await?;
+
+    let photos = fetch_object_photos(pool.inner(), object.id).await?;
+
+    let mut warnings = Vec::new();
+
+    let validation = listings::check_listing(&listing, &object, &platform, photos.len() as i64);
+    warnings.extend(validation.errors.iter().cloned());
+    warnings.extend(validation.warnings.iter().cloned());
+
+    // Listing overrides win, then the platform's default template, then the raw object fields
+    let rendered = templates::render_for_object(&mut conn, object.id, Some(platform.id), None).await.ok();
+    drop(conn);
+
+    let title = match (&listing.title, rendered.as_ref().and_then(|r| r.title.clone())) {
+        (Some(title), _) if !title.trim().is_empty() => title.clone(),
+        (_, Some(title)) if !title.is_empty() => title,
+        _ => listings::effective_title(&listing, &object),
+    };
+    let description = match (&listing.description, rendered.as_ref().map(|r| r.body.clone())) {
+        (Some(description), _) if !description.trim().is_empty() => description.clone(),
+        (_, Some(body)) if !body.is_empty() => body,
+        _ => listings::effective_description(&listing, &object),
+    };
+
+    if let Some(error) = listings::check_title_length(&title, &platform) {
+        if !warnings.contains(&error) {
+            warnings.push(error);
+        }
+    }
+
+    // Determine the package folder
+    let parent = match &request.destination_path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let root_folder = sqlx::query_scalar::<_, String>(
+                "SELECT value FROM settings WHERE key = 'root_folder'"
+            )
+                .fetch_optional(pool.inner())
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| "Root folder is not configured".to_string())?;
+
+            Path::new(&root_folder)
+                .join(EXPORTS_FOLDER)
+                .join(sanitize_file_name(&platform.name))
+        }
+    };
+
+    let folder_name = format!("{} (#{})", sanitize_file_name(&object.name), object.id);
+    let mut package_dir = parent.join(&folder_name);
+
+    // Handle duplicates
+    let mut copy_number = 1;
+    while package_dir.exists() {
+        package_dir = parent.join(format!("{} ({})", folder_name, copy_number));
+        copy_number += 1;
+    }
+
+    fs::create_dir_all(&package_dir)
+        .map_err(|e| format!("Failed to create export folder: {}", e))?;
+
+    let mut write_package = || -> Result<Vec<String>, String> {
+        let mut files = Vec::new();
+
+        let title_file = package_dir.join("title.txt");
+        fs::write(&title_file, &title)
+            .map_err(|e| format!("Failed to write title file: {}", e))?;
+        files.push(title_file.to_string_lossy().to_string());
+
+        let description_file = package_dir.join("description.txt");
+        fs::write(&description_file, &description)
+            .map_err(|e| format!("Failed to write description file: {}", e))?;
+        files.push(description_file.to_string_lossy().to_string());
+
+        // Export photos in display order, up to the platform limit
+        let max_photos = platform.max_photos.map(|m| m.max(0) as usize).unwrap_or(photos.len());
+        let mut exported_photos = Vec::new();
+
+        for (index, photo) in photos.iter().take(max_photos).enumerate() {
+            let source = Path::new(&photo.file_path);
+            if !source.exists() {
+                warnings.push(format!("Photo file not found: {}", photo.file_path));
+                continue;
+            }
+
+            match export_photo(source, &package_dir, exported_photos.len(), platform.max_photo_dimension) {
+                Ok((dest, converted)) => {
+                    if !converted {
+                        warnings.push(format!("{} could not be converted and was copied as is", photo.file_name));
+                    }
+                    let dest_name = dest.file_name().unwrap_or_default().to_string_lossy().to_string();
+                    exported_photos.push(format!("{} <- {} (photo {})", dest_name, photo.file_name, index + 1));
+                    files.push(dest.to_string_lossy().to_string());
+                }
+                Err(e) => warnings.push(e),
+            }
+        }
+
+        // Checklist to follow when posting the listing by hand
+        let mut checklist = Vec::new();
+        checklist.push(format!("LISTING CHECKLIST - {}", platform.name));
+        checklist.push(format!("Object: {} (#{})", object.name, object.id));
+        if let Some(base_url) = &platform.base_url {
+            checklist.push(format!("Site: {}", base_url));
+        }
+        checklist.push(String::new());
+
+        let title_length = title.chars().count();
+        match platform.title_max_length {
+            Some(max) => checklist.push(format!("[ ] Title ({}/{} characters): copy title.txt", title_length, max)),
+            None => checklist.push(format!("[ ] Title ({} characters): copy title.txt", title_length)),
+        }
+        checklist.push("[ ] Description: copy description.txt".to_string());
+        match listing.price {
+            Some(price) => checklist.push(format!(
+                "[ ] Price: {:.2} {}",
+                price,
+                listing.currency.clone().unwrap_or_default()
+            )),
+            None => checklist.push("[ ] Price: not set".to_string()),
+        }
+        if let Some(category_id) = &listing.platform_category_id {
+            checklist.push(format!("[ ] Category: {}", category_id));
+        }
+        if let Some(condition) = &listing.condition {
+            checklist.push(format!("[ ] Condition: {}", condition));
+        }
+        if let Some(shipping_profile) = &listing.shipping_profile {
+            checklist.push(format!("[ ] Shipping: {}", shipping_profile));
+        }
+        if let Some(Ok(serde_json::Value::Object(specifics))) = listing
+            .item_specifics
+            .as_ref()
+            .map(|s| serde_json::from_str::<serde_json::Value>(s))
+        {
+            for (name, value) in specifics {
+                let value = match value {
+                    serde_json::Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                checklist.push(format!("[ ] {}: {}", name, value));
+            }
+        }
+        checklist.push(format!("[ ] Photos ({}), in this order:", exported_photos.len()));
+        for photo in &exported_photos {
+            checklist.push(format!("      {}", photo));
+        }
+        if photos.len() > max_photos {
+            checklist.push(format!(
+                "    {} more photo(s) not exported ({} allows {})",
+                photos.len() - max_photos,
+                platform.name,
+                max_photos
+            ));
+        }
+        checklist.push("[ ] Once published, record the listing URL in Seller Library".to_string());
+
+        if !warnings.is_empty() {
+            checklist.push(String::new());
+            checklist.push("WARNINGS".to_string());
+            for warning in &warnings {
+                checklist.push(format!("- {}", warning));
+            }
+        }
+
+        let checklist_file = package_dir.join("checklist.txt");
+        fs::write(&checklist_file, checklist.join("\n") + "\n")
+            .map_err(|e| format!("Failed to write checklist file: {}", e))?;
+        files.push(checklist_file.to_string_lossy().to_string());
+
+        Ok(files)
+    };
+
+    // A failed package is removed
+    let files = match write_package() {
+        Ok(files) => files,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&package_dir);
+            return Err(e);
+        }
+    };
+
+    // Make sure the object has a listing on this platform so the URL can be recorded once posted
+    let object_platform_id = match record_draft_listing(pool.inner(), object.id, platform.id).await {
+        Ok(id) => id,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&package_dir);
+            return Err(e);
+        }
+    };
+
+    Ok(ExportListingPackageResult {
+        folder_path: package_dir.to_string_lossy().to_string(),
+        object_platform_id,
+        files,
+        warnings,
+    })
+}
+
+// ========== CSV BULK EXPORT ==========
+
+// Fields that can be used as the source of a CSV column.
+// "specific:<Name>" can also be used to export a single item specific of the listing,
+// and "attribute:<Name>" a custom attribute of the object.
+pub(crate) const CSV_SOURCES: &[&str] = &[
+    "id",
+    "sku",
+    "handle",
+    "name",
+    "title",
+    "description",
+    "year",
+    "weight",
+    "quantity",
+    "brand",
+    "condition",
+    "ebay_condition_id",
+    "length",
+    "width",
+    "height",
+    "dimension_unit",
+    "dimensions",
+    "category",
+    "price",
+    "currency",
+    "shipping_profile",
+    "platform_category_id",
+    "status",
+    "platform_url",
+    "item_specifics",
+    "photo_count",
+    "main_photo_path",
+    "main_photo_url",
+    "photo_paths",
+    "photo_urls",
+    "created_at",
+    "updated_at",
+];
+
+fn source_column(header: &str, source: &str) -> CsvColumn {
+    CsvColumn {
+        header: header.to_string(),
+        source: Some(source.to_string()),
+        value: None,
+    }
+}
+
+fn constant_column(header: &str, value: &str) -> CsvColumn {
+    CsvColumn {
+        header: header.to_string(),
+        source: None,
+        value: Some(value.to_string()),
+    }
+}
+
+// Built-in column layouts for each export format
+pub(crate) fn default_csv_columns(format: &str) -> Result<Vec<CsvColumn>, String> {
+    match format {
+        // eBay File Exchange, on eBay France: prices are read in EUR
+        "ebay" => Ok(vec![
+            constant_column("*Action(SiteID=France|Country=FR|Currency=EUR|Version=1193|CC=UTF-8)", "Add"),
+            source_column("CustomLabel", "sku"),
+            source_column("*Category", "platform_category_id"),
+            source_column("*Title", "title"),
+            source_column("*Description", "description"),
+            source_column("*ConditionID", "ebay_condition_id"),
+            source_column("PicURL", "photo_urls"),
+            source_column("*Quantity", "quantity"),
+            constant_column("*Format", "FixedPrice"),
+            source_column("*StartPrice", "price"),
+            constant_column("*Duration", "GTC"),
+            source_column("ShippingProfileName", "shipping_profile"),
+        ]),
+        // Generic Shopify-like product import
+        "shopify" => Ok(vec![
+            source_column("Handle", "handle"),
+            source_column("Title", "title"),
+            source_column("Body (HTML)", "description"),
+            source_column("Type", "category"),
+            source_column("Tags", "category"),
+            constant_column("Published", "TRUE"),
+            source_column("Variant SKU", "sku"),
+            source_column("Variant Inventory Qty", "quantity"),
+            source_column("Variant Price", "price"),
+            source_column("Image Src", "main_photo_url"),
+            constant_column("Status", "active"),
+        ]),
+        // Everything we know about the objects
+        "full" => Ok(CSV_SOURCES.iter().map(|source| source_column(source, source)).collect()),
+        _ => Err(format!("Unknown CSV format: {}", format)),
+    }
+}
+
+// Currency given in the *Action header of an eBay File Exchange layout (Currency=EUR)
+fn header_currency(columns: &[CsvColumn]) -> Option<&str> {
+    columns
+        .iter()
+        .find_map(|column| column.header.split(['(', '|', ')']).find_map(|part| part.strip_prefix("Currency=")))
+}
+
+fn validate_csv_columns(columns: &[CsvColumn]) -> Result<(), String> {
+    if columns.is_empty() {
+        return Err("At least one column is required".to_string());
+    }
+
+    for column in columns {
+        if column.header.trim().is_empty() {
+            return Err("Column headers can't be empty".to_string());
+        }
+        match (&column.source, &column.value) {
+            (Some(source), None) => {
+                let known = CSV_SOURCES.contains(&source.as_str())
+                    || source.strip_prefix("specific:").is_some_and(|name| !name.trim().is_empty())
+                    || source.strip_prefix("attribute:").is_some_and(|name| !name.trim().is_empty());
+                if !known {
+                    return Err(format!("Unknown source '{}' for column '{}'", source, column.header));
+                }
+            }
+            (None, Some(_)) => {}
+            _ => {
+                return Err(format!(
+                    "Column '{}' must have either a source or a constant value",
+                    column.header
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Columns to export for a platform: its saved mapping, or the built-in layout of the format
+async fn resolve_csv_columns(
+    pool: &SqlitePool,
+    platform_id: Option<i64>,
+    format: &str,
+) -> Result<Vec<CsvColumn>, String> {
+    if let Some(platform_id) = platform_id {
+        let mapping = sqlx::query_scalar::<_, Option<String>>("SELECT csv_mapping FROM platforms WHERE id = ?")
+            .bind(platform_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Platform not found".to_string())?;
+
+        if let Some(mapping) = mapping {
+            return serde_json::from_str::<Vec<CsvColumn>>(&mapping)
+                .map_err(|e| format!("Invalid CSV mapping for platform {}: {}", platform_id, e));
+        }
+    }
+
+    default_csv_columns(format)
+}
+
+// eBay condition IDs of the condition grades. Categories that only take "Used" (3000) need the
+// ID set as the condition of the listing: a numeric condition is exported as is
+const EBAY_CONDITION_IDS: &[(&str, &str)] = &[
+    ("new", "1000"),
+    ("like_new", "2750"),
+    ("very_good", "4000"),
+    ("good", "5000"),
+    ("acceptable", "6000"),
+    ("for_parts", "7000"),
+];
+
+pub(crate) fn ebay_condition_id(condition: &str) -> Option<String> {
+    let condition = condition.trim();
+    if !condition.is_empty() && condition.chars().all(|c| c.is_ascii_digit()) {
+        return Some(condition.to_string());
+    }
+
+    EBAY_CONDITION_IDS
+        .iter()
+        .find(|(grade, _)| *grade == condition)
+        .map(|(_, id)| id.to_string())
+}
+
+// URL of a photo hosted under `base_url` with the same layout as the library. Marketplaces can't
+// fetch local files: photos outside the root folder have no URL
+pub(crate) fn photo_url(base_url: &str, root: &str, path: &str) -> Option<String> {
+    let relative = paths::to_stored(root, path);
+    if root.is_empty() || Path::new(&relative).is_absolute() {
+        return None;
+    }
+
+    let mut url = base_url.trim().trim_end_matches('/').to_string();
+    for component in Path::new(&relative).components() {
+        url.push('/');
+        for byte in component.as_os_str().to_string_lossy().bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => url.push(byte as char),
+                _ => url.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+    }
+    Some(url)
+}
+
+// URL-friendly identifier built from the object name
+fn handle_for(object: &Object) -> String {
+    let mut handle = String::new();
+    for c in object.name.to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            handle.push(c);
+        } else if !handle.ends_with('-') && !handle.is_empty() {
+            handle.push('-');
+        }
+    }
+    format!("{}-{}", handle.trim_end_matches('-'), object.id)
+}
+
+// Values of every CSV source for one object (and its listing on the platform, if any), with the
+// photos left out of the URL columns because they have no URL
+async fn build_csv_values(
+    pool: &SqlitePool,
+    root: &str,
+    photo_base_url: Option<&str>,
+    object: &Object,
+    platform_id: Option<i64>,
+) -> Result<(HashMap<String, String>, Vec<String>), String> {
+    let category = match object.category_id {
+        Some(category_id) => sqlx::query_scalar::<_, String>("SELECT name FROM categories WHERE id = ?")
+            .bind(category_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?,
+        None => None,
+    };
+
+    let listing = match platform_id {
+        Some(platform_id) => sqlx::query_as::<_, ObjectPlatform>(
+            "SELECT * FROM object_platforms WHERE object_id = ? AND platform_id = ?"
+        )
+        .bind(object.id)
+        .bind(platform_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?,
+        None => None,
+    };
+
+    let photos = fetch_object_photos(pool, object.id).await?;
+    let photo_paths: Vec<String> = photos.iter().map(|p| p.file_path.clone()).collect();
+    let mut photo_urls = Vec::new();
+    let mut unhosted_photos = Vec::new();
+    for path in &photo_paths {
+        match photo_base_url.and_then(|base_url| photo_url(base_url, root, path)) {
+            Some(url) => photo_urls.push(url),
+            None => unhosted_photos.push(path.clone()),
+        }
+    }
+
+    let mut values = HashMap::new();
+    values.insert("id".to_string(), object.id.to_string());
+    values.insert("sku".to_string(), object.sku.clone().unwrap_or_default());
+    values.insert("handle".to_string(), handle_for(object));
+    values.insert("name".to_string(), object.name.clone());
+    values.insert("description".to_string(), object.description.clone().unwrap_or_default());
+    values.insert("year".to_string(), object.year.map(|y| y.to_string()).unwrap_or_default());
+    values.insert("weight".to_string(), object.weight.map(|w| w.to_string()).unwrap_or_default());
+    values.insert("quantity".to_string(), object.quantity.to_string());
+    values.insert("brand".to_string(), object.brand.clone().unwrap_or_default());
+    values.insert("condition".to_string(), object.condition.clone().unwrap_or_default());
+    values.insert("length".to_string(), object.length.map(|l| l.to_string()).unwrap_or_default());
+    values.insert("width".to_string(), object.width.map(|w| w.to_string()).unwrap_or_default());
+    values.insert("height".to_string(), object.height.map(|h| h.to_string()).unwrap_or_default());
+    values.insert("dimension_unit".to_string(), object.dimension_unit.clone().unwrap_or_default());
+    values.insert("dimensions".to_string(), attributes::format_dimensions(object));
+    values.insert("category".to_string(), category.unwrap_or_default());
+    values.insert("photo_count".to_string(), photos.len().to_string());
+    values.insert("main_photo_path".to_string(), photo_paths.first().cloned().unwrap_or_default());
+    values.insert("main_photo_url".to_string(), photo_urls.first().cloned().unwrap_or_default());
+    values.insert("photo_paths".to_string(), photo_paths.join("|"));
+    values.insert("photo_urls".to_string(), photo_urls.join("|"));
+    values.insert("created_at".to_string(), object.created_at.clone());
+    values.insert("updated_at".to_string(), object.updated_at.clone());
+
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    for attribute in attributes::fetch_object_attributes(&mut conn, object.id).await? {
+        values.insert(format!("attribute:{}", attribute.name), attribute.value);
+    }
+
+    let listing = match listing {
+        Some(listing) => Some(listings::with_mapped_category(&mut conn, &listing, object).await?),
+        None => None,
+    };
+
+    match &listing {
+        Some(listing) => {
+            values.insert("title".to_string(), listings::effective_title(listing, object));
+            values.insert("description".to_string(), listings::effective_description(listing, object));
+            values.insert("price".to_string(), listing.price.map(|p| format!("{:.2}", p)).unwrap_or_default());
+            values.insert("currency".to_string(), listing.currency.clone().unwrap_or_default());
+            if let Some(condition) = listing.condition.as_ref().filter(|c| !c.trim().is_empty()) {
+                values.insert("condition".to_string(), condition.clone());
+            }
+            values.insert("shipping_profile".to_string(), listing.shipping_profile.clone().unwrap_or_default());
+            values.insert("platform_category_id".to_string(), listing.platform_category_id.clone().unwrap_or_default());
+            values.insert("status".to_string(), listing.status.clone());
+            values.insert("platform_url".to_string(), listing.platform_url.clone().unwrap_or_default());
+            values.insert("item_specifics".to_string(), listing.item_specifics.clone().unwrap_or_default());
+
+            if let Some(Ok(serde_json::Value::Object(specifics))) = listing
+                .item_specifics
+                .as_ref()
+                .map(|s| serde_json::from_str::<serde_json::Value>(s))
+            {
+                for (name, value) in specifics {
+                    let value = match value {
+                        serde_json::Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    values.insert(format!("specific:{}", name), value);
+                }
+            }
+        }
+        None => {
+            values.insert("title".to_string(), object.name.clone());
+        }
+    }
+
+    let condition_id = values.get("condition").and_then(|c| ebay_condition_id(c)).unwrap_or_default();
+    values.insert("ebay_condition_id".to_string(), condition_id);
+
+    Ok((values, unhosted_photos))
+}
+
+#[derive(Deserialize)]
+pub struct ExportObjectsCsvRequest {
+    // Objects to export; all objects when empty
+    pub object_ids: Vec<i64>,
+    // "ebay", "shopify" or "full"; ignored when the platform has a saved mapping
+    pub format: String,
+    pub platform_id: Option<i64>,
+    // CSV file to write; defaults to <root_folder>/EXPORTS/<format> <date>.csv
+    pub destination_path: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ExportObjectsCsvResult {
+    pub file_path: String,
+    pub rows: i32,
+    pub warnings: Vec<String>,
+}
+
+// Photo URLs are built from the photo_base_url setting, the URL where the root folder is hosted
+#[tauri::command]
+pub async fn export_objects_csv(
+    pool: State<'_, SqlitePool>,
+    request: ExportObjectsCsvRequest,
+) -> Result<ExportObjectsCsvResult, String> {
+    let columns = resolve_csv_columns(pool.inner(), request.platform_id, &request.format).await?;
+    validate_csv_columns(&columns)?;
+
+    let objects = if request.object_ids.is_empty() {
+        sqlx::query_as::<_, Object>("SELECT * FROM objects ORDER BY id")
+            .fetch_all(pool.inner())
+            .await
+            .map_err(|e| e.to_string())?
+    } else {
+        let mut objects = Vec::new();
+        for object_id in &request.object_ids {
+            let object = sqlx::query_as::<_, Object>("SELECT * FROM objects WHERE id = ?")
+                .bind(object_id)
+                .fetch_optional(pool.inner())
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Object {} not found", object_id))?;
+            objects.push(object);
+        }
+        objects
+    };
+
+    let dest_file = match &request.destination_path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let root_folder = sqlx::query_scalar::<_, String>(
+                "SELECT value FROM settings WHERE key = 'root_folder'"
+            )
+                .fetch_optional(pool.inner())
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| "Root folder is not configured".to_string())?;
+
+            let file_name = format!(
+                "{} {}.csv",
+                sanitize_file_name(&request.format),
+                Local::now().format("%y-%m-%d %H-%M")
+            );
+            Path::new(&root_folder).join(EXPORTS_FOLDER).join(file_name)
+        }
+    };
+
+    if let Some(parent) = dest_file.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create export folder: {}", e))?;
+    }
+
+    let mut writer = csv::Writer::from_path(&dest_file)
+        .map_err(|e| format!("Failed to create CSV file: {}", e))?;
+
+    writer
+        .write_record(columns.iter().map(|c| c.header.as_str()))
+        .map_err(|e| e.to_string())?;
+
+    let root = paths::library_root(pool.inner()).await?;
+    let photo_base_url = sqlx::query_scalar::<_, String>("SELECT value FROM settings WHERE key = 'photo_base_url'")
+        .fetch_optional(pool.inner())
+        .await
+        .map_err(|e| e.to_string())?
+        .filter(|url| !url.trim().is_empty());
+
+    let exported = |source: &str| columns.iter().any(|c| c.source.as_deref() == Some(source));
+    let file_currency = header_currency(&columns);
+    let exports_photo_urls = exported("photo_urls") || exported("main_photo_url");
+
+    let mut rows = 0;
+    let mut warnings = Vec::new();
+
+    if exports_photo_urls && photo_base_url.is_none() {
+        warnings.push("No photo base URL is set: the photo URL columns are left empty".to_string());
+    }
+
+    for object in &objects {
+        let (values, unhosted_photos) =
+            build_csv_values(pool.inner(), &root, photo_base_url.as_deref(), object, request.platform_id).await?;
+
+        let record: Vec<String> = columns
+            .iter()
+            .map(|column| match (&column.source, &column.value) {
+                (Some(source), _) => values.get(source).cloned().unwrap_or_default(),
+                (None, Some(value)) => value.clone(),
+                (None, None) => String::new(),
+            })
+            .collect();
+
+        if request.platform_id.is_some() && values.get("price").is_none_or(|p| p.is_empty()) {
+            warnings.push(format!("{} (#{}) has no price", object.name, object.id));
+        }
+        if let (Some(file_currency), Some(currency)) = (file_currency, values.get("currency").filter(|c| !c.is_empty())) {
+            if currency != file_currency {
+                warnings.push(format!(
+                    "{} (#{}) is priced in {}, the file is read in {}",
+                    object.name, object.id, currency, file_currency
+                ));
+            }
+        }
+        if exports_photo_urls && !unhosted_photos.is_empty() {
+            warnings.push(format!(
+                "{} (#{}): {} photo(s) need hosting, left out of the URL columns: {}",
+                object.name,
+                object.id,
+                unhosted_photos.len(),
+                unhosted_photos.join(", ")
+            ));
+        }
+        if exported("ebay_condition_id") && values.get("ebay_condition_id").is_none_or(|id| id.is_empty()) {
+            match values.get("condition").filter(|c| !c.trim().is_empty()) {
+                Some(condition) => warnings.push(format!(
+                    "{} (#{}): condition '{}' has no eBay condition ID",
+                    object.name, object.id, condition
+                )),
+                None => warnings.push(format!("{} (#{}) has no condition", object.name, object.id)),
+            }
+        }
+
+        writer.write_record(&record).map_err(|e| e.to_string())?;
+        rows += 1;
+    }
+
+    writer.flush().map_err(|e| format!("Failed to write CSV file: {}", e))?;
+
+    Ok(ExportObjectsCsvResult {
+        file_path: dest_file.to_string_lossy().to_string(),
+        rows,
+        warnings,
+    })
+}
+
+#[tauri::command]
+pub async fn list_csv_sources() -> Result<Vec<String>, String> {
+    Ok(CSV_SOURCES.iter().map(|s| s.to_string()).collect())
+}
+
+#[tauri::command]
+pub async fn get_csv_mapping(
+    pool: State<'_, SqlitePool>,
+    platform_id: Option<i64>,
+    format: String,
+) -> Result<Vec<CsvColumn>, String> {
+    resolve_csv_columns(pool.inner(), platform_id, &format).await
+}
+
+// Save the column mapping of a platform; passing no columns restores the built-in layouts
+#[tauri::command]
+pub async fn set_csv_mapping(
+    pool: State<'_, SqlitePool>,
+    platform_id: i64,
+    columns: Option<Vec<CsvColumn>>,
+) -> Result<(), String> {
+    let mapping = match columns {
+        Some(columns) => {
+            validate_csv_columns(&columns)?;
+            Some(serde_json::to_string(&columns).map_err(|e| e.to_string())?)
+        }
+        None => None,
+    };
+
+    let result = sqlx::query("UPDATE platforms SET csv_mapping = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(&mapping)
+        .bind(platform_id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if result.rows_affected() == 0 {
+        Err("Platform not found".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_condition_grade_has_an_ebay_id() {
+        for grade in attributes::CONDITION_GRADES {
+            assert!(ebay_condition_id(grade).is_some(), "{} has no eBay condition ID", grade);
+        }
+        assert_eq!(ebay_condition_id("very_good").as_deref(), Some("4000"));
+        assert_eq!(ebay_condition_id(" 3000 ").as_deref(), Some("3000"));
+        assert_eq!(ebay_condition_id("mint"), None);
+        assert_eq!(ebay_condition_id(""), None);
+    }
+
+    #[test]
+    fn ebay_file_currency() {
+        assert_eq!(header_currency(&default_csv_columns("ebay").unwrap()), Some("EUR"));
+        assert_eq!(header_currency(&default_csv_columns("shopify").unwrap()), None);
+    }
+
+    #[test]
+    fn photo_urls_follow_the_library_layout() {
+        assert_eq!(
+            photo_url("https://cdn.example.com/library/", "/lib", "/lib/Lamps/brass lamp #2.jpg").as_deref(),
+            Some("https://cdn.example.com/library/Lamps/brass%20lamp%20%232.jpg")
+        );
+        assert_eq!(
+            photo_url("https://cdn.example.com", "/lib", "/lib/Vase/été.jpg").as_deref(),
+            Some("https://cdn.example.com/Vase/%C3%A9t%C3%A9.jpg")
+        );
+    }
+
+    #[test]
+    fn photos_outside_the_root_have_no_url() {
+        assert_eq!(photo_url("https://cdn.example.com", "/lib", "/tmp/lamp.jpg"), None);
+        assert_eq!(photo_url("https://cdn.example.com", "", "/lib/lamp.jpg"), None);
+    }
+}
@@ -0,0 +1,307 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+// ========== FILE OPERATIONS ==========
+// Moves fall back to copy + verify + delete when the destination is on another disk
+// (fs::rename fails with EXDEV, e.g. towards an external archive drive).
+
+const BUFFER_SIZE: usize = 1024 * 1024;
+
+// Minimum number of bytes copied between two progress reports
+const REPORT_EVERY: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CopyProgress {
+    pub files_done: u64,
+    pub files_total: u64,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    pub current_path: String,
+}
+
+// Progress of a set of file operations, reported through a callback
+pub(crate) struct Progress<'a> {
+    pub state: CopyProgress,
+    last_reported: u64,
+    report: Box<dyn FnMut(&CopyProgress) + Send + 'a>,
+}
+
+impl<'a> Progress<'a> {
+    pub(crate) fn new(files_total: u64, bytes_total: u64, report: impl FnMut(&CopyProgress) + Send + 'a) -> Self {
+        Progress {
+            state: CopyProgress {
+                files_total,
+                bytes_total,
+                ..Default::default()
+            },
+            last_reported: 0,
+            report: Box::new(report),
+        }
+    }
+
+    pub(crate) fn silent() -> Progress<'static> {
+        Progress::new(0, 0, |_| {})
+    }
+
+    fn add_bytes(&mut self, path: &Path, bytes: u64) {
+        self.state.bytes_done += bytes;
+        if self.state.bytes_done - self.last_reported >= REPORT_EVERY {
+            self.state.current_path = path.to_string_lossy().to_string();
+            self.send();
+        }
+    }
+
+    fn add_files(&mut self, path: &Path, files: u64, bytes: u64) {
+        self.state.files_done += files;
+        self.state.bytes_done += bytes;
+        self.state.current_path = path.to_string_lossy().to_string();
+        self.send();
+    }
+
+    fn send(&mut self) {
+        self.last_reported = self.state.bytes_done;
+        (self.report)(&self.state);
+    }
+}
+
+// Number of files and total size of a file or folder
+pub(crate) fn tree_stats(path: &Path) -> (u64, u64) {
+    if path.is_dir() {
+        fs::read_dir(path)
+            .map(|entries| {
+                entries.flatten().fold((0, 0), |(files, bytes), entry| {
+                    let (f, b) = tree_stats(&entry.path());
+                    (files + f, bytes + b)
+                })
+            })
+            .unwrap_or((0, 0))
+    } else {
+        (1, fs::metadata(path).map(|m| m.len()).unwrap_or(0))
+    }
+}
+
+fn is_cross_device(error: &io::Error) -> bool {
+    // EXDEV on Unix, ERROR_NOT_SAME_DEVICE on Windows
+    if cfg!(windows) {
+        error.raw_os_error() == Some(17)
+    } else {
+        error.raw_os_error() == Some(18)
+    }
+}
+
+fn file_checksum(path: &Path) -> io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; BUFFER_SIZE];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize().to_vec())
+}
+
+// Modification time in milliseconds since the epoch
+pub(crate) fn modified_millis(metadata: &fs::Metadata) -> Option<i64> {
+    let modified = metadata.modified().ok()?;
+    modified.duration_since(UNIX_EPOCH).ok().map(|d| d.as_millis() as i64)
+}
+
+// Apply `f` to every item on all the cores (file system metadata reads on large libraries)
+pub(crate) fn parallel_map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let threads = std::thread::available_parallelism().map_or(4, |n| n.get()).min(16);
+    let chunk_size = items.len().div_ceil(threads).max(1);
+    let f = &f;
+
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(f).collect::<Vec<R>>()))
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect()
+    })
+}
+
+// Hexadecimal SHA-256 of a file
+pub(crate) fn file_hash(path: &Path) -> io::Result<String> {
+    Ok(file_checksum(path)?.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+// Stream a file to its destination, then read the copy back and compare checksums
+fn copy_file_verified(source: &Path, destination: &Path, progress: &mut Progress) -> Result<(), String> {
+    let error = |e: io::Error| format!("Failed to copy {}: {}", source.display(), e);
+
+    let mut input = File::open(source).map_err(error)?;
+    let metadata = input.metadata().map_err(error)?;
+    let mut output = File::create(destination).map_err(error)?;
+
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; BUFFER_SIZE];
+    loop {
+        let read = input.read(&mut buffer).map_err(error)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        output.write_all(&buffer[..read]).map_err(error)?;
+        progress.add_bytes(source, read as u64);
+    }
+    output.sync_all().map_err(error)?;
+
+    // Keep the modification date (photo order by date relies on it)
+    if let Ok(modified) = metadata.modified() {
+        let _ = output.set_modified(modified);
+    }
+    drop(output);
+    let _ = fs::set_permissions(destination, metadata.permissions());
+
+    let copied = file_checksum(destination).map_err(error)?;
+    if copied != hasher.finalize().to_vec() {
+        return Err(format!("The copy of {} is corrupted (checksum mismatch)", source.display()));
+    }
+
+    progress.add_files(source, 1, 0);
+    Ok(())
+}
+
+fn copy_tree_verified(source: &Path, destination: &Path, progress: &mut Progress) -> Result<(), String> {
+    if !source.is_dir() {
+        return copy_file_verified(source, destination, progress);
+    }
+
+    fs::create_dir_all(destination).map_err(|e| format!("Failed to create folder: {}", e))?;
+    let entries = fs::read_dir(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+        copy_tree_verified(&entry.path(), &destination.join(entry.file_name()), progress)?;
+    }
+
+    // Folder dates change while their content is copied: set them last
+    if let Ok(modified) = fs::metadata(source).and_then(|m| m.modified()) {
+        if let Ok(folder) = File::open(destination) {
+            let _ = folder.set_modified(modified);
+        }
+    }
+
+    Ok(())
+}
+
+// Copy a file or folder, checking every copied file. A partial copy is removed on failure.
+pub(crate) fn copy_path(source: &Path, destination: &Path, progress: &mut Progress) -> Result<(), String> {
+    if destination.exists() {
+        return Err(format!("{} already exists", destination.display()));
+    }
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create folder: {}", e))?;
+    }
+
+    if let Err(e) = copy_tree_verified(source, destination, progress) {
+        let _ = if destination.is_dir() {
+            fs::remove_dir_all(destination)
+        } else {
+            fs::remove_file(destination)
+        };
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+// Copy back the files of a verified copy that are missing in its source (partly removed)
+fn restore_missing(copy: &Path, source: &Path) -> Result<(), String> {
+    if !copy.is_dir() {
+        if source.exists() {
+            return Ok(());
+        }
+        return copy_file_verified(copy, source, &mut Progress::silent());
+    }
+
+    fs::create_dir_all(source).map_err(|e| format!("Failed to create folder: {}", e))?;
+    let entries = fs::read_dir(copy).map_err(|e| format!("Failed to read {}: {}", copy.display(), e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read {}: {}", copy.display(), e))?;
+        restore_missing(&entry.path(), &source.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+// Move a file or folder. Across disks, the source is only deleted once the copy is verified.
+pub(crate) fn move_path(source: &Path, destination: &Path, progress: &mut Progress) -> Result<(), String> {
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create folder: {}", e))?;
+    }
+
+    let (files, bytes) = tree_stats(source);
+    match fs::rename(source, destination) {
+        Ok(()) => {
+            progress.add_files(destination, files, bytes);
+            Ok(())
+        }
+        Err(e) if is_cross_device(&e) => {
+            copy_path(source, destination, progress)?;
+
+            let removed = if source.is_dir() {
+                fs::remove_dir_all(source)
+            } else {
+                fs::remove_file(source)
+            };
+            let Err(e) = removed else {
+                return Ok(());
+            };
+
+            // The source may be partly removed: put it back from the copy so that the move
+            // fails as a whole, and only keep the copy when that fails too
+            let error = format!("Failed to remove {} after copying it: {}", source.display(), e);
+            match restore_missing(destination, source) {
+                Ok(()) => {
+                    let _ = if destination.is_dir() {
+                        fs::remove_dir_all(destination)
+                    } else {
+                        fs::remove_file(destination)
+                    };
+                    Err(error)
+                }
+                Err(restore) => Err(format!(
+                    "{} ({}); a complete copy is kept in {}",
+                    error,
+                    restore,
+                    destination.display()
+                )),
+            }
+        }
+        Err(e) => Err(format!("Failed to move {}: {}", source.display(), e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restore_missing_files() {
+        let dir = std::env::temp_dir().join(format!("seller-library-fileops-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let copy = dir.join("copy");
+        let source = dir.join("source");
+        fs::create_dir_all(copy.join("sub")).unwrap();
+        fs::write(copy.join("a.jpg"), "a").unwrap();
+        fs::write(copy.join("sub/b.jpg"), "b").unwrap();
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("a.jpg"), "kept").unwrap();
+
+        restore_missing(&copy, &source).unwrap();
+        assert_eq!(fs::read_to_string(source.join("a.jpg")).unwrap(), "kept");
+        assert_eq!(fs::read_to_string(source.join("sub/b.jpg")).unwrap(), "b");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
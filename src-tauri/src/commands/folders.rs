@@ -0,0 +1,539 @@
+use crate::models::{Folder, Object, UpdateFolderMetadata};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use tauri::State;
+
+use super::categories::category_folder_path;
+use super::descriptions::{self, DESCRIPTION_FILE};
+use super::exports::sanitize_file_name;
+use super::journal::{self, JournalStep};
+use super::paths::{self, LibraryPaths};
+use super::transfers::{self, ensure_folder_row, path_taken, MoveItemsRequest};
+use super::{replace_path_prefix, skus, trash};
+
+// ========== FOLDER RENAME ==========
+
+// Folders created with the root folder, that the application relies on
+const SYSTEM_FOLDERS: [&str; 3] = ["CATEGORIES", "IMPORTS", super::EXPORTS_FOLDER];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RenameFolderRequest {
+    pub folder_path: String,
+    pub new_name: String,
+}
+
+fn check_folder_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Folder name is required".to_string());
+    }
+    if name.contains('/') || name.contains('\\') || name == "." || name == ".." {
+        return Err(format!("Invalid folder name: {}", name));
+    }
+    Ok(())
+}
+
+// Folders whose name is managed elsewhere: the system folders and the folders of the categories
+// (renamed with their category)
+async fn check_renamable(conn: &mut SqliteConnection, root: &str, folder: &Path) -> Result<(), String> {
+    if root.is_empty() {
+        return Ok(());
+    }
+    if SYSTEM_FOLDERS.iter().any(|name| folder == Path::new(root).join(name)) {
+        return Err(format!("{} is used by the application and can't be renamed", folder.display()));
+    }
+
+    let category_ids = sqlx::query_scalar::<_, i64>("SELECT id FROM categories")
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    for id in category_ids {
+        if category_folder_path(conn, id, root).await? == folder {
+            return Err("This is the folder of a category: rename the category instead".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+// Rows changed by renaming the folder at `path`, for the journal
+async fn rows_under_path(conn: &mut SqliteConnection, path: &str) -> Result<[(&'static str, Vec<i64>); 3], String> {
+    Ok([
+        ("folders", trash::ids_under_path(conn, "folders", "path", path).await?),
+        ("photos", trash::ids_under_path(conn, "photos", "file_path", path).await?),
+        ("text_files", trash::ids_under_path(conn, "text_files", "file_path", path).await?),
+    ])
+}
+
+async fn rename_folder_rows(
+    conn: &mut SqliteConnection,
+    folder: &Folder,
+    new_path: &str,
+    new_name: &str,
+) -> Result<(), String> {
+    let touched = rows_under_path(conn, &folder.path).await?;
+    let mut before = Vec::new();
+    for (table, ids) in &touched {
+        before.push(trash::snapshot_rows(conn, table, "id", ids).await?);
+    }
+
+    replace_path_prefix(conn, &folder.path, new_path).await?;
+    sqlx::query("UPDATE folders SET name = ? WHERE id = ?")
+        .bind(new_name)
+        .bind(folder.id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut steps = vec![JournalStep::MovePath {
+        from: folder.path.clone(),
+        to: new_path.to_string(),
+    }];
+    for ((table, ids), before) in touched.iter().zip(before) {
+        let after = trash::snapshot_rows(conn, table, "id", ids).await?;
+        steps.push(JournalStep::rows("id", ids, before, after));
+    }
+
+    journal::record_operation(
+        conn,
+        "rename_folder",
+        &format!("Rename folder '{}' to '{}'", folder.name, new_name),
+        steps,
+    )
+    .await
+}
+
+// Rename a folder on disk and update the paths of the folder, of its sub-folders, photos and
+// text files. The folder is renamed back if the database can't be updated.
+#[tauri::command]
+pub async fn rename_folder(pool: State<'_, SqlitePool>, request: RenameFolderRequest) -> Result<Folder, String> {
+    let new_name = request.new_name.trim().to_string();
+    check_folder_name(&new_name)?;
+
+    let old_folder = Path::new(&request.folder_path);
+    if !old_folder.is_dir() {
+        return Err("Folder does not exist".to_string());
+    }
+    let new_folder = old_folder.with_file_name(&new_name);
+    let new_path = new_folder.to_string_lossy().to_string();
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let root = paths::library_root(&mut *tx).await?;
+
+    let folder = sqlx::query_as::<_, Folder>("SELECT * FROM folders WHERE path = ?")
+        .bind(paths::to_stored(&root, &request.folder_path))
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .resolve_paths(&root)
+        .ok_or_else(|| "Folder not found in database".to_string())?;
+
+    if new_folder == old_folder {
+        return Ok(folder);
+    }
+    check_renamable(&mut tx, &root, old_folder).await?;
+
+    // A change of case only is allowed on case-insensitive disks (the "new" folder is the old one)
+    let same_folder = fs::canonicalize(&new_folder).ok() == fs::canonicalize(old_folder).ok();
+    if new_folder.exists() && !same_folder {
+        return Err(format!("Folder already exists: {}", new_folder.display()));
+    }
+    let taken = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM folders WHERE path = ?")
+        .bind(paths::to_stored(&root, &new_path))
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    if taken > 0 && !same_folder {
+        return Err(format!("A folder is already registered at {}", new_path));
+    }
+
+    fs::rename(old_folder, &new_folder).map_err(|e| format!("Failed to rename folder: {}", e))?;
+
+    let renamed = rename_folder_rows(&mut tx, &folder, &new_path, &new_name).await;
+    if let Err(e) = renamed.and(tx.commit().await.map_err(|e| e.to_string())) {
+        // Put the folder back where the database expects it
+        let _ = fs::rename(&new_folder, old_folder);
+        return Err(e);
+    }
+
+    get_folder(pool.inner(), folder.id).await
+}
+
+// ========== FOLDER METADATA ==========
+
+async fn get_folder(pool: &SqlitePool, id: i64) -> Result<Folder, String> {
+    let root = paths::library_root(pool).await?;
+
+    sqlx::query_as::<_, Folder>("SELECT * FROM folders WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .resolve_paths(&root)
+        .ok_or_else(|| "Folder not found".to_string())
+}
+
+// "#rgb" or "#rrggbb", stored as lowercase "#rrggbb"
+fn normalize_color(color: &str) -> Result<String, String> {
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid colour: {}", color));
+    }
+    match hex.len() {
+        3 => Ok(format!("#{}", hex.chars().flat_map(|c| [c, c]).collect::<String>()).to_lowercase()),
+        6 => Ok(format!("#{}", hex).to_lowercase()),
+        _ => Err(format!("Invalid colour: {}", color)),
+    }
+}
+
+// Trimmed tags without duplicates (compared without case), in the given order
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags.iter().map(|tag| tag.trim()).filter(|tag| !tag.is_empty()) {
+        if !normalized.iter().any(|existing| existing.to_lowercase() == tag.to_lowercase()) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+#[tauri::command]
+pub async fn update_folder_metadata(
+    pool: State<'_, SqlitePool>,
+    folder_id: i64,
+    metadata: UpdateFolderMetadata,
+) -> Result<Folder, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let folder = sqlx::query_as::<_, Folder>("SELECT * FROM folders WHERE id = ?")
+        .bind(folder_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Folder not found".to_string())?;
+    let before = trash::snapshot_rows(&mut tx, "folders", "id", &[folder_id]).await?;
+
+    let notes = match metadata.notes {
+        Some(notes) => Some(notes.trim().to_string()).filter(|notes| !notes.is_empty()),
+        None => folder.notes,
+    };
+    let color = match metadata.color.as_deref().map(str::trim) {
+        Some("") => None,
+        Some(color) => Some(normalize_color(color)?),
+        None => folder.color,
+    };
+    let tags = match metadata.tags {
+        Some(tags) => {
+            let tags = normalize_tags(&tags);
+            if tags.is_empty() {
+                None
+            } else {
+                Some(serde_json::to_string(&tags).map_err(|e| e.to_string())?)
+            }
+        }
+        None => folder.tags,
+    };
+
+    sqlx::query("UPDATE folders SET notes = ?, color = ?, tags = ? WHERE id = ?")
+        .bind(notes)
+        .bind(color)
+        .bind(tags)
+        .bind(folder_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let after = trash::snapshot_rows(&mut tx, "folders", "id", &[folder_id]).await?;
+    journal::record_operation(
+        &mut tx,
+        "update_folder",
+        &format!("Edit folder '{}'", folder.name),
+        vec![JournalStep::rows("id", &[folder_id], before, after)],
+    )
+    .await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    get_folder(pool.inner(), folder_id).await
+}
+
+// Tags used on folders, for suggestions
+#[tauri::command]
+pub async fn list_folder_tags(pool: State<'_, SqlitePool>) -> Result<Vec<String>, String> {
+    let rows = sqlx::query_scalar::<_, String>("SELECT tags FROM folders WHERE tags IS NOT NULL")
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let tags: Vec<String> = rows
+        .iter()
+        .filter_map(|tags| serde_json::from_str::<Vec<String>>(tags).ok())
+        .flatten()
+        .collect();
+    let mut tags = normalize_tags(&tags);
+    tags.sort_by_key(|tag| tag.to_lowercase());
+    Ok(tags)
+}
+
+// ========== FOLDERS AND OBJECTS ==========
+// One folder per item: the folder holds the photos and the description.txt of an object.
+
+#[derive(Debug, Serialize)]
+pub struct ObjectFolderResult {
+    pub folder: Folder,
+    pub photos_moved: i32,
+    // Photos that could not be moved (not found...)
+    pub errors: Vec<String>,
+}
+
+// Content of the description.txt of a folder (None when missing or empty)
+async fn read_description_file(conn: &mut SqliteConnection, root: &str, folder: &Folder) -> Result<Option<String>, String> {
+    let stored = sqlx::query_scalar::<_, String>(
+        "SELECT file_path FROM text_files WHERE folder_id = ? AND lower(file_name) = ? ORDER BY id LIMIT 1"
+    )
+    .bind(folder.id)
+    .bind(DESCRIPTION_FILE)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let file = match stored {
+        Some(stored) => paths::to_absolute(root, &stored),
+        None => Path::new(&folder.path).join(DESCRIPTION_FILE).to_string_lossy().to_string(),
+    };
+    let content = match fs::read_to_string(&file) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", file, e)),
+    };
+
+    Ok(Some(content.trim().to_string()).filter(|content| !content.is_empty()))
+}
+
+async fn linked_object_name(conn: &mut SqliteConnection, object_id: Option<i64>) -> Result<Option<String>, String> {
+    let Some(object_id) = object_id else {
+        return Ok(None);
+    };
+    sqlx::query_scalar::<_, String>("SELECT name FROM objects WHERE id = ?")
+        .bind(object_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Create an object from a folder: named after the folder, described by its description.txt,
+// with the photos of the folder (the main photo first)
+#[tauri::command]
+pub async fn create_object_from_folder(pool: State<'_, SqlitePool>, folder_id: i64) -> Result<Object, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let root = paths::library_root(&mut *tx).await?;
+
+    let folder = sqlx::query_as::<_, Folder>("SELECT * FROM folders WHERE id = ?")
+        .bind(folder_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .resolve_paths(&root)
+        .ok_or_else(|| "Folder not found".to_string())?;
+
+    if let Some(name) = linked_object_name(&mut tx, folder.object_id).await? {
+        return Err(format!("This folder is already linked to the object '{}'", name));
+    }
+    let name = folder.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Folder name is required".to_string());
+    }
+    let description = read_description_file(&mut tx, &root, &folder).await?;
+
+    let object_id = sqlx::query("INSERT INTO objects (name, description) VALUES (?, ?)")
+        .bind(&name)
+        .bind(&description)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .last_insert_rowid();
+    skus::assign_sku(&mut tx, object_id, false).await?;
+
+    let photo_ids = sqlx::query_scalar::<_, i64>(
+        "SELECT id FROM photos WHERE folder_id = ? ORDER BY is_main DESC, file_name, id"
+    )
+    .bind(folder_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for (display_order, photo_id) in photo_ids.iter().enumerate() {
+        sqlx::query("INSERT INTO object_photos (object_id, photo_id, display_order) VALUES (?, ?, ?)")
+            .bind(object_id)
+            .bind(photo_id)
+            .bind(display_order as i32)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    sqlx::query("UPDATE folders SET object_id = ? WHERE id = ?")
+        .bind(object_id)
+        .bind(folder_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    descriptions::sync_folder(&mut tx, &root, folder_id).await?;
+
+    let object = sqlx::query_as::<_, Object>("SELECT * FROM objects WHERE id = ?")
+        .bind(object_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(object)
+}
+
+// Free folder for an object in `parent`, numbered on duplicates ("Lamp (1)")
+async fn object_folder_path(conn: &mut SqliteConnection, parent: &Path, object: &Object) -> Result<PathBuf, String> {
+    let name = sanitize_file_name(&object.name);
+    let name = if name.is_empty() { format!("Object {}", object.id) } else { name };
+
+    let mut target = parent.join(&name);
+    let mut copy_number = 1;
+    while path_taken(conn, &target, &HashSet::new()).await? {
+        target = parent.join(format!("{} ({})", name, copy_number));
+        copy_number += 1;
+    }
+    Ok(target)
+}
+
+// Create a folder for an object (in `parent_path`, IMPORTS by default) and move its photos into
+// it. The description of the object is written to the description.txt of the folder.
+#[tauri::command]
+pub async fn create_folder_for_object(
+    pool: State<'_, SqlitePool>,
+    object_id: i64,
+    parent_path: Option<String>,
+) -> Result<ObjectFolderResult, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let root = paths::library_root(&mut *tx).await?;
+    if root.is_empty() {
+        return Err("Root folder not configured".to_string());
+    }
+
+    let object = sqlx::query_as::<_, Object>("SELECT * FROM objects WHERE id = ?")
+        .bind(object_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Object not found".to_string())?;
+
+    let linked = sqlx::query_scalar::<_, String>("SELECT path FROM folders WHERE object_id = ? LIMIT 1")
+        .bind(object_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(linked) = linked {
+        return Err(format!("This object already has a folder: {}", paths::to_absolute(&root, &linked)));
+    }
+
+    let parent = match &parent_path {
+        Some(parent_path) => PathBuf::from(parent_path),
+        None => Path::new(&root).join("IMPORTS"),
+    };
+    // starts_with compares components: "<root>/../elsewhere" would pass it
+    let leaves_folder = parent.components().any(|c| matches!(c, Component::ParentDir | Component::CurDir));
+    if leaves_folder || !parent.starts_with(&root) {
+        return Err("The folder must be inside the root folder".to_string());
+    }
+    let target = object_folder_path(&mut tx, &parent, &object).await?;
+
+    let photo_ids = sqlx::query_scalar::<_, i64>(
+        "SELECT photo_id FROM object_photos WHERE object_id = ? ORDER BY display_order, id"
+    )
+    .bind(object_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let request = MoveItemsRequest {
+        photo_ids,
+        text_file_ids: Vec::new(),
+        folder_paths: Vec::new(),
+        destination_path: target.to_string_lossy().to_string(),
+        delete_source_folder: false,
+    };
+    let (report, errors) = transfers::move_items(&mut tx, &request).await?;
+
+    let description_file = target.join(DESCRIPTION_FILE);
+    let linked = match link_object_folder(&mut tx, &root, object_id, &target).await {
+        Ok(id) => tx.commit().await.map(|_| id).map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+
+    let folder_id = match linked {
+        Ok(id) => id,
+        Err(e) => {
+            let _ = fs::remove_file(&description_file);
+            transfers::revert_file_operations(&report);
+            let _ = fs::remove_dir(&target);
+            return Err(format!("Nothing was changed: {}", e));
+        }
+    };
+
+    Ok(ObjectFolderResult {
+        folder: get_folder(pool.inner(), folder_id).await?,
+        photos_moved: report.photos_updated.len() as i32,
+        errors,
+    })
+}
+
+// Folder row of the new folder of an object, linked to it, with the description.txt
+async fn link_object_folder(conn: &mut SqliteConnection, root: &str, object_id: i64, target: &Path) -> Result<i64, String> {
+    fs::create_dir_all(target).map_err(|e| format!("Failed to create folder: {}", e))?;
+    let folder_id = ensure_folder_row(conn, target, root, &mut Vec::new())
+        .await?
+        .ok_or_else(|| format!("Invalid folder: {}", target.display()))?;
+
+    sqlx::query("UPDATE folders SET object_id = ? WHERE id = ?")
+        .bind(object_id)
+        .bind(folder_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    descriptions::sync_folder(conn, root, folder_id).await?;
+
+    Ok(folder_id)
+}
+
+// Link a folder to an object, or unlink it with None. The description.txt of the folder and
+// the description of the object are synced when one of them is empty (conflict otherwise).
+#[tauri::command]
+pub async fn set_folder_object(
+    pool: State<'_, SqlitePool>,
+    folder_id: i64,
+    object_id: Option<i64>,
+) -> Result<Folder, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let updated = sqlx::query("UPDATE folders SET object_id = ? WHERE id = ?")
+        .bind(object_id)
+        .bind(folder_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("FOREIGN KEY") {
+                "Object not found".to_string()
+            } else {
+                e.to_string()
+            }
+        })?;
+    if updated.rows_affected() == 0 {
+        return Err("Folder not found".to_string());
+    }
+    if object_id.is_some() {
+        let root = paths::library_root(&mut *tx).await?;
+        descriptions::sync_folder(&mut tx, &root, folder_id).await?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    get_folder(pool.inner(), folder_id).await
+}
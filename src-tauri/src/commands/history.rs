@@ -0,0 +1,46 @@
+use crate::models::ChangeHistoryEntry;
+use sqlx::SqlitePool;
+use tauri::State;
+
+// ========== CHANGE HISTORY ==========
+// Rows are written by the triggers of migration 019
+
+// Timeline of an object: its own changes, its listings and its photos, most recent first
+#[tauri::command]
+pub async fn get_object_history(
+    pool: State<'_, SqlitePool>,
+    object_id: i64,
+    limit: Option<i64>,
+) -> Result<Vec<ChangeHistoryEntry>, String> {
+    sqlx::query_as::<_, ChangeHistoryEntry>(
+        "SELECT * FROM change_history WHERE object_id = ? ORDER BY changed_at DESC, id DESC LIMIT ?"
+    )
+    .bind(object_id)
+    .bind(limit.unwrap_or(-1))
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Latest changes, optionally limited to one entity (e.g. a category)
+#[tauri::command]
+pub async fn list_change_history(
+    pool: State<'_, SqlitePool>,
+    entity_type: Option<String>,
+    entity_id: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Vec<ChangeHistoryEntry>, String> {
+    sqlx::query_as::<_, ChangeHistoryEntry>(
+        "SELECT * FROM change_history
+         WHERE (? IS NULL OR entity_type = ?) AND (? IS NULL OR entity_id = ?)
+         ORDER BY changed_at DESC, id DESC LIMIT ?"
+    )
+    .bind(&entity_type)
+    .bind(&entity_type)
+    .bind(entity_id)
+    .bind(entity_id)
+    .bind(limit.unwrap_or(100))
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| e.to_string())
+}
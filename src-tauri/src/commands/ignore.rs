@@ -0,0 +1,399 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::{is_excluded_dir, is_text_file};
+
+// ========== IGNORE RULES ==========
+// Files and folders left out by the scans, the sync and the imports:
+// - gitignore-style patterns, global (settings) and per folder (.sellerignore files, applying to
+//   the folder holding them and everything below it)
+// - folders excluded by name at any depth (exports, trash folders...)
+// - images under a minimum file size or dimension (thumbnails, icons)
+// There is no file system watcher: files changed outside the app are picked up by the next sync,
+// which applies these rules like the scans and the imports.
+
+pub(crate) const IGNORE_FILE: &str = ".sellerignore";
+
+const PATTERNS_SETTING: &str = "ignore_patterns";
+const FOLDERS_SETTING: &str = "ignore_folders";
+const MIN_FILE_SIZE_SETTING: &str = "ignore_min_file_size";
+const MIN_DIMENSION_SETTING: &str = "ignore_min_dimension";
+
+// Excluded folder names until the setting is saved (compared without case)
+const DEFAULT_EXCLUDED_FOLDERS: [&str; 6] = ["EXPORTS", "Trash", ".Trash", ".Trashes", "$RECYCLE.BIN", "@eaDir"];
+
+// One line of an ignore file
+#[derive(Debug, Clone)]
+struct Pattern {
+    glob: Vec<char>,
+    // "!pattern": re-include what a previous pattern ignored
+    negated: bool,
+    // "pattern/": only matches folders
+    dir_only: bool,
+    // A pattern holding a "/" is relative to the folder of the ignore file, otherwise it matches
+    // the name of a file or folder at any depth
+    anchored: bool,
+}
+
+impl Pattern {
+    fn parse(line: &str) -> Option<Pattern> {
+        let line = line.trim_end();
+        if line.trim().is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').filter(|rest| rest.starts_with(['#', '!'])).unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let line = line.strip_prefix('/').unwrap_or(line);
+        if line.is_empty() {
+            return None;
+        }
+
+        Some(Pattern {
+            glob: line.chars().collect(),
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    // `relative` uses "/" separators and is relative to the folder of the ignore file
+    fn matches(&self, relative: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let text = if self.anchored {
+            relative
+        } else {
+            relative.rsplit('/').next().unwrap_or(relative)
+        };
+        let text: Vec<char> = text.chars().collect();
+        glob_match(&self.glob, &text)
+    }
+}
+
+fn parse_patterns(text: &str) -> Vec<Pattern> {
+    text.lines().filter_map(Pattern::parse).collect()
+}
+
+// "*" and "?" stop at "/", "**" crosses folders, "[a-z]" / "[!a-z]" are character classes
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            let rest = &pattern[2..];
+            // "**/" also matches no folder at all
+            if rest.first() == Some(&'/') && glob_match(&rest[1..], text) {
+                return true;
+            }
+            (0..=text.len()).any(|i| glob_match(rest, &text[i..]))
+        }
+        Some('*') => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != '/')
+            .any(|i| glob_match(&pattern[1..], &text[i..])),
+        Some('?') => text.first().is_some_and(|c| *c != '/') && glob_match(&pattern[1..], &text[1..]),
+        Some('[') => match (class_match(&pattern[1..], text.first()), text.first()) {
+            (Some((true, length)), Some(_)) => glob_match(&pattern[1 + length..], &text[1..]),
+            (Some(_), _) => false,
+            // No closing bracket: a literal "["
+            (None, first) => first == Some(&'[') && glob_match(&pattern[1..], &text[1..]),
+        },
+        Some('\\') if pattern.len() > 1 => text.first() == Some(&pattern[1]) && glob_match(&pattern[2..], &text[1..]),
+        Some(c) => text.first() == Some(c) && glob_match(&pattern[1..], &text[1..]),
+    }
+}
+
+// Whether `c` is in the class starting after "[", and the length of the class with its "]"
+fn class_match(class: &[char], c: Option<&char>) -> Option<(bool, usize)> {
+    let negated = matches!(class.first(), Some('!' | '^'));
+    let start = usize::from(negated);
+    let end = start + 1 + class.get(start + 1..)?.iter().position(|&x| x == ']')?;
+    let c = match c {
+        Some(c) if *c != '/' => *c,
+        _ => return Some((false, end + 1)),
+    };
+
+    let items = &class[start..end];
+    let mut found = false;
+    let mut i = 0;
+    while i < items.len() {
+        if i + 2 < items.len() && items[i + 1] == '-' {
+            found |= items[i] <= c && c <= items[i + 2];
+            i += 3;
+        } else {
+            found |= items[i] == c;
+            i += 1;
+        }
+    }
+
+    Some((found != negated, end + 1))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IgnoreSettings {
+    // Global patterns, one per line like in a .sellerignore file
+    pub patterns: Vec<String>,
+    pub excluded_folders: Vec<String>,
+    // Images smaller than this are left out (bytes, 0 to keep them all)
+    pub min_file_size: u64,
+    // Images whose width or height is under this are left out (pixels, 0 to keep them all)
+    pub min_dimension: u32,
+}
+
+pub(crate) struct IgnoreRules {
+    settings: IgnoreSettings,
+    global: Vec<Pattern>,
+    // Patterns of the .sellerignore file of each folder seen so far
+    folder_patterns: Mutex<HashMap<PathBuf, Arc<Vec<Pattern>>>>,
+}
+
+async fn read_settings(pool: &SqlitePool) -> Result<IgnoreSettings, String> {
+    let rows = sqlx::query_as::<_, (String, String)>(
+        "SELECT key, value FROM settings WHERE key IN (?, ?, ?, ?)"
+    )
+    .bind(PATTERNS_SETTING)
+    .bind(FOLDERS_SETTING)
+    .bind(MIN_FILE_SIZE_SETTING)
+    .bind(MIN_DIMENSION_SETTING)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let values: HashMap<String, String> = rows.into_iter().collect();
+    let lines = |value: &String| -> Vec<String> {
+        value.lines().map(str::trim).filter(|line| !line.is_empty()).map(String::from).collect()
+    };
+
+    Ok(IgnoreSettings {
+        patterns: values.get(PATTERNS_SETTING).map(lines).unwrap_or_default(),
+        excluded_folders: values
+            .get(FOLDERS_SETTING)
+            .map(lines)
+            .unwrap_or_else(|| DEFAULT_EXCLUDED_FOLDERS.iter().map(|name| name.to_string()).collect()),
+        min_file_size: values.get(MIN_FILE_SIZE_SETTING).and_then(|v| v.parse().ok()).unwrap_or(0),
+        min_dimension: values.get(MIN_DIMENSION_SETTING).and_then(|v| v.parse().ok()).unwrap_or(0),
+    })
+}
+
+impl IgnoreRules {
+    pub(crate) async fn load(pool: &SqlitePool) -> Result<IgnoreRules, String> {
+        Ok(IgnoreRules::new(read_settings(pool).await?))
+    }
+
+    fn new(settings: IgnoreSettings) -> IgnoreRules {
+        IgnoreRules {
+            global: parse_patterns(&settings.patterns.join("\n")),
+            settings,
+            folder_patterns: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn patterns_of(&self, dir: &Path) -> Arc<Vec<Pattern>> {
+        let mut cache = self.folder_patterns.lock().unwrap_or_else(|e| e.into_inner());
+        cache
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| Arc::new(fs::read_to_string(dir.join(IGNORE_FILE)).map(|text| parse_patterns(&text)).unwrap_or_default()))
+            .clone()
+    }
+
+    fn excluded_folder(&self, name: &str) -> bool {
+        self.settings.excluded_folders.iter().any(|excluded| excluded.eq_ignore_ascii_case(name))
+    }
+
+    // Whether a file or folder is left out of a scan of `root` (global patterns are relative to
+    // `root`). The content of an ignored folder is ignored too.
+    pub(crate) fn is_ignored(&self, root: &Path, path: &Path, is_dir: bool) -> bool {
+        let Ok(relative) = path.strip_prefix(root) else {
+            return false;
+        };
+        let names: Vec<String> = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+                _ => None,
+            })
+            .collect();
+
+        let mut dir = root.to_path_buf();
+        let mut folders = vec![(dir.clone(), self.patterns_of(&dir))];
+
+        for (i, name) in names.iter().enumerate() {
+            let entry = dir.join(name);
+            let entry_is_dir = is_dir || i + 1 < names.len();
+            if entry_is_dir && (self.excluded_folder(name) || is_excluded_dir(&entry, root)) {
+                return true;
+            }
+
+            // Last matching pattern wins, the deepest ignore files come last
+            let mut ignored = false;
+            let global = std::iter::once((root, self.global.as_slice()));
+            let files = folders.iter().map(|(folder, patterns)| (folder.as_path(), patterns.as_slice()));
+            for (base, patterns) in global.chain(files) {
+                let Ok(relative) = entry.strip_prefix(base) else {
+                    continue;
+                };
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                for pattern in patterns {
+                    if pattern.matches(&relative, entry_is_dir) {
+                        ignored = !pattern.negated;
+                    }
+                }
+            }
+            if ignored {
+                return true;
+            }
+
+            if entry_is_dir {
+                folders.push((entry.clone(), self.patterns_of(&entry)));
+            }
+            dir = entry;
+        }
+
+        false
+    }
+
+    // Images under the size or dimension thresholds (only the image header is read)
+    pub(crate) fn is_too_small(&self, path: &Path) -> bool {
+        if is_text_file(path) {
+            return false;
+        }
+        let min_file_size = self.settings.min_file_size;
+        if min_file_size > 0 && fs::metadata(path).is_ok_and(|m| m.len() < min_file_size) {
+            return true;
+        }
+        let min_dimension = self.settings.min_dimension;
+        if min_dimension > 0 {
+            if let Ok((width, height)) = image::image_dimensions(path) {
+                return width < min_dimension || height < min_dimension;
+            }
+        }
+        false
+    }
+
+    // Files left out of a scan of `root`
+    pub(crate) fn skips_file(&self, root: &Path, path: &Path) -> bool {
+        self.is_ignored(root, path, false) || self.is_too_small(path)
+    }
+}
+
+#[tauri::command]
+pub async fn get_ignore_settings(pool: State<'_, SqlitePool>) -> Result<IgnoreSettings, String> {
+    read_settings(pool.inner()).await
+}
+
+#[tauri::command]
+pub async fn set_ignore_settings(pool: State<'_, SqlitePool>, settings: IgnoreSettings) -> Result<IgnoreSettings, String> {
+    let values = [
+        (PATTERNS_SETTING, settings.patterns.join("\n")),
+        (FOLDERS_SETTING, settings.excluded_folders.join("\n")),
+        (MIN_FILE_SIZE_SETTING, settings.min_file_size.to_string()),
+        (MIN_DIMENSION_SETTING, settings.min_dimension.to_string()),
+    ];
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for (key, value) in values {
+        sqlx::query(
+            "INSERT INTO settings (key, value, updated_at) VALUES (?, ?, CURRENT_TIMESTAMP)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = CURRENT_TIMESTAMP"
+        )
+        .bind(key)
+        .bind(value)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    read_settings(pool.inner()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glob(pattern: &str, text: &str) -> bool {
+        glob_match(&pattern.chars().collect::<Vec<_>>(), &text.chars().collect::<Vec<_>>())
+    }
+
+    fn settings(patterns: &[&str]) -> IgnoreSettings {
+        IgnoreSettings {
+            patterns: patterns.iter().map(|p| p.to_string()).collect(),
+            excluded_folders: DEFAULT_EXCLUDED_FOLDERS.iter().map(|name| name.to_string()).collect(),
+            min_file_size: 0,
+            min_dimension: 0,
+        }
+    }
+
+    #[test]
+    fn glob_patterns() {
+        assert!(glob("*.jpg", "photo.jpg"));
+        assert!(!glob("*.jpg", "a/photo.jpg"));
+        assert!(glob("photo?.jpg", "photo1.jpg"));
+        assert!(!glob("photo?.jpg", "photo/.jpg"));
+        assert!(glob("**/thumbs", "a/b/thumbs"));
+        assert!(glob("**/thumbs", "thumbs"));
+        assert!(glob("a/**/b.jpg", "a/x/y/b.jpg"));
+        assert!(glob("img[0-9].jpg", "img5.jpg"));
+        assert!(!glob("img[!0-9].jpg", "img5.jpg"));
+        assert!(glob("img[!0-9].jpg", "imgx.jpg"));
+        assert!(glob("[abc", "[abc"));
+        assert!(glob("\\*.jpg", "*.jpg"));
+        assert!(!glob("\\*.jpg", "a.jpg"));
+        assert!(glob("été*", "été 2024"));
+    }
+
+    #[test]
+    fn ignore_patterns() {
+        let root = Path::new("/lib");
+        let rules = IgnoreRules::new(settings(&["*.tmp", "/drafts/", "cache/", "*.png", "!keep.png"]));
+
+        assert!(rules.is_ignored(root, Path::new("/lib/a/file.tmp"), false));
+        assert!(rules.is_ignored(root, Path::new("/lib/drafts"), true));
+        assert!(rules.is_ignored(root, Path::new("/lib/drafts/p.jpg"), false));
+        assert!(!rules.is_ignored(root, Path::new("/lib/a/drafts/p.jpg"), false));
+        assert!(rules.is_ignored(root, Path::new("/lib/a/cache/p.jpg"), false));
+        assert!(!rules.is_ignored(root, Path::new("/lib/a/cache"), false));
+        assert!(rules.is_ignored(root, Path::new("/lib/a/icon.png"), false));
+        assert!(!rules.is_ignored(root, Path::new("/lib/a/keep.png"), false));
+        assert!(!rules.is_ignored(root, Path::new("/lib/a/p.jpg"), false));
+        assert!(!rules.is_ignored(root, Path::new("/elsewhere/file.tmp"), false));
+
+        // Excluded folders, at any depth and whatever the case
+        assert!(rules.is_ignored(root, Path::new("/lib/a/trash/p.jpg"), false));
+        assert!(rules.is_ignored(root, Path::new("/lib/EXPORTS"), true));
+    }
+
+    #[test]
+    fn folder_ignore_files() {
+        let root = std::env::temp_dir().join(format!("seller-library-ignore-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        fs::write(root.join("a").join(IGNORE_FILE), "*.jpg\n!cover.jpg\n").unwrap();
+        fs::write(root.join("a/b").join(IGNORE_FILE), "# comment\ncover.jpg\n").unwrap();
+        fs::write(root.join("a/small.jpg"), "tiny").unwrap();
+
+        let mut rules = IgnoreRules::new(settings(&[]));
+        assert!(rules.is_ignored(&root, &root.join("a/p.jpg"), false));
+        assert!(!rules.is_ignored(&root, &root.join("a/cover.jpg"), false));
+        assert!(rules.is_ignored(&root, &root.join("a/b/cover.jpg"), false));
+        assert!(!rules.is_ignored(&root, &root.join("p.jpg"), false));
+        assert!(!rules.is_ignored(&root, &root.join("a/notes.txt"), false));
+
+        rules.settings.min_file_size = 10;
+        assert!(rules.is_too_small(&root.join("a/small.jpg")));
+        assert!(rules.skips_file(&root, &root.join("a/small.jpg")));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,580 @@
+use crate::models::{Category, Folder, Object};
+use sqlx::{SqliteConnection, SqlitePool};
+use tauri::State;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::fs;
+
+use super::{categories, paths};
+
+// ========== SPREADSHEET IMPORT ==========
+
+// Read a CSV or spreadsheet (xlsx, xls, ods) as a header row and data rows
+fn read_table(file_path: &str, sheet_name: Option<&str>) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let path = Path::new(file_path);
+    if !path.exists() {
+        return Err("File does not exist".to_string());
+    }
+
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let mut rows: Vec<Vec<String>> = match extension.as_str() {
+        "csv" | "txt" | "tsv" => {
+            let content = fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read file: {}", e))?;
+            let content = content.trim_start_matches('\u{feff}');
+
+            // Spreadsheets exported with a French locale use ';' as separator
+            let first_line = content.lines().next().unwrap_or("");
+            let delimiter = if extension == "tsv" || first_line.matches('\t').count() > first_line.matches(',').count() {
+                b'\t'
+            } else if first_line.matches(';').count() > first_line.matches(',').count() {
+                b';'
+            } else {
+                b','
+            };
+
+            let mut reader = csv::ReaderBuilder::new()
+                .delimiter(delimiter)
+                .has_headers(false)
+                .flexible(true)
+                .from_reader(content.as_bytes());
+
+            let mut rows = Vec::new();
+            for record in reader.records() {
+                let record = record.map_err(|e| format!("Failed to parse CSV: {}", e))?;
+                rows.push(record.iter().map(|v| v.trim().to_string()).collect());
+            }
+            rows
+        }
+        "xlsx" | "xlsm" | "xls" | "ods" => {
+            use calamine::Reader;
+
+            let mut workbook = calamine::open_workbook_auto(path)
+                .map_err(|e| format!("Failed to open spreadsheet: {}", e))?;
+
+            let sheet = match sheet_name {
+                Some(name) => name.to_string(),
+                None => workbook
+                    .sheet_names()
+                    .first()
+                    .cloned()
+                    .ok_or_else(|| "The spreadsheet has no sheet".to_string())?,
+            };
+
+            let range = workbook
+                .worksheet_range(&sheet)
+                .map_err(|e| format!("Failed to read sheet '{}': {}", sheet, e))?;
+
+            range
+                .rows()
+                .map(|row| row.iter().map(|cell| cell.to_string().trim().to_string()).collect())
+                .collect()
+        }
+        _ => return Err(format!("Unsupported file type: .{}", extension)),
+    };
+
+    // Skip leading empty lines
+    while rows.first().is_some_and(|row| row.iter().all(|v| v.is_empty())) {
+        rows.remove(0);
+    }
+
+    if rows.is_empty() {
+        return Err("The file is empty".to_string());
+    }
+
+    let headers = rows.remove(0);
+    Ok((headers, rows))
+}
+
+fn parse_year(value: &str) -> Result<i32, String> {
+    if let Ok(year) = value.parse::<i32>() {
+        return Ok(year);
+    }
+    match value.parse::<f64>() {
+        Ok(year) if year.fract() == 0.0 && year.abs() < i32::MAX as f64 => Ok(year as i32),
+        _ => Err(format!("Invalid year: '{}'", value)),
+    }
+}
+
+fn parse_weight(value: &str) -> Result<f64, String> {
+    match value.replace(',', ".").parse::<f64>() {
+        Ok(weight) if weight >= 0.0 => Ok(weight),
+        _ => Err(format!("Invalid weight: '{}'", value)),
+    }
+}
+
+#[derive(Serialize)]
+pub struct SpreadsheetPreview {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub total_rows: usize,
+}
+
+// Headers and first rows of a file, to build the column mapping
+#[tauri::command]
+pub async fn preview_spreadsheet(
+    file_path: String,
+    sheet_name: Option<String>,
+) -> Result<SpreadsheetPreview, String> {
+    let (headers, rows) = read_table(&file_path, sheet_name.as_deref())?;
+    let total_rows = rows.len();
+
+    Ok(SpreadsheetPreview {
+        headers,
+        rows: rows.into_iter().take(10).collect(),
+        total_rows,
+    })
+}
+
+// Header of the spreadsheet column used for each object field
+#[derive(Deserialize)]
+pub struct ImportColumnMapping {
+    pub name: String,
+    pub description: Option<String>,
+    pub year: Option<String>,
+    pub weight: Option<String>,
+    pub category: Option<String>,
+    pub sku: Option<String>,
+    // Column containing the name of the folder holding the object's photos
+    pub photo_folder: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ImportObjectsRequest {
+    pub file_path: String,
+    pub sheet_name: Option<String>,
+    pub mapping: ImportColumnMapping,
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub create_missing_categories: bool,
+}
+
+#[derive(Serialize)]
+pub struct ImportRowResult {
+    // Line number in the file (the header is line 1)
+    pub row: usize,
+    // "create", "update" or "skip"
+    pub action: String,
+    pub object_id: Option<i64>,
+    pub name: String,
+    pub photos: i32,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct ImportObjectsResult {
+    pub dry_run: bool,
+    pub created: i32,
+    pub updated: i32,
+    pub failed: i32,
+    pub photos_associated: i32,
+    pub rows: Vec<ImportRowResult>,
+}
+
+struct ParsedRow {
+    name: String,
+    description: Option<String>,
+    year: Option<i32>,
+    weight: Option<f64>,
+    category: Option<String>,
+    sku: Option<String>,
+    photo_folder_id: Option<i64>,
+}
+
+// Top-level category created by an import, with its folder row. Returns the path of the folder
+// to create on disk (None without root folder).
+async fn insert_category(conn: &mut SqliteConnection, root: &str, name: &str) -> Result<(i64, Option<PathBuf>), String> {
+    let id = sqlx::query("INSERT INTO categories (name, parent_id) VALUES (?, NULL)")
+        .bind(name)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .last_insert_rowid();
+
+    let categories_folder_id = sqlx::query_scalar::<_, i64>(
+        "SELECT id FROM folders WHERE name = 'CATEGORIES' AND parent_id IS NULL"
+    )
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    let (false, Some(categories_folder_id)) = (root.is_empty(), categories_folder_id) else {
+        return Ok((id, None));
+    };
+
+    let folder = categories::category_folder_path(conn, id, root).await?;
+    sqlx::query("INSERT OR IGNORE INTO folders (path, name, parent_id) VALUES (?, ?, ?)")
+        .bind(paths::to_stored(root, &folder.to_string_lossy()))
+        .bind(name)
+        .bind(categories_folder_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok((id, Some(folder)))
+}
+
+#[tauri::command]
+pub async fn import_objects(
+    pool: State<'_, SqlitePool>,
+    request: ImportObjectsRequest,
+) -> Result<ImportObjectsResult, String> {
+    let (headers, rows) = read_table(&request.file_path, request.sheet_name.as_deref())?;
+
+    // Resolve each mapped column to its index in the file
+    let column_index = |header: &Option<String>| -> Result<Option<usize>, String> {
+        match header {
+            Some(header) if !header.trim().is_empty() => headers
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(header.trim()))
+                .map(Some)
+                .ok_or_else(|| format!("Column '{}' not found in the file", header)),
+            _ => Ok(None),
+        }
+    };
+
+    let name_column = column_index(&Some(request.mapping.name.clone()))?
+        .ok_or_else(|| "The name column is required".to_string())?;
+    let description_column = column_index(&request.mapping.description)?;
+    let year_column = column_index(&request.mapping.year)?;
+    let weight_column = column_index(&request.mapping.weight)?;
+    let category_column = column_index(&request.mapping.category)?;
+    let sku_column = column_index(&request.mapping.sku)?;
+    let photo_folder_column = column_index(&request.mapping.photo_folder)?;
+
+    let categories = sqlx::query_as::<_, Category>("SELECT * FROM categories")
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut category_ids: HashMap<String, i64> = categories
+        .into_iter()
+        .map(|c| (c.name.to_lowercase(), c.id))
+        .collect();
+
+    let folders = sqlx::query_as::<_, Folder>("SELECT * FROM folders")
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let cell = |row: &Vec<String>, column: Option<usize>| -> Option<String> {
+        column
+            .and_then(|i| row.get(i))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    // First pass: validate every row
+    let mut results = Vec::new();
+    let mut parsed_rows = Vec::new();
+    let mut seen_skus = HashSet::new();
+    let mut missing_categories = Vec::new();
+
+    for (index, row) in rows.iter().enumerate() {
+        if row.iter().all(|v| v.trim().is_empty()) {
+            continue;
+        }
+
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+
+        let name = cell(row, Some(name_column)).unwrap_or_default();
+        if name.is_empty() {
+            errors.push("Name is required".to_string());
+        }
+
+        let year = match cell(row, year_column) {
+            Some(value) => parse_year(&value).map_err(|e| errors.push(e)).ok(),
+            None => None,
+        };
+        let weight = match cell(row, weight_column) {
+            Some(value) => parse_weight(&value).map_err(|e| errors.push(e)).ok(),
+            None => None,
+        };
+
+        let category = cell(row, category_column);
+        if let Some(category) = &category {
+            if !category_ids.contains_key(&category.to_lowercase()) {
+                if request.create_missing_categories {
+                    warnings.push(format!("Category '{}' will be created", category));
+                    if !missing_categories.iter().any(|c: &String| c.eq_ignore_ascii_case(category)) {
+                        missing_categories.push(category.clone());
+                    }
+                } else {
+                    errors.push(format!("Unknown category: '{}'", category));
+                }
+            }
+        }
+
+        let sku = cell(row, sku_column);
+        if let Some(sku) = &sku {
+            if !seen_skus.insert(sku.clone()) {
+                errors.push(format!("SKU '{}' appears more than once in the file", sku));
+            }
+        }
+
+        let mut photo_folder_id = None;
+        if let Some(folder_name) = cell(row, photo_folder_column) {
+            let matches: Vec<&Folder> = folders.iter().filter(|f| f.name == folder_name).collect();
+            match matches.len() {
+                0 => warnings.push(format!("No folder named '{}'", folder_name)),
+                1 => photo_folder_id = Some(matches[0].id),
+                n => warnings.push(format!("{} folders are named '{}', photos not associated", n, folder_name)),
+            }
+        }
+
+        let existing = match &sku {
+            Some(sku) => sqlx::query_as::<_, Object>("SELECT * FROM objects WHERE external_sku = ?")
+                .bind(sku)
+                .fetch_optional(pool.inner())
+                .await
+                .map_err(|e| e.to_string())?,
+            None => None,
+        };
+
+        let action = if !errors.is_empty() {
+            "skip"
+        } else if existing.is_some() {
+            "update"
+        } else {
+            "create"
+        };
+
+        results.push(ImportRowResult {
+            row: index + 2,
+            action: action.to_string(),
+            object_id: existing.as_ref().map(|o| o.id),
+            name: name.clone(),
+            photos: 0,
+            errors,
+            warnings,
+        });
+
+        parsed_rows.push(ParsedRow {
+            name,
+            description: cell(row, description_column),
+            year,
+            weight,
+            category,
+            sku,
+            photo_folder_id,
+        });
+    }
+
+    // Photos that would be associated, for the preview
+    for (result, parsed) in results.iter_mut().zip(parsed_rows.iter()) {
+        if let Some(folder_id) = parsed.photo_folder_id {
+            result.photos = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM photos WHERE folder_id = ?")
+                .bind(folder_id)
+                .fetch_one(pool.inner())
+                .await
+                .map_err(|e| e.to_string())? as i32;
+        }
+    }
+
+    let failed = results.iter().filter(|r| r.action == "skip").count() as i32;
+
+    if request.dry_run {
+        return Ok(ImportObjectsResult {
+            dry_run: true,
+            created: results.iter().filter(|r| r.action == "create").count() as i32,
+            updated: results.iter().filter(|r| r.action == "update").count() as i32,
+            failed,
+            photos_associated: results.iter().filter(|r| r.action != "skip").map(|r| r.photos).sum(),
+            rows: results,
+        });
+    }
+
+    // Second pass: write the missing categories and every valid row in a single transaction
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let root = paths::library_root(&mut *tx).await?;
+
+    let mut category_folders = Vec::new();
+    for name in missing_categories {
+        let (id, folder) = insert_category(&mut tx, &root, &name).await?;
+        category_ids.insert(name.to_lowercase(), id);
+        category_folders.extend(folder);
+    }
+    let mut created = 0;
+    let mut updated = 0;
+    let mut photos_associated = 0;
+
+    for (result, parsed) in results.iter_mut().zip(parsed_rows.iter()) {
+        if result.action == "skip" {
+            continue;
+        }
+
+        let category_id = parsed
+            .category
+            .as_ref()
+            .and_then(|c| category_ids.get(&c.to_lowercase()).copied());
+
+        let object_id = match result.object_id {
+            Some(object_id) => {
+                // Only overwrite the fields that have a value in the file
+                sqlx::query(
+                    "UPDATE objects SET
+                        name = ?,
+                        description = COALESCE(?, description),
+                        year = COALESCE(?, year),
+                        weight = COALESCE(?, weight),
+                        category_id = COALESCE(?, category_id),
+                        updated_at = CURRENT_TIMESTAMP
+                     WHERE id = ?"
+                )
+                .bind(&parsed.name)
+                .bind(&parsed.description)
+                .bind(parsed.year)
+                .bind(parsed.weight)
+                .bind(category_id)
+                .bind(object_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Row {}: {}", result.row, e))?;
+                updated += 1;
+                object_id
+            }
+            None => {
+                let insert = sqlx::query(
+                    "INSERT INTO objects (name, description, year, weight, category_id, external_sku) VALUES (?, ?, ?, ?, ?, ?)"
+                )
+                .bind(&parsed.name)
+                .bind(&parsed.description)
+                .bind(parsed.year)
+                .bind(parsed.weight)
+                .bind(category_id)
+                .bind(&parsed.sku)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Row {}: {}", result.row, e))?;
+                created += 1;
+                let object_id = insert.last_insert_rowid();
+                super::skus::assign_sku(&mut tx, object_id, false)
+                    .await
+                    .map_err(|e| format!("Row {}: {}", result.row, e))?;
+                object_id
+            }
+        };
+        result.object_id = Some(object_id);
+
+        if let Some(folder_id) = parsed.photo_folder_id {
+            // Main photo of the folder first, after the photos already associated
+            let photo_ids = sqlx::query_scalar::<_, i64>(
+                "SELECT id FROM photos WHERE folder_id = ? ORDER BY is_main DESC, file_name"
+            )
+            .bind(folder_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+
+            let mut display_order = sqlx::query_scalar::<_, Option<i32>>(
+                "SELECT MAX(display_order) + 1 FROM object_photos WHERE object_id = ?"
+            )
+            .bind(object_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?
+            .unwrap_or(0);
+
+            let mut associated = 0;
+            for photo_id in photo_ids {
+                let insert = sqlx::query(
+                    "INSERT OR IGNORE INTO object_photos (object_id, photo_id, display_order) VALUES (?, ?, ?)"
+                )
+                .bind(object_id)
+                .bind(photo_id)
+                .bind(display_order)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+
+                if insert.rows_affected() > 0 {
+                    display_order += 1;
+                    associated += 1;
+                }
+            }
+            result.photos = associated;
+            photos_associated += associated;
+        }
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    // The folders of the new categories are only created once the import is saved. They are
+    // empty: a folder that can't be created is created again by the first move into it.
+    for folder in category_folders {
+        let _ = fs::create_dir_all(folder);
+    }
+
+    Ok(ImportObjectsResult {
+        dry_run: false,
+        created,
+        updated,
+        failed,
+        photos_associated,
+        rows: results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn years() {
+        assert_eq!(parse_year("1954"), Ok(1954));
+        // Spreadsheet cells hold numbers as floats
+        assert_eq!(parse_year("1954.0"), Ok(1954));
+        assert_eq!(parse_year("-300"), Ok(-300));
+        assert!(parse_year("1954.5").is_err());
+        assert!(parse_year("circa 1950").is_err());
+        assert_eq!(parse_year("1e20"), Err("Invalid year: '1e20'".to_string()));
+    }
+
+    #[test]
+    fn weights() {
+        assert_eq!(parse_weight("1.5"), Ok(1.5));
+        // French decimal separator
+        assert_eq!(parse_weight("1,5"), Ok(1.5));
+        assert_eq!(parse_weight("0"), Ok(0.0));
+        assert!(parse_weight("-2").is_err());
+        assert_eq!(parse_weight("2 kg"), Err("Invalid weight: '2 kg'".to_string()));
+    }
+
+    #[test]
+    fn csv_tables() {
+        let dir = std::env::temp_dir().join(format!("seller-library-imports-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let table = |name: &str, content: &str| {
+            let path = dir.join(name);
+            fs::write(&path, content).unwrap();
+            read_table(&path.to_string_lossy(), None)
+        };
+
+        // Byte order mark, leading empty lines, quoted values and short rows
+        let (headers, rows) = table("comma.csv", "\u{feff}\n,,\nName,Year,Weight\n\"Lamp, brass\", 1954 ,\"1,5\"\nVase\n").unwrap();
+        assert_eq!(headers, ["Name", "Year", "Weight"]);
+        assert_eq!(rows, [vec!["Lamp, brass", "1954", "1,5"], vec!["Vase"]]);
+
+        let (headers, rows) = table("semicolon.csv", "Name;Weight\nLamp;1,5\n").unwrap();
+        assert_eq!(headers, ["Name", "Weight"]);
+        assert_eq!(rows, [["Lamp", "1,5"]]);
+
+        let (headers, rows) = table("tabs.txt", "Name\tDescription\nLamp\tBrass, working\n").unwrap();
+        assert_eq!(headers, ["Name", "Description"]);
+        assert_eq!(rows, [["Lamp", "Brass, working"]]);
+
+        assert_eq!(table("empty.csv", "\n,,\n"), Err("The file is empty".to_string()));
+        assert_eq!(table("objects.json", "[]"), Err("Unsupported file type: .json".to_string()));
+        let missing = read_table(&dir.join("missing.csv").to_string_lossy(), None);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(missing, Err("File does not exist".to_string()));
+    }
+}
@@ -0,0 +1,441 @@
+use crate::models::{JournalEntry, TrashEntry};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::{SqliteConnection, SqlitePool};
+use std::fs;
+use std::path::PathBuf;
+use tauri::State;
+
+use super::trash::{self, TrashedRecords};
+
+// ========== UNDO / REDO JOURNAL ==========
+
+// Number of operations kept in the journal
+const JOURNAL_SIZE: i64 = 200;
+
+// Trash entry restored by an undo, kept to send it back to the trash on redo
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct RestoredTrashEntry {
+    pub kind: String,
+    pub name: String,
+    pub original_path: Option<String>,
+    pub records: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum JournalStep {
+    // Rows of `table` whose `key` column is one of `ids`, before and after the operation
+    Rows {
+        table: String,
+        key: String,
+        ids: Vec<i64>,
+        before: Vec<Map<String, Value>>,
+        after: Vec<Map<String, Value>>,
+    },
+    // A file or folder moved on disk
+    MovePath { from: String, to: String },
+    // An empty folder removed by the operation
+    RemoveDir { path: String },
+    // Files and rows sent to the trash
+    Trash {
+        entry_id: i64,
+        #[serde(default)]
+        restored: Option<RestoredTrashEntry>,
+    },
+}
+
+impl JournalStep {
+    // Step from two snapshots of the same rows taken with `trash::snapshot_rows`
+    pub(crate) fn rows(key: &str, ids: &[i64], before: TrashedRecords, after: TrashedRecords) -> JournalStep {
+        JournalStep::Rows {
+            table: before.table,
+            key: key.to_string(),
+            ids: ids.to_vec(),
+            before: before.rows,
+            after: after.rows,
+        }
+    }
+
+    fn is_noop(&self) -> bool {
+        matches!(self, JournalStep::Rows { before, after, .. } if before == after)
+    }
+}
+
+// Record an operation in the journal. Runs inside the transaction of the operation.
+// Recording a new operation drops the operations that were undone (they can't be redone anymore).
+pub(crate) async fn record_operation(
+    conn: &mut SqliteConnection,
+    operation: &str,
+    description: &str,
+    steps: Vec<JournalStep>,
+) -> Result<(), String> {
+    let steps: Vec<JournalStep> = steps.into_iter().filter(|step| !step.is_noop()).collect();
+    if steps.is_empty() {
+        return Ok(());
+    }
+
+    sqlx::query("DELETE FROM operation_journal WHERE undone = 1")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    sqlx::query("INSERT INTO operation_journal (operation, description, steps) VALUES (?, ?, ?)")
+        .bind(operation)
+        .bind(description)
+        .bind(serde_json::to_string(&steps).map_err(|e| e.to_string())?)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    sqlx::query(
+        "DELETE FROM operation_journal WHERE id NOT IN (
+            SELECT id FROM operation_journal ORDER BY id DESC LIMIT ?
+         )"
+    )
+    .bind(JOURNAL_SIZE)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// Columns that only record when a row was last written: put back when nobody changed them since,
+// left as they are otherwise
+const BOOKKEEPING_COLUMNS: [&str; 1] = ["updated_at"];
+
+fn row_id(row: &Map<String, Value>) -> Option<i64> {
+    row.get("id").and_then(|v| v.as_i64())
+}
+
+// Columns whose value differs between two snapshots of a row
+fn changed_columns<'a>(from: &'a Map<String, Value>, to: &'a Map<String, Value>) -> Vec<&'a str> {
+    to.iter()
+        .filter(|(column, value)| from.get(column.as_str()) != Some(*value))
+        .map(|(column, _)| column.as_str())
+        .collect()
+}
+
+// Turn the rows of `table` from the `from` snapshot into the `to` snapshot. Only the rows and
+// columns the operation changed are written: changes made since by other commands (stock, SKU,
+// location, paths updated by a sync...) are kept. When one of the columns to put back was changed
+// since, nothing is written and an error is returned.
+async fn apply_rows(
+    conn: &mut SqliteConnection,
+    table: &str,
+    from: &[Map<String, Value>],
+    to: &[Map<String, Value>],
+) -> Result<(), String> {
+    // Rows created by the operation (undo) or removed by it (redo)
+    for row in from.iter().filter(|row| !to.iter().any(|other| row_id(other) == row_id(row))) {
+        sqlx::query(&format!("DELETE FROM {} WHERE id = ?", table))
+            .bind(row_id(row))
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    for row in to {
+        let Some(previous) = from.iter().find(|other| row_id(other) == row_id(row)) else {
+            trash::write_row(conn, table, row).await?;
+            continue;
+        };
+        let columns = changed_columns(previous, row);
+        if columns.is_empty() {
+            continue;
+        }
+
+        let current = trash::snapshot_rows(conn, table, "id", &[row_id(row).unwrap_or_default()]).await?;
+        let current = current
+            .rows
+            .first()
+            .ok_or_else(|| format!("{} #{} no longer exists", table, row_id(row).unwrap_or_default()))?;
+
+        let (bookkeeping, data): (Vec<&str>, Vec<&str>) =
+            columns.into_iter().partition(|column| BOOKKEEPING_COLUMNS.contains(column));
+        let changed_since: Vec<&str> = data
+            .iter()
+            .copied()
+            .filter(|column| current.get(*column) != previous.get(*column))
+            .collect();
+        if !changed_since.is_empty() {
+            return Err(format!(
+                "{} #{} was changed since ({})",
+                table,
+                row_id(row).unwrap_or_default(),
+                changed_since.join(", ")
+            ));
+        }
+
+        let mut columns = data;
+        columns.extend(bookkeeping.into_iter().filter(|column| current.get(*column) == previous.get(*column)));
+        trash::update_columns(conn, table, row, &columns).await?;
+    }
+
+    Ok(())
+}
+
+// Move a file back or forth, refusing to overwrite anything
+fn move_journal_path(from: &str, to: &str, moved: &mut Vec<(PathBuf, PathBuf)>) -> Result<(), String> {
+    let (from, to) = (PathBuf::from(from), PathBuf::from(to));
+    if !from.exists() {
+        return Err(format!("{} no longer exists", from.display()));
+    }
+    if to.exists() {
+        return Err(format!("{} already exists", to.display()));
+    }
+    trash::move_path(&from, &to)?;
+    moved.push((from, to));
+    Ok(())
+}
+
+// Apply one step of an operation, backwards (undo) or forwards (redo). Files moved are added to
+// `moved` so that they can be put back if a later step or the transaction fails.
+async fn apply_step(
+    conn: &mut SqliteConnection,
+    step: &mut JournalStep,
+    undo: bool,
+    moved: &mut Vec<(PathBuf, PathBuf)>,
+) -> Result<(), String> {
+    match step {
+        JournalStep::Rows { table, before, after, .. } => {
+            if undo {
+                apply_rows(conn, table, after, before).await
+            } else {
+                apply_rows(conn, table, before, after).await
+            }
+        }
+        JournalStep::MovePath { from, to } => {
+            if undo {
+                move_journal_path(to, from, moved)
+            } else {
+                move_journal_path(from, to, moved)
+            }
+        }
+        JournalStep::RemoveDir { path } => {
+            if undo {
+                fs::create_dir_all(&*path).map_err(|e| format!("Failed to create folder: {}", e))
+            } else {
+                let _ = fs::remove_dir(&*path);
+                Ok(())
+            }
+        }
+        JournalStep::Trash { entry_id, restored } => {
+            if undo {
+                let (entry, restored_file) = trash::restore_entry(conn, *entry_id).await?;
+                if let Some((trash_path, original_path)) = restored_file {
+                    moved.push((trash_path, original_path));
+                }
+                *restored = Some(RestoredTrashEntry {
+                    kind: entry.kind,
+                    name: entry.name,
+                    original_path: entry.original_path,
+                    records: entry.records,
+                });
+                Ok(())
+            } else {
+                let data = restored
+                    .take()
+                    .ok_or_else(|| "Nothing to send back to the trash".to_string())?;
+                let entry = TrashEntry {
+                    id: *entry_id,
+                    kind: data.kind,
+                    name: data.name,
+                    original_path: data.original_path,
+                    trash_path: None,
+                    records: data.records,
+                    file_size: None,
+                    deleted_at: String::new(),
+                };
+                let (new_entry_id, trash_path) = trash::retrash_entry(conn, &entry).await?;
+                if let (Some(original_path), Some(trash_path)) = (&entry.original_path, trash_path) {
+                    moved.push((PathBuf::from(original_path), trash_path));
+                }
+                *entry_id = new_entry_id;
+                Ok(())
+            }
+        }
+    }
+}
+
+async fn replay_operation(pool: &SqlitePool, undo: bool) -> Result<JournalEntry, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    // Rows are put back table by table: foreign keys are only checked at commit
+    sqlx::query("PRAGMA defer_foreign_keys = ON")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let entry = if undo {
+        sqlx::query_as::<_, JournalEntry>(
+            "SELECT * FROM operation_journal WHERE undone = 0 ORDER BY id DESC LIMIT 1"
+        )
+    } else {
+        sqlx::query_as::<_, JournalEntry>(
+            "SELECT * FROM operation_journal WHERE undone = 1 ORDER BY id ASC LIMIT 1"
+        )
+    }
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| if undo { "Nothing to undo" } else { "Nothing to redo" }.to_string())?;
+
+    let mut steps: Vec<JournalStep> = serde_json::from_str(&entry.steps).map_err(|e| e.to_string())?;
+    let mut moved: Vec<(PathBuf, PathBuf)> = Vec::new();
+
+    let outcome: Result<(), String> = async {
+        if undo {
+            for step in steps.iter_mut().rev() {
+                apply_step(&mut tx, step, true, &mut moved).await?;
+            }
+        } else {
+            for step in steps.iter_mut() {
+                apply_step(&mut tx, step, false, &mut moved).await?;
+            }
+        }
+
+        sqlx::query("UPDATE operation_journal SET undone = ?, steps = ? WHERE id = ?")
+            .bind(undo)
+            .bind(serde_json::to_string(&steps).map_err(|e| e.to_string())?)
+            .bind(entry.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+    .await;
+
+    let outcome = match outcome {
+        Ok(()) => tx.commit().await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = outcome {
+        for (from, to) in moved.iter().rev() {
+            let _ = trash::move_path(to, from);
+        }
+        return Err(format!("Can't {} '{}': {}", if undo { "undo" } else { "redo" }, entry.description, e));
+    }
+
+    Ok(JournalEntry { undone: undo, ..entry })
+}
+
+// Undo the last operation of the journal
+#[tauri::command]
+pub async fn undo_last(pool: State<'_, SqlitePool>) -> Result<JournalEntry, String> {
+    replay_operation(pool.inner(), true).await
+}
+
+// Redo the last undone operation
+#[tauri::command]
+pub async fn redo(pool: State<'_, SqlitePool>) -> Result<JournalEntry, String> {
+    replay_operation(pool.inner(), false).await
+}
+
+// Latest operations of the journal, most recent first
+#[tauri::command]
+pub async fn list_journal(
+    pool: State<'_, SqlitePool>,
+    limit: Option<i64>,
+) -> Result<Vec<JournalEntry>, String> {
+    sqlx::query_as::<_, JournalEntry>("SELECT * FROM operation_journal ORDER BY id DESC LIMIT ?")
+        .bind(limit.unwrap_or(50))
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    // In-memory database with an object; one connection so that every query sees the same database
+    async fn test_pool() -> SqlitePool {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        sqlx::raw_sql(include_str!("../../migrations/018_add_operation_journal.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::raw_sql(
+            "CREATE TABLE objects (id INTEGER PRIMARY KEY, name TEXT, quantity INTEGER, updated_at TEXT);
+             INSERT INTO objects (id, name, quantity, updated_at) VALUES (1, 'Lamp', 1, '2024-01-01');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    // Run a statement on the objects `ids` and record it in the journal
+    async fn record(pool: &SqlitePool, ids: &[i64], sql: &str) {
+        let mut conn = pool.acquire().await.unwrap();
+        let before = trash::snapshot_rows(&mut conn, "objects", "id", ids).await.unwrap();
+        sqlx::query(sql).execute(&mut *conn).await.unwrap();
+        let after = trash::snapshot_rows(&mut conn, "objects", "id", ids).await.unwrap();
+        record_operation(&mut conn, "test", "Test", vec![JournalStep::rows("id", ids, before, after)])
+            .await
+            .unwrap();
+    }
+
+    async fn object(pool: &SqlitePool, id: i64) -> Option<(String, i64, String)> {
+        sqlx::query_as("SELECT name, quantity, updated_at FROM objects WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn undo_and_redo_an_update() {
+        let pool = test_pool().await;
+        record(&pool, &[1], "UPDATE objects SET name = 'Brass lamp', updated_at = '2024-02-01' WHERE id = 1").await;
+
+        // Changed since by another command: kept on undo
+        sqlx::query("UPDATE objects SET quantity = 3 WHERE id = 1").execute(&pool).await.unwrap();
+
+        let entry = replay_operation(&pool, true).await.unwrap();
+        assert!(entry.undone);
+        assert_eq!(object(&pool, 1).await.unwrap(), ("Lamp".into(), 3, "2024-01-01".into()));
+
+        let entry = replay_operation(&pool, false).await.unwrap();
+        assert!(!entry.undone);
+        assert_eq!(object(&pool, 1).await.unwrap(), ("Brass lamp".into(), 3, "2024-02-01".into()));
+
+        assert_eq!(replay_operation(&pool, false).await.unwrap_err(), "Nothing to redo");
+    }
+
+    #[tokio::test]
+    async fn undo_a_created_row() {
+        let pool = test_pool().await;
+        record(&pool, &[2], "INSERT INTO objects (id, name, quantity) VALUES (2, 'Vase', 1)").await;
+
+        replay_operation(&pool, true).await.unwrap();
+        assert_eq!(object(&pool, 2).await, None);
+        assert_eq!(object(&pool, 1).await.unwrap().0, "Lamp");
+
+        replay_operation(&pool, false).await.unwrap();
+        assert_eq!(object(&pool, 2).await.unwrap().0, "Vase");
+    }
+
+    #[tokio::test]
+    async fn refuse_to_undo_a_column_edited_since() {
+        let pool = test_pool().await;
+        record(&pool, &[1], "UPDATE objects SET name = 'Brass lamp' WHERE id = 1").await;
+        sqlx::query("UPDATE objects SET name = 'Copper lamp' WHERE id = 1").execute(&pool).await.unwrap();
+
+        let error = replay_operation(&pool, true).await.unwrap_err();
+        assert_eq!(error, "Can't undo 'Test': objects #1 was changed since (name)");
+        assert_eq!(object(&pool, 1).await.unwrap().0, "Copper lamp");
+
+        // Still waiting to be undone
+        let undone = sqlx::query_scalar::<_, bool>("SELECT undone FROM operation_journal")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(!undone);
+    }
+}
@@ -0,0 +1,179 @@
+use serde::Serialize;
+use sqlx::{SqliteConnection, SqlitePool};
+use std::path::Path;
+use tauri::State;
+
+use super::{fileops, paths, write_root_folder};
+
+// ========== LIBRARY ROOT ==========
+// When the drive holding the root folder is unplugged, the library is "offline": every row is
+// kept and the sync refuses to run, instead of removing all the photos it can't find.
+
+// Number of photos checked before the library is re-pointed to a new location
+const RELOCATION_SAMPLE_SIZE: usize = 20;
+
+#[derive(Debug, Serialize)]
+pub struct LibraryStatus {
+    pub root_folder: Option<String>,
+    pub online: bool,
+    // Why the library is offline
+    pub reason: Option<String>,
+    pub photo_count: i64,
+    pub text_file_count: i64,
+}
+
+// Why the files of the library can't be reached, None when the library is online
+pub(crate) async fn offline_reason(conn: &mut SqliteConnection, root: &str) -> Result<Option<String>, String> {
+    if root.is_empty() {
+        return Ok(None);
+    }
+    if !Path::new(root).is_dir() {
+        return Ok(Some(format!("The root folder {} can't be found (is the drive unplugged?)", root)));
+    }
+
+    // An empty mount point: the folder exists but none of the library folders are in it
+    let top_folders = sqlx::query_scalar::<_, String>("SELECT path FROM folders WHERE parent_id IS NULL")
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    let in_library: Vec<String> = top_folders
+        .into_iter()
+        .filter(|path| !Path::new(path).is_absolute())
+        .collect();
+
+    if !in_library.is_empty() && !in_library.iter().any(|path| Path::new(&paths::to_absolute(root, path)).exists()) {
+        return Ok(Some(format!(
+            "None of the library folders can be found in {} (is the drive mounted?)",
+            root
+        )));
+    }
+
+    Ok(None)
+}
+
+#[tauri::command]
+pub async fn get_library_status(pool: State<'_, SqlitePool>) -> Result<LibraryStatus, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    let root = paths::library_root(&mut *conn).await?;
+    let reason = offline_reason(&mut conn, &root).await?;
+
+    let photo_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM photos")
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    let text_file_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM text_files")
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(LibraryStatus {
+        root_folder: (!root.is_empty()).then_some(root),
+        online: reason.is_none(),
+        reason,
+        photo_count,
+        text_file_count,
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct RelocationCheck {
+    pub path: String,
+    // 'missing', 'size_mismatch' or 'hash_mismatch'
+    pub problem: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RelocationReport {
+    pub root_folder: String,
+    // False when the sample didn't match and the setting was left unchanged
+    pub applied: bool,
+    pub checked: usize,
+    pub verified: usize,
+    pub failures: Vec<RelocationCheck>,
+}
+
+// The library was moved (new drive letter, mount point or renamed folder): only the setting
+// changes, the stored paths are relative to the root folder. A sample of photos is checked at
+// the new location first (size, and content hash when it is known); `force` re-points anyway.
+#[tauri::command]
+pub async fn relocate_root_folder(
+    pool: State<'_, SqlitePool>,
+    path: String,
+    force: Option<bool>,
+) -> Result<RelocationReport, String> {
+    if !Path::new(&path).is_dir() {
+        return Err(format!("Folder does not exist: {}", path));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let candidates = sqlx::query_as::<_, (i64, String, Option<i64>, Option<String>)>(
+        "SELECT id, file_path, file_size, content_hash FROM photos ORDER BY RANDOM() LIMIT ?"
+    )
+    .bind((RELOCATION_SAMPLE_SIZE * 10) as i64)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // Only the photos stored under the root folder move with it
+    let sample = candidates
+        .into_iter()
+        .filter(|(_, stored, _, _)| !Path::new(stored).is_absolute())
+        .take(RELOCATION_SAMPLE_SIZE);
+
+    let mut report = RelocationReport {
+        root_folder: path.clone(),
+        applied: false,
+        checked: 0,
+        verified: 0,
+        failures: Vec::new(),
+    };
+    let mut new_hashes = Vec::new();
+
+    for (id, stored, file_size, content_hash) in sample {
+        report.checked += 1;
+        let file = paths::to_absolute(&path, &stored);
+        let failure = |problem: &str| RelocationCheck {
+            path: file.clone(),
+            problem: problem.to_string(),
+        };
+
+        let Ok(metadata) = std::fs::metadata(&file) else {
+            report.failures.push(failure("missing"));
+            continue;
+        };
+        if file_size.is_some_and(|size| size != metadata.len() as i64) {
+            report.failures.push(failure("size_mismatch"));
+            continue;
+        }
+
+        let hash = fileops::file_hash(Path::new(&file)).map_err(|e| format!("Failed to read {}: {}", file, e))?;
+        match content_hash {
+            Some(known) if known != hash => report.failures.push(failure("hash_mismatch")),
+            Some(_) => report.verified += 1,
+            None => {
+                new_hashes.push((id, hash));
+                report.verified += 1;
+            }
+        }
+    }
+
+    if !report.failures.is_empty() && !force.unwrap_or(false) {
+        return Ok(report);
+    }
+
+    // Hashes seen for the first time make the next relocation check stricter
+    for (id, hash) in new_hashes {
+        sqlx::query("UPDATE photos SET content_hash = ? WHERE id = ?")
+            .bind(hash)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    write_root_folder(&mut tx, &path).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    report.applied = true;
+    Ok(report)
+}
@@ -0,0 +1,373 @@
+use crate::models::{Object, Platform, ObjectPlatform, UpdateObjectListing, ListingValidation};
+use sqlx::{SqliteConnection, SqlitePool};
+use tauri::State;
+
+use super::categories;
+
+// ========== LISTINGS COMMANDS ==========
+
+// Load the object and platform a listing belongs to, plus the number of photos of the object
+async fn load_listing_context(
+    conn: &mut SqliteConnection,
+    listing: &ObjectPlatform,
+) -> Result<(Object, Platform, i64), String> {
+    let object = sqlx::query_as::<_, Object>("SELECT * FROM objects WHERE id = ?")
+        .bind(listing.object_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Object not found".to_string())?;
+
+    let platform = sqlx::query_as::<_, Platform>("SELECT * FROM platforms WHERE id = ?")
+        .bind(listing.platform_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Platform not found".to_string())?;
+
+    let photo_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM object_photos WHERE object_id = ?")
+        .bind(listing.object_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok((object, platform, photo_count))
+}
+
+// Listing with its platform category id filled from the category mapping when not set
+pub(crate) async fn with_mapped_category(
+    conn: &mut SqliteConnection,
+    listing: &ObjectPlatform,
+    object: &Object,
+) -> Result<ObjectPlatform, String> {
+    let mut listing = listing.clone();
+
+    let missing = listing.platform_category_id.as_deref().is_none_or(|c| c.trim().is_empty());
+    if let (true, Some(category_id)) = (missing, object.category_id) {
+        if let Some(mapped) = categories::mapped_platform_category_id(conn, category_id, listing.platform_id).await? {
+            listing.platform_category_id = Some(mapped);
+        }
+    }
+
+    Ok(listing)
+}
+
+// Title used on the platform: the listing override, or the object name
+pub(crate) fn effective_title(listing: &ObjectPlatform, object: &Object) -> String {
+    listing
+        .title
+        .as_ref()
+        .filter(|t| !t.trim().is_empty())
+        .cloned()
+        .unwrap_or_else(|| object.name.clone())
+}
+
+// Description used on the platform: the listing override, or the object description
+pub(crate) fn effective_description(listing: &ObjectPlatform, object: &Object) -> String {
+    listing
+        .description
+        .as_ref()
+        .filter(|d| !d.trim().is_empty())
+        .or(object.description.as_ref())
+        .cloned()
+        .unwrap_or_default()
+}
+
+pub(crate) fn check_title_length(title: &str, platform: &Platform) -> Option<String> {
+    let length = title.chars().count() as i64;
+    match platform.title_max_length {
+        Some(max) if length > max => Some(format!(
+            "Title is {} characters long, {} allows at most {}",
+            length, platform.name, max
+        )),
+        _ => None,
+    }
+}
+
+// Check a listing against the rules of its platform
+pub(crate) fn check_listing(
+    listing: &ObjectPlatform,
+    object: &Object,
+    platform: &Platform,
+    photo_count: i64,
+) -> ListingValidation {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+
+    let title = effective_title(listing, object);
+    if title.trim().is_empty() {
+        errors.push("Title is required".to_string());
+    } else if let Some(error) = check_title_length(&title, platform) {
+        errors.push(error);
+    }
+
+    let description = effective_description(listing, object);
+    if description.trim().is_empty() {
+        warnings.push("Description is empty".to_string());
+    } else if let Some(max) = platform.description_max_length {
+        let length = description.chars().count() as i64;
+        if length > max {
+            errors.push(format!(
+                "Description is {} characters long, {} allows at most {}",
+                length, platform.name, max
+            ));
+        }
+    }
+
+    match listing.price {
+        Some(price) if price > 0.0 => {}
+        _ => errors.push("A price greater than 0 is required".to_string()),
+    }
+
+    let currency = listing.currency.as_deref().unwrap_or("");
+    if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()) {
+        errors.push(format!("Invalid currency code: '{}'", currency));
+    }
+
+    if platform.requires_condition.unwrap_or(false)
+        && listing.condition.as_deref().is_none_or(|c| c.trim().is_empty())
+    {
+        errors.push(format!("{} requires a condition", platform.name));
+    }
+
+    if platform.requires_category_id.unwrap_or(false)
+        && listing.platform_category_id.as_deref().is_none_or(|c| c.trim().is_empty())
+    {
+        errors.push(format!("{} requires a platform category id", platform.name));
+    }
+
+    if let Some(specifics) = &listing.item_specifics {
+        match serde_json::from_str::<serde_json::Value>(specifics) {
+            Ok(serde_json::Value::Object(_)) => {}
+            _ => errors.push("Item specifics must be a JSON object".to_string()),
+        }
+    }
+
+    if photo_count == 0 {
+        warnings.push("No photos are associated with this object".to_string());
+    } else if let Some(max) = platform.max_photos {
+        if photo_count > max {
+            warnings.push(format!(
+                "{} photos associated, only the first {} will be used on {}",
+                photo_count, max, platform.name
+            ));
+        }
+    }
+
+    ListingValidation {
+        valid: errors.is_empty(),
+        errors,
+        warnings,
+    }
+}
+
+// Validate a listing about to be published; returns an error listing every problem found
+pub(crate) async fn ensure_listing_publishable(
+    conn: &mut SqliteConnection,
+    listing: &ObjectPlatform,
+) -> Result<(), String> {
+    let (object, platform, photo_count) = load_listing_context(conn, listing).await?;
+    let listing = with_mapped_category(conn, listing, &object).await?;
+    let validation = check_listing(&listing, &object, &platform, photo_count);
+
+    if validation.valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Listing cannot be published on {}: {}",
+            platform.name,
+            validation.errors.join("; ")
+        ))
+    }
+}
+
+#[tauri::command]
+pub async fn validate_object_listing(
+    pool: State<'_, SqlitePool>,
+    id: i64,
+) -> Result<ListingValidation, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+
+    let listing = sqlx::query_as::<_, ObjectPlatform>("SELECT * FROM object_platforms WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Platform association not found".to_string())?;
+
+    let (object, platform, photo_count) = load_listing_context(&mut conn, &listing).await?;
+    let listing = with_mapped_category(&mut conn, &listing, &object).await?;
+
+    Ok(check_listing(&listing, &object, &platform, photo_count))
+}
+
+#[tauri::command]
+pub async fn update_object_listing(
+    pool: State<'_, SqlitePool>,
+    id: i64,
+    request: UpdateObjectListing,
+) -> Result<ObjectPlatform, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let mut listing = sqlx::query_as::<_, ObjectPlatform>("SELECT * FROM object_platforms WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Platform association not found".to_string())?;
+
+    let was_listed = listing.status == "listed";
+
+    // Apply the requested changes on top of the current values
+    if request.platform_url.is_some() {
+        listing.platform_url = request.platform_url;
+    }
+    if let Some(status) = request.status {
+        listing.status = status;
+    }
+    if request.title.is_some() {
+        listing.title = request.title;
+    }
+    if request.description.is_some() {
+        listing.description = request.description;
+    }
+    if request.price.is_some() {
+        listing.price = request.price;
+    }
+    if let Some(currency) = request.currency {
+        listing.currency = Some(currency.trim().to_uppercase());
+    }
+    if request.condition.is_some() {
+        listing.condition = request.condition;
+    }
+    if request.shipping_profile.is_some() {
+        listing.shipping_profile = request.shipping_profile;
+    }
+    if request.platform_category_id.is_some() {
+        listing.platform_category_id = request.platform_category_id;
+    }
+    if let Some(specifics) = request.item_specifics {
+        if !specifics.is_object() {
+            return Err("Item specifics must be a JSON object".to_string());
+        }
+        listing.item_specifics = Some(specifics.to_string());
+    }
+
+    // The platform title limit is enforced on every save, the full rules only when publishing
+    if let Some(title) = &listing.title {
+        let (_, platform, _) = load_listing_context(&mut tx, &listing).await?;
+        if let Some(error) = check_title_length(title, &platform) {
+            return Err(error);
+        }
+    }
+
+    if listing.status == "listed" && !was_listed {
+        ensure_listing_publishable(&mut tx, &listing).await?;
+    }
+
+    sqlx::query(
+        "UPDATE object_platforms SET
+            platform_url = ?, status = ?, title = ?, description = ?, price = ?, currency = ?,
+            condition = ?, shipping_profile = ?, platform_category_id = ?, item_specifics = ?,
+            listed_at = CASE WHEN ? = 'listed' AND listed_at IS NULL THEN CURRENT_TIMESTAMP ELSE listed_at END,
+            updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?"
+    )
+    .bind(&listing.platform_url)
+    .bind(&listing.status)
+    .bind(&listing.title)
+    .bind(&listing.description)
+    .bind(listing.price)
+    .bind(&listing.currency)
+    .bind(&listing.condition)
+    .bind(&listing.shipping_profile)
+    .bind(&listing.platform_category_id)
+    .bind(&listing.item_specifics)
+    .bind(&listing.status)
+    .bind(id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let updated = sqlx::query_as::<_, ObjectPlatform>("SELECT * FROM object_platforms WHERE id = ?")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object() -> Object {
+        serde_json::from_value(json!({
+            "id": 1, "name": "Brass lamp", "description": "Working brass lamp", "quantity": 1,
+            "created_at": "", "updated_at": ""
+        }))
+        .unwrap()
+    }
+
+    fn platform() -> Platform {
+        serde_json::from_value(json!({
+            "id": 1, "name": "eBay", "title_max_length": 10, "description_max_length": 100, "max_photos": 2,
+            "requires_condition": true, "requires_category_id": true, "created_at": ""
+        }))
+        .unwrap()
+    }
+
+    fn listing() -> ObjectPlatform {
+        serde_json::from_value(json!({
+            "id": 1, "object_id": 1, "platform_id": 1, "status": "draft", "price": 12.5, "currency": "EUR",
+            "condition": "good", "platform_category_id": "1234", "created_at": "", "updated_at": ""
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn valid_listing() {
+        let validation = check_listing(&listing(), &object(), &platform(), 1);
+        assert!(validation.valid, "{:?}", validation.errors);
+        assert!(validation.warnings.is_empty());
+    }
+
+    #[test]
+    fn listing_overrides_the_object() {
+        let mut listing = listing();
+        listing.title = Some("Lamp".into());
+        listing.description = Some("  ".into());
+        assert_eq!(effective_title(&listing, &object()), "Lamp");
+        assert_eq!(effective_description(&listing, &object()), "Working brass lamp");
+    }
+
+    #[test]
+    fn platform_rules() {
+        let mut listing = listing();
+        listing.title = Some("A title too long".into());
+        listing.price = Some(0.0);
+        listing.currency = Some("eur".into());
+        listing.condition = None;
+        listing.platform_category_id = Some(" ".into());
+        listing.item_specifics = Some("[1]".into());
+
+        let validation = check_listing(&listing, &object(), &platform(), 3);
+        assert!(!validation.valid);
+        assert_eq!(validation.errors.len(), 6, "{:?}", validation.errors);
+        assert!(validation.errors[0].starts_with("Title is 16 characters long"));
+        assert_eq!(validation.warnings, ["3 photos associated, only the first 2 will be used on eBay"]);
+    }
+
+    #[test]
+    fn missing_content_warnings() {
+        let mut object = object();
+        object.description = None;
+        let validation = check_listing(&listing(), &object, &platform(), 0);
+        assert!(validation.valid);
+        assert_eq!(validation.warnings, ["Description is empty", "No photos are associated with this object"]);
+    }
+}
@@ -0,0 +1,310 @@
+use crate::models::{CreateLocation, Location, Object, ObjectLocationMove, UpdateLocation};
+use sqlx::{SqliteConnection, SqlitePool};
+use tauri::State;
+
+const LOCATION_KINDS: &[&str] = &["warehouse", "room", "shelf", "box", "other"];
+
+// ========== LOCATIONS COMMANDS ==========
+
+fn check_location_kind(kind: &str) -> Result<(), String> {
+    if LOCATION_KINDS.contains(&kind) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unknown location kind '{}' (expected one of: {})",
+            kind,
+            LOCATION_KINDS.join(", ")
+        ))
+    }
+}
+
+async fn fetch_location(conn: &mut SqliteConnection, id: i64) -> Result<Location, String> {
+    sqlx::query_as::<_, Location>("SELECT * FROM locations WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Location not found".to_string())
+}
+
+// Full path of a location, e.g. "Warehouse / Shelf A / Box 3"
+pub(crate) async fn location_path(conn: &mut SqliteConnection, id: i64) -> Result<String, String> {
+    let names = sqlx::query_scalar::<_, String>(
+        "WITH RECURSIVE ancestors(id, name, parent_id, depth) AS (
+            SELECT id, name, parent_id, 0 FROM locations WHERE id = ?
+            UNION ALL
+            SELECT l.id, l.name, l.parent_id, a.depth + 1
+            FROM locations l JOIN ancestors a ON l.id = a.parent_id
+         )
+         SELECT name FROM ancestors ORDER BY depth DESC"
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(names.join(" / "))
+}
+
+// A location can't have the same name as one of its siblings
+async fn check_sibling_name(
+    conn: &mut SqliteConnection,
+    name: &str,
+    parent_id: Option<i64>,
+    exclude_id: Option<i64>,
+) -> Result<(), String> {
+    let exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM locations
+         WHERE name = ? COLLATE NOCASE AND parent_id IS ? AND id IS NOT ?"
+    )
+    .bind(name)
+    .bind(parent_id)
+    .bind(exclude_id)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if exists > 0 {
+        Err(format!("A location named '{}' already exists here", name))
+    } else {
+        Ok(())
+    }
+}
+
+#[tauri::command]
+pub async fn list_locations(pool: State<'_, SqlitePool>) -> Result<Vec<Location>, String> {
+    sqlx::query_as::<_, Location>("SELECT * FROM locations ORDER BY name ASC")
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_location_path(pool: State<'_, SqlitePool>, id: i64) -> Result<String, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    fetch_location(&mut conn, id).await?;
+    location_path(&mut conn, id).await
+}
+
+#[tauri::command]
+pub async fn create_location(
+    pool: State<'_, SqlitePool>,
+    location: CreateLocation,
+) -> Result<Location, String> {
+    let name = location.name.trim();
+    if name.is_empty() {
+        return Err("Location name is required".to_string());
+    }
+    let kind = location.kind.unwrap_or_else(|| "box".to_string());
+    check_location_kind(&kind)?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    if let Some(parent_id) = location.parent_id {
+        fetch_location(&mut tx, parent_id).await?;
+    }
+    check_sibling_name(&mut tx, name, location.parent_id, None).await?;
+
+    let result = sqlx::query("INSERT INTO locations (name, kind, parent_id, notes) VALUES (?, ?, ?, ?)")
+        .bind(name)
+        .bind(&kind)
+        .bind(location.parent_id)
+        .bind(&location.notes)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let created = fetch_location(&mut tx, result.last_insert_rowid()).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(created)
+}
+
+#[tauri::command]
+pub async fn update_location(
+    pool: State<'_, SqlitePool>,
+    id: i64,
+    location: UpdateLocation,
+) -> Result<Location, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let mut current = fetch_location(&mut tx, id).await?;
+
+    if let Some(name) = location.name {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err("Location name is required".to_string());
+        }
+        current.name = name;
+    }
+    if let Some(kind) = location.kind {
+        check_location_kind(&kind)?;
+        current.kind = kind;
+    }
+    if location.notes.is_some() {
+        current.notes = location.notes;
+    }
+    if location.clear_parent.unwrap_or(false) {
+        current.parent_id = None;
+    } else if let Some(parent_id) = location.parent_id {
+        // The new parent can't be the location itself or one of its sub-locations
+        let is_descendant = sqlx::query_scalar::<_, i64>(
+            "WITH RECURSIVE descendants(id) AS (
+                SELECT ?
+                UNION ALL
+                SELECT l.id FROM locations l JOIN descendants d ON l.parent_id = d.id
+             )
+             SELECT COUNT(*) FROM descendants WHERE id = ?"
+        )
+        .bind(id)
+        .bind(parent_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if is_descendant > 0 {
+            return Err("A location can't be moved inside itself".to_string());
+        }
+        fetch_location(&mut tx, parent_id).await?;
+        current.parent_id = Some(parent_id);
+    }
+
+    check_sibling_name(&mut tx, &current.name, current.parent_id, Some(id)).await?;
+
+    sqlx::query(
+        "UPDATE locations SET name = ?, kind = ?, parent_id = ?, notes = ?, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?"
+    )
+    .bind(&current.name)
+    .bind(&current.kind)
+    .bind(current.parent_id)
+    .bind(&current.notes)
+    .bind(id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let updated = fetch_location(&mut tx, id).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(updated)
+}
+
+// Only empty locations (no sub-location, no object) can be deleted
+#[tauri::command]
+pub async fn delete_location(pool: State<'_, SqlitePool>, id: i64) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    fetch_location(&mut tx, id).await?;
+
+    let children = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM locations WHERE parent_id = ?")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    if children > 0 {
+        return Err(format!("This location still contains {} sub-location(s)", children));
+    }
+
+    let objects = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM objects WHERE location_id = ?")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    if objects > 0 {
+        return Err(format!("This location still contains {} object(s)", objects));
+    }
+
+    sqlx::query("DELETE FROM locations WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// Move objects to a location (None to remove them from their location), recording the move
+// in the history of each object. Returns the number of objects moved.
+#[tauri::command]
+pub async fn move_objects_to_location(
+    pool: State<'_, SqlitePool>,
+    object_ids: Vec<i64>,
+    location_id: Option<i64>,
+    note: Option<String>,
+) -> Result<i32, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let to_path = match location_id {
+        Some(location_id) => {
+            fetch_location(&mut tx, location_id).await?;
+            Some(location_path(&mut tx, location_id).await?)
+        }
+        None => None,
+    };
+
+    let mut moved = 0;
+
+    for object_id in object_ids {
+        let object = sqlx::query_as::<_, Object>("SELECT * FROM objects WHERE id = ?")
+            .bind(object_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Object {} not found", object_id))?;
+
+        if object.location_id == location_id {
+            continue;
+        }
+
+        let from_path = match object.location_id {
+            Some(from_id) => Some(location_path(&mut tx, from_id).await?),
+            None => None,
+        };
+
+        sqlx::query("UPDATE objects SET location_id = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(location_id)
+            .bind(object_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        sqlx::query(
+            "INSERT INTO object_location_history (object_id, from_location_id, to_location_id, from_path, to_path, note)
+             VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(object_id)
+        .bind(object.location_id)
+        .bind(location_id)
+        .bind(&from_path)
+        .bind(&to_path)
+        .bind(&note)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        moved += 1;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(moved)
+}
+
+#[tauri::command]
+pub async fn get_object_location_history(
+    pool: State<'_, SqlitePool>,
+    object_id: i64,
+) -> Result<Vec<ObjectLocationMove>, String> {
+    sqlx::query_as::<_, ObjectLocationMove>(
+        "SELECT * FROM object_location_history WHERE object_id = ? ORDER BY moved_at DESC, id DESC"
+    )
+    .bind(object_id)
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -1,4 +1,4 @@
-use crate::models::{Object, CreateObject, UpdateObject, Photo, Platform, CreatePlatform, UpdatePlatform, ObjectPhoto, ObjectPlatform, Category, CreateCategory, Folder, TextFile};
+use crate::models::{Object, CreateObject, UpdateObject, SearchObjects, Photo, Platform, CreatePlatform, UpdatePlatform, ObjectPhoto, ObjectPlatform, Category, CreateCategory, Folder, TextFile};
 use sqlx::SqlitePool;
 use tauri::State;
 use serde::{Deserialize, Serialize};
@@ -7,6 +7,39 @@ use std::fs;
 use base64::{Engine as _, engine::general_purpose};
 use chrono::Local;
 
+use journal::JournalStep;
+use paths::LibraryPaths;
+
+pub mod listings;
+pub mod templates;
+pub mod exports;
+pub mod imports;
+pub mod skus;
+pub mod locations;
+pub mod bundles;
+pub mod sales;
+pub mod attributes;
+pub mod categories;
+pub mod trash;
+pub mod journal;
+pub mod history;
+pub mod transfers;
+pub mod fileops;
+pub mod paths;
+pub mod library;
+pub mod sync;
+pub mod ignore;
+pub mod folders;
+pub mod descriptions;
+
+// Folder under the root folder where listing packages are exported (never scanned)
+const EXPORTS_FOLDER: &str = "EXPORTS";
+
+// Folders under the root folder that must not be imported into the library
+fn is_excluded_dir(path: &Path, root_path: &Path) -> bool {
+    path == root_path.join(EXPORTS_FOLDER)
+}
+
 // ========== OBJECTS COMMANDS ==========
 
 #[tauri::command]
@@ -17,6 +50,89 @@ pub async fn list_objects(pool: State<'_, SqlitePool>) -> Result<Vec<Object>, St
         .map_err(|e| e.to_string())
 }
 
+// Search objects by text (name, description, SKU, brand), category, storage location,
+// condition, brand and custom attribute values
+#[tauri::command]
+pub async fn search_objects(
+    pool: State<'_, SqlitePool>,
+    filters: SearchObjects,
+) -> Result<Vec<Object>, String> {
+    let mut query = sqlx::QueryBuilder::<sqlx::Sqlite>::new("SELECT * FROM objects WHERE 1 = 1");
+
+    if let Some(text) = filters.query.as_ref().map(|q| q.trim()).filter(|q| !q.is_empty()) {
+        let pattern = format!("%{}%", text);
+        query.push(" AND (name LIKE ").push_bind(pattern.clone());
+        query.push(" OR description LIKE ").push_bind(pattern.clone());
+        query.push(" OR sku LIKE ").push_bind(pattern.clone());
+        query.push(" OR external_sku LIKE ").push_bind(pattern.clone());
+        query.push(" OR brand LIKE ").push_bind(pattern);
+        query.push(")");
+    }
+    if let Some(category_id) = filters.category_id {
+        query.push(" AND category_id = ").push_bind(category_id);
+    }
+    if let Some(location_id) = filters.location_id {
+        query.push(
+            " AND location_id IN (
+                WITH RECURSIVE sublocations(id) AS (SELECT "
+        );
+        query.push_bind(location_id);
+        query.push(
+            " UNION ALL SELECT l.id FROM locations l JOIN sublocations s ON l.parent_id = s.id WHERE "
+        );
+        query.push_bind(filters.include_sublocations.unwrap_or(true));
+        query.push(") SELECT id FROM sublocations)");
+    }
+    if filters.unlocated.unwrap_or(false) {
+        query.push(" AND location_id IS NULL");
+    }
+    if let Some(condition) = filters.condition.as_ref().filter(|c| !c.is_empty()) {
+        query.push(" AND condition = ").push_bind(condition.clone());
+    }
+    if let Some(brand) = filters.brand.as_ref().map(|b| b.trim()).filter(|b| !b.is_empty()) {
+        query.push(" AND brand = ").push_bind(brand.to_string()).push(" COLLATE NOCASE");
+    }
+
+    if let Some(attribute_filters) = &filters.attributes {
+        let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+
+        for filter in attribute_filters {
+            let definition = attributes::fetch_attribute_definition(&mut conn, filter.attribute_id).await?;
+
+            query.push(
+                " AND EXISTS (SELECT 1 FROM object_attributes oa WHERE oa.object_id = objects.id AND oa.attribute_id = "
+            );
+            query.push_bind(filter.attribute_id);
+
+            if let Some(value) = filter.value.as_ref().filter(|v| !v.is_null()) {
+                let value = attributes::normalize_attribute_value(&definition, value)?;
+                if definition.attribute_type == "text" {
+                    query.push(" AND oa.value LIKE ").push_bind(format!("%{}%", value));
+                } else if definition.attribute_type == "number" {
+                    query.push(" AND CAST(oa.value AS REAL) = ").push_bind(value.parse::<f64>().unwrap_or_default());
+                } else {
+                    query.push(" AND oa.value = ").push_bind(value);
+                }
+            }
+            if let Some(min) = filter.min {
+                query.push(" AND CAST(oa.value AS REAL) >= ").push_bind(min);
+            }
+            if let Some(max) = filter.max {
+                query.push(" AND CAST(oa.value AS REAL) <= ").push_bind(max);
+            }
+            query.push(")");
+        }
+    }
+
+    query.push(" ORDER BY created_at DESC");
+
+    query
+        .build_query_as::<Object>()
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_object(pool: State<'_, SqlitePool>, id: i64) -> Result<Object, String> {
     sqlx::query_as::<_, Object>("SELECT * FROM objects WHERE id = ?")
@@ -32,25 +148,66 @@ pub async fn create_object(
     pool: State<'_, SqlitePool>,
     object: CreateObject,
 ) -> Result<Object, String> {
+    if object.quantity.is_some_and(|q| q < 0) {
+        return Err("Quantity can't be negative".to_string());
+    }
+    if let Some(condition) = &object.condition {
+        attributes::check_condition(condition)?;
+    }
+    if let Some(unit) = &object.dimension_unit {
+        attributes::check_dimension_unit(unit)?;
+    }
+    attributes::check_dimension("Length", object.length)?;
+    attributes::check_dimension("Width", object.width)?;
+    attributes::check_dimension("Height", object.height)?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let sku = object.sku.as_ref().map(|s| s.trim()).filter(|s| !s.is_empty());
+
     let result = sqlx::query(
-        "INSERT INTO objects (name, description, year, weight, category_id) VALUES (?, ?, ?, ?, ?)"
+        "INSERT INTO objects (name, description, year, weight, category_id, external_sku, sku, quantity,
+            condition, brand, length, width, height, dimension_unit)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
     )
     .bind(&object.name)
     .bind(&object.description)
     .bind(object.year)
     .bind(object.weight)
     .bind(object.category_id)
-    .execute(pool.inner())
+    .bind(&object.external_sku)
+    .bind(sku)
+    .bind(object.quantity.unwrap_or(1))
+    .bind(object.condition.as_ref().filter(|c| !c.is_empty()))
+    .bind(&object.brand)
+    .bind(object.length)
+    .bind(object.width)
+    .bind(object.height)
+    .bind(object.dimension_unit.as_deref().unwrap_or("cm"))
+    .execute(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
 
     let id = result.last_insert_rowid();
 
-    sqlx::query_as::<_, Object>("SELECT * FROM objects WHERE id = ?")
+    if let Some(values) = &object.attributes {
+        attributes::save_object_attributes(&mut tx, id, values).await?;
+    }
+
+    // Objects created without a SKU get one from the SKU generator
+    if sku.is_none() {
+        skus::assign_sku(&mut tx, id, false).await?;
+    }
+
+    let created = sqlx::query_as::<_, Object>("SELECT * FROM objects WHERE id = ?")
         .bind(id)
-        .fetch_one(pool.inner())
+        .fetch_one(&mut *tx)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(created)
 }
 
 #[tauri::command]
@@ -59,35 +216,125 @@ pub async fn update_object(
     id: i64,
     object: UpdateObject,
 ) -> Result<Object, String> {
-    let mut query = String::from("UPDATE objects SET updated_at = CURRENT_TIMESTAMP");
+    let mut query = sqlx::QueryBuilder::<sqlx::Sqlite>::new("UPDATE objects SET updated_at = CURRENT_TIMESTAMP");
 
     if let Some(name) = &object.name {
-        query.push_str(", name = '");
-        query.push_str(&name.replace("'", "''"));
-        query.push('\'');
+        query.push(", name = ").push_bind(name.clone());
     }
     if let Some(desc) = &object.description {
-        query.push_str(", description = '");
-        query.push_str(&desc.replace("'", "''"));
-        query.push('\'');
+        query.push(", description = ").push_bind(desc.clone());
     }
     if let Some(year) = object.year {
-        query.push_str(&format!(", year = {}", year));
+        query.push(", year = ").push_bind(year);
     }
     if let Some(weight) = object.weight {
-        query.push_str(&format!(", weight = {}", weight));
+        query.push(", weight = ").push_bind(weight);
     }
     if let Some(category_id) = object.category_id {
-        query.push_str(&format!(", category_id = {}", category_id));
+        query.push(", category_id = ").push_bind(category_id);
+    }
+    if let Some(external_sku) = &object.external_sku {
+        query.push(", external_sku = ").push_bind(external_sku.clone());
+    }
+    if let Some(quantity) = object.quantity {
+        if quantity < 0 {
+            return Err("Quantity can't be negative".to_string());
+        }
+        query.push(", quantity = ").push_bind(quantity);
+    }
+    // Cleared SKUs and conditions are stored as NULL (as by create_object): the unique index
+    // allows any number of NULL SKUs
+    if let Some(sku) = &object.sku {
+        query.push(", sku = ").push_bind(Some(sku.trim().to_string()).filter(|s| !s.is_empty()));
+    }
+    if let Some(condition) = &object.condition {
+        attributes::check_condition(condition)?;
+        query.push(", condition = ").push_bind(Some(condition.clone()).filter(|c| !c.is_empty()));
+    }
+    if let Some(brand) = &object.brand {
+        query.push(", brand = ").push_bind(brand.clone());
+    }
+    let clear_dimensions = object.clear_dimensions.unwrap_or(false);
+    for (column, name, value) in [
+        ("length", "Length", object.length),
+        ("width", "Width", object.width),
+        ("height", "Height", object.height),
+    ] {
+        attributes::check_dimension(name, value)?;
+        if value.is_some() || clear_dimensions {
+            query.push(format!(", {} = ", column)).push_bind(value);
+        }
+    }
+    if let Some(unit) = &object.dimension_unit {
+        attributes::check_dimension_unit(unit)?;
+        query.push(", dimension_unit = ").push_bind(unit.clone());
     }
 
-    query.push_str(&format!(" WHERE id = {}", id));
+    query.push(" WHERE id = ").push_bind(id);
 
-    sqlx::query(&query)
-        .execute(pool.inner())
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let object_before = trash::snapshot_rows(&mut tx, "objects", "id", &[id]).await?;
+    let attributes_before = trash::snapshot_rows(&mut tx, "object_attributes", "object_id", &[id]).await?;
+    let description_before = sqlx::query_scalar::<_, Option<String>>("SELECT description FROM objects WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .flatten();
+
+    query
+        .build()
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Values of attributes specific to the previous category no longer apply
+    if let Some(category_id) = object.category_id {
+        let category_ids = categories::category_ancestor_ids(&mut tx, category_id).await?;
+        let definitions = sqlx::query_as::<_, (i64, i64)>(
+            "SELECT ad.id, ad.category_id FROM object_attributes oa
+             INNER JOIN attribute_definitions ad ON ad.id = oa.attribute_id
+             WHERE oa.object_id = ? AND ad.category_id IS NOT NULL"
+        )
+        .bind(id)
+        .fetch_all(&mut *tx)
         .await
         .map_err(|e| e.to_string())?;
 
+        for (attribute_id, attribute_category_id) in definitions {
+            if !category_ids.contains(&attribute_category_id) {
+                sqlx::query("DELETE FROM object_attributes WHERE object_id = ? AND attribute_id = ?")
+                    .bind(id)
+                    .bind(attribute_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    if let Some(values) = &object.attributes {
+        attributes::save_object_attributes(&mut tx, id, values).await?;
+    }
+
+    // Keep the description.txt of the folders linked to the object in sync
+    let root = paths::library_root(&mut *tx).await?;
+    descriptions::object_saved(&mut tx, &root, id, description_before.as_deref()).await?;
+
+    let steps = vec![
+        JournalStep::rows("id", &[id], object_before, trash::snapshot_rows(&mut tx, "objects", "id", &[id]).await?),
+        JournalStep::rows(
+            "object_id",
+            &[id],
+            attributes_before,
+            trash::snapshot_rows(&mut tx, "object_attributes", "object_id", &[id]).await?,
+        ),
+    ];
+    journal::record_operation(&mut tx, "update_object", &format!("Edit object #{}", id), steps).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
     get_object(pool, id).await
 }
 
@@ -110,10 +357,14 @@ pub async fn delete_object(pool: State<'_, SqlitePool>, id: i64) -> Result<(), S
 
 #[tauri::command]
 pub async fn list_photos(pool: State<'_, SqlitePool>) -> Result<Vec<Photo>, String> {
-    sqlx::query_as::<_, Photo>("SELECT * FROM photos ORDER BY created_at DESC")
+    let root = paths::library_root(pool.inner()).await?;
+
+    let photos = sqlx::query_as::<_, Photo>("SELECT * FROM photos ORDER BY created_at DESC")
         .fetch_all(pool.inner())
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    Ok(photos.resolve_paths(&root))
 }
 
 #[derive(Deserialize)]
@@ -134,8 +385,9 @@ pub async fn scan_photos(
 
     let mut imported_count = 0;
     let mut errors = vec![];
+    let rules = ignore::IgnoreRules::load(pool.inner()).await?;
 
-    scan_directory_recursive(pool.inner(), source_path, source_path, &mut imported_count, &mut errors)
+    scan_directory_recursive(pool.inner(), source_path, source_path, &rules, &mut imported_count, &mut errors)
         .await
         .map_err(|e| e.to_string())?;
 
@@ -149,6 +401,7 @@ fn scan_directory_recursive<'a>(
     pool: &'a SqlitePool,
     path: &'a Path,
     root_path: &'a Path,  // Added root path to limit hierarchy
+    rules: &'a ignore::IgnoreRules,
     count: &'a mut i32,
     errors: &'a mut Vec<String>,
 ) -> std::pin::Pin<Box<dyn std::future::Future<Output = std::io::Result<()>> + Send + 'a>> {
@@ -159,7 +412,12 @@ fn scan_directory_recursive<'a>(
                 let path = entry.path();
 
                 if path.is_dir() {
-                    scan_directory_recursive(pool, &path, root_path, count, errors).await?;
+                    if rules.is_ignored(root_path, &path, true) {
+                        continue;
+                    }
+                    scan_directory_recursive(pool, &path, root_path, rules, count, errors).await?;
+                } else if (is_image_file(&path) || is_text_file(&path)) && rules.skips_file(root_path, &path) {
+                    continue;
                 } else if is_image_file(&path) {
                     if let Err(e) = import_photo(pool, &path, root_path).await {
                         errors.push(format!("{}: {}", path.display(), e));
@@ -215,10 +473,13 @@ async fn import_photo(pool: &SqlitePool, path: &Path, root_path: &Path) -> Resul
     let root_path_str = root_path.to_string_lossy().to_string();
     let folder_id = ensure_folder_in_db(pool, &folder_path, &root_path_str).await
         .map_err(|e| format!("Failed to ensure folder in DB: {}", e))?;
+    let file_path = paths::to_stored(&paths::library_root(pool).await?, &file_path);
+    // Recorded to find the photo again if it is moved outside the application
+    let content_hash = fileops::file_hash(path).ok();
 
     sqlx::query(
-        "INSERT OR IGNORE INTO photos (file_path, original_path, file_name, file_size, width, height, folder_id)
-         VALUES (?, ?, ?, ?, ?, ?, ?)"
+        "INSERT OR IGNORE INTO photos (file_path, original_path, file_name, file_size, width, height, folder_id, content_hash)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
     )
     .bind(&file_path)
     .bind(&file_path)
@@ -227,43 +488,75 @@ async fn import_photo(pool: &SqlitePool, path: &Path, root_path: &Path) -> Resul
     .bind(width)
     .bind(height)
     .bind(folder_id)
+    .bind(content_hash)
     .execute(pool)
     .await?;
 
     Ok(())
 }
 
+// Move a photo to the trash (with its object associations, so that it can be restored)
 #[tauri::command]
 pub async fn delete_photo(pool: State<'_, SqlitePool>, photo_id: i64) -> Result<(), String> {
-    // Get the file path before deleting from the DB
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let root = paths::library_root(&mut *tx).await?;
+
     let photo = sqlx::query_as::<_, Photo>("SELECT * FROM photos WHERE id = ?")
         .bind(photo_id)
-        .fetch_optional(pool.inner())
+        .fetch_optional(&mut *tx)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Photo not found".to_string())?
+        .resolve_paths(&root);
 
-    if let Some(photo) = photo {
-        // Delete the physical file
-        if Path::new(&photo.file_path).exists() {
-            fs::remove_file(&photo.file_path)
-                .map_err(|e| format!("Failed to delete file: {}", e))?;
-        }
+    let records = vec![
+        trash::snapshot_rows(&mut tx, "photos", "id", &[photo.id]).await?,
+        trash::snapshot_rows(&mut tx, "object_photos", "photo_id", &[photo.id]).await?,
+    ];
 
-        // Delete from the database
-        let result = sqlx::query("DELETE FROM photos WHERE id = ?")
+    // The file goes to the trash before the rows are removed
+    let original_path = Path::new(&photo.file_path);
+    let trash_path = trash::trash_if_exists(original_path)?;
+
+    let outcome: Result<(), String> = async {
+        sqlx::query("DELETE FROM photos WHERE id = ?")
             .bind(photo_id)
-            .execute(pool.inner())
+            .execute(&mut *tx)
             .await
             .map_err(|e| e.to_string())?;
 
-        if result.rows_affected() == 0 {
-            Err("Photo not found in database".to_string())
-        } else {
-            Ok(())
+        let entry_id = trash::add_trash_entry(
+            &mut tx,
+            "photo",
+            &photo.file_name,
+            Some(original_path),
+            trash_path.as_deref(),
+            &records,
+        )
+        .await?;
+        journal::record_operation(
+            &mut tx,
+            "delete_photo",
+            &format!("Delete photo '{}'", photo.file_name),
+            vec![JournalStep::Trash { entry_id, restored: None }],
+        )
+        .await
+    }
+    .await;
+
+    let outcome = match outcome {
+        Ok(()) => tx.commit().await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = outcome {
+        if let Some(trash_path) = &trash_path {
+            let _ = trash::move_path(trash_path, original_path);
         }
-    } else {
-        Err("Photo not found".to_string())
+        return Err(e);
     }
+
+    Ok(())
 }
 
 #[derive(Deserialize)]
@@ -321,6 +614,7 @@ pub async fn toggle_main_photo(pool: State<'_, SqlitePool>, photo_id: i64) -> Re
     Ok(())
 }
 
+// Move a folder and everything inside it to the trash
 #[tauri::command]
 pub async fn delete_folder_recursive(
     pool: State<'_, SqlitePool>,
@@ -332,89 +626,68 @@ pub async fn delete_folder_recursive(
         return Err("Folder does not exist".to_string());
     }
 
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let root = paths::library_root(&mut *tx).await?;
+
     // Get the folder from database
     let folder = sqlx::query_as::<_, Folder>("SELECT * FROM folders WHERE path = ?")
-        .bind(&request.folder_path)
-        .fetch_optional(pool.inner())
+        .bind(paths::to_stored(&root, &request.folder_path))
+        .fetch_optional(&mut *tx)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.to_string())?
+        .resolve_paths(&root);
 
     let folder = folder.ok_or_else(|| "Folder not found in database".to_string())?;
 
-    // Get all photos in this folder and its subfolders using recursive CTE
-    // This ensures we only delete photos that belong to this folder tree
-    let photos = sqlx::query_as::<_, Photo>(
-        "WITH RECURSIVE folder_tree AS (
-            SELECT id FROM folders WHERE id = ?
-            UNION ALL
-            SELECT f.id FROM folders f
-            INNER JOIN folder_tree ft ON f.parent_id = ft.id
+    // Keep the rows of the folder tree with the trash entry so that it can be restored
+    let records = trash::snapshot_path(&mut tx, &folder.path).await?;
+    let folders_removed = records.first().map_or(0, |r| r.rows.len());
+
+    // The folder goes to the trash before the rows are removed
+    let trash_path = trash::trash_if_exists(folder_path)?;
+
+    let outcome: Result<(i64, i64), String> = async {
+        let (deleted_count, _) = trash::delete_path_rows(&mut tx, &folder.path).await?;
+        let trash_entry_id = trash::add_trash_entry(
+            &mut tx,
+            "folder",
+            &folder.name,
+            Some(folder_path),
+            trash_path.as_deref(),
+            &records,
         )
-        SELECT p.* FROM photos p
-        WHERE p.folder_id IN (SELECT id FROM folder_tree)"
-    )
-        .bind(folder.id)
-        .fetch_all(pool.inner())
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let mut deleted_count = 0;
-    let mut errors = vec![];
-
-    // Delete each photo from the DB
-    for photo in &photos {
-        match sqlx::query("DELETE FROM photos WHERE id = ?")
-            .bind(photo.id)
-            .execute(pool.inner())
-            .await
-        {
-            Ok(_) => deleted_count += 1,
-            Err(e) => errors.push(format!("DB error for {}: {}", photo.file_name, e)),
-        }
-    }
-
-    // Delete the physical folder with all its contents
-    if let Err(e) = fs::remove_dir_all(folder_path) {
-        errors.push(format!("Failed to delete folder: {}", e));
-    }
-
-    // Get all folders in the tree (including the main folder and its descendants)
-    let all_folders = sqlx::query_as::<_, Folder>(
-        "WITH RECURSIVE folder_tree AS (
-            SELECT id, path FROM folders WHERE id = ?
-            UNION ALL
-            SELECT f.id, f.path FROM folders f
-            INNER JOIN folder_tree ft ON f.parent_id = ft.id
+        .await?;
+        journal::record_operation(
+            &mut tx,
+            "delete_folder",
+            &format!("Delete folder '{}'", folder.name),
+            vec![JournalStep::Trash { entry_id: trash_entry_id, restored: None }],
         )
-        SELECT f.* FROM folders f
-        WHERE f.id IN (SELECT id FROM folder_tree)"
-    )
-        .bind(folder.id)
-        .fetch_all(pool.inner())
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
+        Ok((deleted_count, trash_entry_id))
+    }
+    .await;
 
-    let mut folders_removed = 0;
-    for folder in all_folders {
-        let folder_fs_path = Path::new(&folder.path);
+    let outcome = match outcome {
+        Ok(counts) => tx.commit().await.map(|_| counts).map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
 
-        // Delete from DB if folder doesn't exist on filesystem
-        if !folder_fs_path.exists() {
-            match sqlx::query("DELETE FROM folders WHERE id = ?")
-                .bind(folder.id)
-                .execute(pool.inner())
-                .await
-            {
-                Ok(_) => folders_removed += 1,
-                Err(e) => errors.push(format!("Failed to delete folder {} from DB: {}", folder.id, e)),
+    let (deleted_count, trash_entry_id) = match outcome {
+        Ok(counts) => counts,
+        Err(e) => {
+            if let Some(trash_path) = &trash_path {
+                let _ = trash::move_path(trash_path, folder_path);
             }
+            return Err(e);
         }
-    }
+    };
 
     Ok(serde_json::json!({
         "deleted": deleted_count,
         "folders_removed": folders_removed,
-        "errors": errors
+        "trash_entry_id": trash_entry_id,
+        "errors": Vec::<String>::new()
     }))
 }
 
@@ -423,9 +696,11 @@ pub async fn delete_folder_recursive_db_only(
     pool: State<'_, SqlitePool>,
     request: DeleteFolderRequest,
 ) -> Result<serde_json::Value, String> {
+    let root = paths::library_root(pool.inner()).await?;
+
     // Get the folder from database
     let folder = sqlx::query_as::<_, Folder>("SELECT * FROM folders WHERE path = ?")
-        .bind(&request.folder_path)
+        .bind(paths::to_stored(&root, &request.folder_path))
         .fetch_optional(pool.inner())
         .await
         .map_err(|e| e.to_string())?;
@@ -513,13 +788,16 @@ pub async fn save_edited_photo(
     photo_id: i64,
     request: SaveEditedPhotoRequest,
 ) -> Result<Photo, String> {
+    let root = paths::library_root(pool.inner()).await?;
+
     // Get the photo from the DB
     let photo = sqlx::query_as::<_, Photo>("SELECT * FROM photos WHERE id = ?")
         .bind(photo_id)
         .fetch_optional(pool.inner())
         .await
         .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Photo not found".to_string())?;
+        .ok_or_else(|| "Photo not found".to_string())?
+        .resolve_paths(&root);
 
     // Decode the base64 data
     let image_data = general_purpose::STANDARD
@@ -575,14 +853,16 @@ pub async fn save_edited_photo(
 
         // Create a new entry in the DB
         let result = sqlx::query(
-            "INSERT INTO photos (file_path, original_path, file_name, file_size, width, height) VALUES (?, ?, ?, ?, ?, ?)"
+            "INSERT INTO photos (file_path, original_path, file_name, file_size, width, height, content_hash)
+             VALUES (?, ?, ?, ?, ?, ?, ?)"
         )
-        .bind(&target_path)
-        .bind(&target_original_path)
+        .bind(paths::to_stored(&root, &target_path))
+        .bind(paths::to_stored(&root, &target_original_path))
         .bind(&target_file_name)
         .bind(file_size)
         .bind(width)
         .bind(height)
+        .bind(fileops::file_hash(Path::new(&target_path)).ok())
         .execute(pool.inner())
         .await
         .map_err(|e| e.to_string())?;
@@ -594,6 +874,7 @@ pub async fn save_edited_photo(
             .bind(new_photo_id)
             .fetch_one(pool.inner())
             .await
+            .map(|photo| photo.resolve_paths(&root))
             .map_err(|e| e.to_string())
     } else {
         // Overwrite the original file
@@ -624,6 +905,7 @@ pub async fn save_edited_photo(
             .bind(photo_id)
             .fetch_one(pool.inner())
             .await
+            .map(|photo| photo.resolve_paths(&root))
             .map_err(|e| e.to_string())
     }
 }
@@ -647,6 +929,8 @@ pub async fn get_object_photos(
     pool: State<'_, SqlitePool>,
     object_id: i64,
 ) -> Result<Vec<PhotoWithAssociation>, String> {
+    let root = paths::library_root(pool.inner()).await?;
+
     sqlx::query_as::<_, PhotoWithAssociation>(
         "SELECT p.*, op.id as association_id, op.display_order FROM photos p
          INNER JOIN object_photos op ON p.id = op.photo_id
@@ -656,6 +940,7 @@ pub async fn get_object_photos(
     .bind(object_id)
     .fetch_all(pool.inner())
     .await
+    .map(|photos| photos.resolve_paths(&root))
     .map_err(|e| e.to_string())
 }
 
@@ -674,39 +959,74 @@ pub async fn associate_photo(
 ) -> Result<ObjectPhoto, String> {
     let display_order = request.display_order.unwrap_or(0);
 
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let before = trash::snapshot_rows(&mut tx, "object_photos", "object_id", &[object_id]).await?;
+
     sqlx::query(
         "INSERT INTO object_photos (object_id, photo_id, display_order) VALUES (?, ?, ?)"
     )
     .bind(object_id)
     .bind(request.photo_id)
     .bind(display_order)
-    .execute(pool.inner())
+    .execute(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
 
-    sqlx::query_as::<_, ObjectPhoto>(
+    let object_photo = sqlx::query_as::<_, ObjectPhoto>(
         "SELECT * FROM object_photos WHERE object_id = ? AND photo_id = ?"
     )
     .bind(object_id)
     .bind(request.photo_id)
-    .fetch_one(pool.inner())
+    .fetch_one(&mut *tx)
     .await
-    .map_err(|e| e.to_string())
+    .map_err(|e| e.to_string())?;
+
+    let after = trash::snapshot_rows(&mut tx, "object_photos", "object_id", &[object_id]).await?;
+    journal::record_operation(
+        &mut tx,
+        "associate_photo",
+        &format!("Associate photo #{} with object #{}", request.photo_id, object_id),
+        vec![JournalStep::rows("object_id", &[object_id], before, after)],
+    )
+    .await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(object_photo)
 }
 
 #[tauri::command]
 pub async fn dissociate_photo(pool: State<'_, SqlitePool>, id: i64) -> Result<(), String> {
-    let result = sqlx::query("DELETE FROM object_photos WHERE id = ?")
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let association = sqlx::query_as::<_, ObjectPhoto>("SELECT * FROM object_photos WHERE id = ?")
         .bind(id)
-        .execute(pool.inner())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Association not found".to_string())?;
+
+    let object_id = association.object_id;
+    let before = trash::snapshot_rows(&mut tx, "object_photos", "object_id", &[object_id]).await?;
+
+    sqlx::query("DELETE FROM object_photos WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
         .await
         .map_err(|e| e.to_string())?;
 
-    if result.rows_affected() == 0 {
-        Err("Association not found".to_string())
-    } else {
-        Ok(())
-    }
+    let after = trash::snapshot_rows(&mut tx, "object_photos", "object_id", &[object_id]).await?;
+    journal::record_operation(
+        &mut tx,
+        "dissociate_photo",
+        &format!("Dissociate photo #{} from object #{}", association.photo_id, object_id),
+        vec![JournalStep::rows("object_id", &[object_id], before, after)],
+    )
+    .await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(())
 }
 
 #[tauri::command]
@@ -718,6 +1038,8 @@ pub async fn set_main_photo_for_object(
     // Start a transaction to ensure atomicity
     let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
 
+    let before = trash::snapshot_rows(&mut tx, "object_photos", "object_id", &[object_id]).await?;
+
     // Get all photos for this object
     let photos = sqlx::query_as::<_, ObjectPhoto>(
         "SELECT * FROM object_photos WHERE object_id = ? ORDER BY display_order"
@@ -749,6 +1071,15 @@ pub async fn set_main_photo_for_object(
         }
     }
 
+    let after = trash::snapshot_rows(&mut tx, "object_photos", "object_id", &[object_id]).await?;
+    journal::record_operation(
+        &mut tx,
+        "set_main_photo_for_object",
+        &format!("Set photo #{} as main photo of object #{}", photo_id, object_id),
+        vec![JournalStep::rows("object_id", &[object_id], before, after)],
+    )
+    .await?;
+
     // Commit the transaction
     tx.commit().await.map_err(|e| e.to_string())?;
 
@@ -759,9 +1090,12 @@ pub async fn set_main_photo_for_object(
 
 #[tauri::command]
 pub async fn list_text_files(pool: State<'_, SqlitePool>) -> Result<Vec<TextFile>, String> {
+    let root = paths::library_root(pool.inner()).await?;
+
     sqlx::query_as::<_, TextFile>("SELECT * FROM text_files ORDER BY created_at DESC")
         .fetch_all(pool.inner())
         .await
+        .map(|text_files| text_files.resolve_paths(&root))
         .map_err(|e| e.to_string())
 }
 
@@ -770,13 +1104,16 @@ pub async fn get_text_file_content(
     pool: State<'_, SqlitePool>,
     file_id: i64,
 ) -> Result<String, String> {
+    let root = paths::library_root(pool.inner()).await?;
+
     // Get the text file from the database
     let text_file = sqlx::query_as::<_, TextFile>("SELECT * FROM text_files WHERE id = ?")
         .bind(file_id)
         .fetch_optional(pool.inner())
         .await
         .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Text file not found".to_string())?;
+        .ok_or_else(|| "Text file not found".to_string())?
+        .resolve_paths(&root);
 
     // Read the file content from the filesystem
     fs::read_to_string(&text_file.file_path)
@@ -789,13 +1126,20 @@ pub async fn save_text_file_content(
     file_id: i64,
     content: String,
 ) -> Result<(), String> {
+    let root = paths::library_root(pool.inner()).await?;
+
     // Get the text file from the database
     let text_file = sqlx::query_as::<_, TextFile>("SELECT * FROM text_files WHERE id = ?")
         .bind(file_id)
         .fetch_optional(pool.inner())
         .await
         .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Text file not found".to_string())?;
+        .ok_or_else(|| "Text file not found".to_string())?
+        .resolve_paths(&root);
+
+    // A description.txt linked to an object is copied to the object description
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    descriptions::check_file_save(&mut tx, &root, file_id, &content).await?;
 
     // Write the content to the filesystem
     fs::write(&text_file.file_path, content)
@@ -809,44 +1153,75 @@ pub async fn save_text_file_content(
     sqlx::query("UPDATE text_files SET file_size = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
         .bind(file_size)
         .bind(file_id)
-        .execute(pool.inner())
+        .execute(&mut *tx)
         .await
         .map_err(|e| e.to_string())?;
 
+    descriptions::file_saved(&mut tx, &root, file_id).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
     Ok(())
 }
 
+// Move a text file to the trash
 #[tauri::command]
 pub async fn delete_text_file(pool: State<'_, SqlitePool>, file_id: i64) -> Result<(), String> {
-    // Get the file path before deleting from the DB
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let root = paths::library_root(&mut *tx).await?;
+
     let text_file = sqlx::query_as::<_, TextFile>("SELECT * FROM text_files WHERE id = ?")
         .bind(file_id)
-        .fetch_optional(pool.inner())
+        .fetch_optional(&mut *tx)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Text file not found".to_string())?
+        .resolve_paths(&root);
 
-    if let Some(text_file) = text_file {
-        // Delete the physical file
-        if Path::new(&text_file.file_path).exists() {
-            fs::remove_file(&text_file.file_path)
-                .map_err(|e| format!("Failed to delete file: {}", e))?;
-        }
+    let records = vec![trash::snapshot_rows(&mut tx, "text_files", "id", &[text_file.id]).await?];
 
-        // Delete from the database
-        let result = sqlx::query("DELETE FROM text_files WHERE id = ?")
+    // The file goes to the trash before the row is removed
+    let original_path = Path::new(&text_file.file_path);
+    let trash_path = trash::trash_if_exists(original_path)?;
+
+    let outcome: Result<(), String> = async {
+        sqlx::query("DELETE FROM text_files WHERE id = ?")
             .bind(file_id)
-            .execute(pool.inner())
+            .execute(&mut *tx)
             .await
             .map_err(|e| e.to_string())?;
 
-        if result.rows_affected() == 0 {
-            Err("Text file not found in database".to_string())
-        } else {
-            Ok(())
+        let entry_id = trash::add_trash_entry(
+            &mut tx,
+            "text_file",
+            &text_file.file_name,
+            Some(original_path),
+            trash_path.as_deref(),
+            &records,
+        )
+        .await?;
+        journal::record_operation(
+            &mut tx,
+            "delete_text_file",
+            &format!("Delete text file '{}'", text_file.file_name),
+            vec![JournalStep::Trash { entry_id, restored: None }],
+        )
+        .await
+    }
+    .await;
+
+    let outcome = match outcome {
+        Ok(()) => tx.commit().await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = outcome {
+        if let Some(trash_path) = &trash_path {
+            let _ = trash::move_path(trash_path, original_path);
         }
-    } else {
-        Err("Text file not found".to_string())
+        return Err(e);
     }
+
+    Ok(())
 }
 
 #[tauri::command]
@@ -914,7 +1289,7 @@ pub async fn create_description_file(
         .map_err(|e| format!("Failed to ensure folder in database: {}", e))?;
 
     // Insert into database
-    let file_path_str = desc_file_path.to_string_lossy().to_string();
+    let file_path_str = paths::to_stored(&root_path, &desc_file_path.to_string_lossy());
     let result = sqlx::query(
         "INSERT INTO text_files (file_path, file_name, folder_id, file_size)
          VALUES (?, ?, ?, ?)"
@@ -927,12 +1302,18 @@ pub async fn create_description_file(
     .await
     .map_err(|e| e.to_string())?;
 
+    // Fill it with the description of the object linked to the folder
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    descriptions::sync_folder(&mut conn, &root_path, folder_id).await?;
+    drop(conn);
+
     // Retrieve the created text file
     let text_file = sqlx::query_as::<_, TextFile>("SELECT * FROM text_files WHERE id = ?")
         .bind(result.last_insert_rowid())
         .fetch_one(pool.inner())
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.to_string())?
+        .resolve_paths(&root_path);
 
     Ok(text_file)
 }
@@ -967,6 +1348,7 @@ async fn import_text_file(pool: &SqlitePool, path: &Path, root_path: &Path) -> R
     let root_path_str = root_path.to_string_lossy().to_string();
     let folder_id = ensure_folder_in_db(pool, &folder_path, &root_path_str).await
         .map_err(|e| format!("Failed to ensure folder in DB: {}", e))?;
+    let file_path = paths::to_stored(&paths::library_root(pool).await?, &file_path);
 
     sqlx::query(
         "INSERT OR IGNORE INTO text_files (file_path, file_name, file_size, folder_id)
@@ -1060,6 +1442,30 @@ pub async fn update_platform(
         updates.push("environment = ?");
         bindings.push(environment.clone());
     }
+    if let Some(title_max_length) = request.title_max_length {
+        updates.push("title_max_length = ?");
+        bindings.push(title_max_length.to_string());
+    }
+    if let Some(description_max_length) = request.description_max_length {
+        updates.push("description_max_length = ?");
+        bindings.push(description_max_length.to_string());
+    }
+    if let Some(max_photos) = request.max_photos {
+        updates.push("max_photos = ?");
+        bindings.push(max_photos.to_string());
+    }
+    if let Some(max_photo_dimension) = request.max_photo_dimension {
+        updates.push("max_photo_dimension = ?");
+        bindings.push(max_photo_dimension.to_string());
+    }
+    if let Some(requires_category_id) = request.requires_category_id {
+        updates.push("requires_category_id = ?");
+        bindings.push(if requires_category_id { "1" } else { "0" }.to_string());
+    }
+    if let Some(requires_condition) = request.requires_condition {
+        updates.push("requires_condition = ?");
+        bindings.push(if requires_condition { "1" } else { "0" }.to_string());
+    }
 
     if updates.is_empty() {
         return Err("No fields to update".to_string());
@@ -1131,6 +1537,9 @@ pub async fn add_object_to_platform(
 ) -> Result<ObjectPlatform, String> {
     let status = request.status.unwrap_or_else(|| "draft".to_string());
 
+    // Insert in a transaction so a listing that fails the platform rules is not kept
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
     let result = sqlx::query(
         "INSERT INTO object_platforms (object_id, platform_id, platform_url, status, listed_at)
          VALUES (?, ?, ?, ?, CASE WHEN ? = 'listed' THEN CURRENT_TIMESTAMP ELSE NULL END)"
@@ -1140,19 +1549,27 @@ pub async fn add_object_to_platform(
     .bind(&request.platform_url)
     .bind(&status)
     .bind(&status)
-    .execute(pool.inner())
+    .execute(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
 
     let id = result.last_insert_rowid();
 
-    sqlx::query_as::<_, ObjectPlatform>(
+    let object_platform = sqlx::query_as::<_, ObjectPlatform>(
         "SELECT * FROM object_platforms WHERE id = ?"
     )
     .bind(id)
-    .fetch_one(pool.inner())
+    .fetch_one(&mut *tx)
     .await
-    .map_err(|e| e.to_string())
+    .map_err(|e| e.to_string())?;
+
+    if object_platform.status == "listed" {
+        listings::ensure_listing_publishable(&mut tx, &object_platform).await?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(object_platform)
 }
 
 #[tauri::command]
@@ -1185,9 +1602,19 @@ pub async fn create_category(
     pool: State<'_, SqlitePool>,
     category: CreateCategory,
 ) -> Result<Category, String> {
+    if let Some(parent_id) = category.parent_id {
+        sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE id = ?")
+            .bind(parent_id)
+            .fetch_optional(pool.inner())
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Parent category not found".to_string())?;
+    }
+
     // Insert category in database
-    let result = sqlx::query("INSERT INTO categories (name) VALUES (?)")
+    let result = sqlx::query("INSERT INTO categories (name, parent_id) VALUES (?, ?)")
         .bind(&category.name)
+        .bind(category.parent_id)
         .execute(pool.inner())
         .await
         .map_err(|e| e.to_string())?;
@@ -1212,10 +1639,10 @@ pub async fn create_category(
             .map_err(|e| e.to_string())?;
 
         if let Some(categories_folder) = categories_folder {
-            // Create physical folder for the category
-            let category_folder_path = Path::new(&root_path)
-                .join("CATEGORIES")
-                .join(&category.name);
+            // Create physical folder for the category (inside the folder of its parent category)
+            let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+            let category_folder_path = categories::category_folder_path(&mut conn, id, &root_path).await?;
+            drop(conn);
 
             // Create the physical folder if it doesn't exist
             if !category_folder_path.exists() {
@@ -1227,13 +1654,24 @@ pub async fn create_category(
             let folder_path_str = category_folder_path
                 .to_str()
                 .ok_or_else(|| "Invalid path".to_string())?;
+            let folder_path_str = paths::to_stored(&root_path, folder_path_str);
+
+            let parent_folder_id = match category_folder_path.parent().and_then(|p| p.to_str()) {
+                Some(parent_path) => sqlx::query_scalar::<_, i64>("SELECT id FROM folders WHERE path = ?")
+                    .bind(paths::to_stored(&root_path, parent_path))
+                    .fetch_optional(pool.inner())
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .unwrap_or(categories_folder.id),
+                None => categories_folder.id,
+            };
 
             sqlx::query(
                 "INSERT OR IGNORE INTO folders (path, name, parent_id) VALUES (?, ?, ?)"
             )
-                .bind(folder_path_str)
+                .bind(&folder_path_str)
                 .bind(&category.name)
-                .bind(categories_folder.id)
+                .bind(parent_folder_id)
                 .execute(pool.inner())
                 .await
                 .map_err(|e| e.to_string())?;
@@ -1247,98 +1685,7 @@ pub async fn create_category(
         .map_err(|e| e.to_string())
 }
 
-#[tauri::command]
-pub async fn delete_category(pool: State<'_, SqlitePool>, id: i64) -> Result<(), String> {
-    // First, get the category to retrieve its name
-    let category = sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE id = ?")
-        .bind(id)
-        .fetch_optional(pool.inner())
-        .await
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Category not found".to_string())?;
-
-    // Get root folder path
-    let root_folder = sqlx::query_scalar::<_, String>(
-        "SELECT value FROM settings WHERE key = 'root_folder'"
-    )
-        .fetch_optional(pool.inner())
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if let Some(root_path) = root_folder {
-        // Build the category folder path
-        let category_folder_path = Path::new(&root_path)
-            .join("CATEGORIES")
-            .join(&category.name);
-
-        // If the physical folder exists, delete it recursively
-        if category_folder_path.exists() {
-            fs::remove_dir_all(&category_folder_path)
-                .map_err(|e| format!("Failed to delete category folder: {}", e))?;
-        }
-
-        // Delete the folder entry from database (and cascade delete photos)
-        let folder_path_str = category_folder_path.to_str().ok_or_else(|| "Invalid path".to_string())?;
-
-        // Get the folder from database
-        let folder = sqlx::query_as::<_, Folder>("SELECT * FROM folders WHERE path = ?")
-            .bind(folder_path_str)
-            .fetch_optional(pool.inner())
-            .await
-            .map_err(|e| e.to_string())?;
-
-        if let Some(folder) = folder {
-            // Delete all photos in this folder and its subfolders from database
-            // Get all folders in the tree (including the main folder and its descendants)
-            let all_folders = sqlx::query_as::<_, Folder>(
-                "WITH RECURSIVE folder_tree AS (
-                    SELECT id, path FROM folders WHERE id = ?
-                    UNION ALL
-                    SELECT f.id, f.path FROM folders f
-                    INNER JOIN folder_tree ft ON f.parent_id = ft.id
-                )
-                SELECT * FROM folders WHERE id IN (SELECT id FROM folder_tree)"
-            )
-                .bind(folder.id)
-                .fetch_all(pool.inner())
-                .await
-                .map_err(|e| e.to_string())?;
-
-            // Delete all photos from these folders
-            for folder in &all_folders {
-                sqlx::query("DELETE FROM photos WHERE folder_id = ?")
-                    .bind(folder.id)
-                    .execute(pool.inner())
-                    .await
-                    .map_err(|e| e.to_string())?;
-            }
-
-            // Delete all folders from database
-            for folder in &all_folders {
-                sqlx::query("DELETE FROM folders WHERE id = ?")
-                    .bind(folder.id)
-                    .execute(pool.inner())
-                    .await
-                    .map_err(|e| e.to_string())?;
-            }
-        }
-    }
-
-    // Finally, delete the category from database
-    let result = sqlx::query("DELETE FROM categories WHERE id = ?")
-        .bind(id)
-        .execute(pool.inner())
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if result.rows_affected() == 0 {
-        Err("Category not found".to_string())
-    } else {
-        Ok(())
-    }
-}
-
-// ========== PHOTO IMPORT COMMANDS ==========
+// ========== PHOTO IMPORT COMMANDS ==========
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Volume {
@@ -1355,6 +1702,8 @@ pub struct ScanVolumeResult {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ImportPhotosResult {
     pub imported: i32,
+    // Photos left out by the ignore rules
+    pub skipped: i32,
     pub errors: Vec<String>,
 }
 
@@ -1457,7 +1806,14 @@ fn is_photo_file(path: &Path) -> bool {
     }
 }
 
-fn scan_for_photos_recursive(dir: &Path, photos: &mut Vec<String>, max_depth: usize, current_depth: usize) -> std::io::Result<()> {
+fn scan_for_photos_recursive(
+    dir: &Path,
+    root: &Path,
+    rules: &ignore::IgnoreRules,
+    photos: &mut Vec<String>,
+    max_depth: usize,
+    current_depth: usize,
+) -> std::io::Result<()> {
     if current_depth > max_depth {
         return Ok(());
     }
@@ -1476,9 +1832,12 @@ fn scan_for_photos_recursive(dir: &Path, photos: &mut Vec<String>, max_depth: us
             }
 
             if path.is_dir() {
+                if rules.is_ignored(root, &path, true) {
+                    continue;
+                }
                 // Recursively scan subdirectories
-                scan_for_photos_recursive(&path, photos, max_depth, current_depth + 1)?;
-            } else if is_photo_file(&path) {
+                scan_for_photos_recursive(&path, root, rules, photos, max_depth, current_depth + 1)?;
+            } else if is_photo_file(&path) && !rules.skips_file(root, &path) {
                 // Only add if not already in the list (avoid duplicates)
                 let path_str = path.to_string_lossy().to_string();
                 if !photos.contains(&path_str) {
@@ -1492,7 +1851,7 @@ fn scan_for_photos_recursive(dir: &Path, photos: &mut Vec<String>, max_depth: us
 }
 
 #[tauri::command]
-pub async fn scan_volume_for_photos(volume_path: String) -> Result<ScanVolumeResult, String> {
+pub async fn scan_volume_for_photos(pool: State<'_, SqlitePool>, volume_path: String) -> Result<ScanVolumeResult, String> {
     let path = Path::new(&volume_path);
 
     if !path.exists() {
@@ -1500,9 +1859,10 @@ pub async fn scan_volume_for_photos(volume_path: String) -> Result<ScanVolumeRes
     }
 
     let mut photos = Vec::new();
+    let rules = ignore::IgnoreRules::load(pool.inner()).await?;
 
     // Scan recursively with max depth of 10 to avoid infinite loops
-    scan_for_photos_recursive(path, &mut photos, 10, 0)
+    scan_for_photos_recursive(path, path, &rules, &mut photos, 10, 0)
         .map_err(|e| format!("Failed to scan volume: {}", e))?;
 
     // Get first 4 photos as previews (as base64)
@@ -1582,7 +1942,7 @@ pub async fn import_photos(
                 .map_err(|e| format!("Failed to write description file: {}", e))?;
 
             // Add description file to database
-            let desc_file_path = desc_file.to_string_lossy().to_string();
+            let desc_file_path = paths::to_stored(&root_path, &desc_file.to_string_lossy());
             let desc_file_size = desc.len() as i64;
 
             // Check if already exists in database (by file_path)
@@ -1609,7 +1969,9 @@ pub async fn import_photos(
     }
 
     let mut imported_count = 0;
+    let mut skipped_count = 0;
     let mut errors = Vec::new();
+    let rules = ignore::IgnoreRules::load(pool.inner()).await?;
 
     // Import each photo
     for photo_path in photos {
@@ -1620,6 +1982,17 @@ pub async fn import_photos(
             continue;
         }
 
+        // Patterns apply from the root folder for photos of the library, from their own folder otherwise
+        let rules_root = match source.parent() {
+            Some(_) if !root_path.is_empty() && source.starts_with(&root_path) => Path::new(&root_path),
+            Some(parent) => parent,
+            None => source,
+        };
+        if rules.skips_file(rules_root, source) {
+            skipped_count += 1;
+            continue;
+        }
+
         // Get the file name
         let file_name = match source.file_name() {
             Some(name) => name.to_string_lossy().to_string(),
@@ -1653,7 +2026,7 @@ pub async fn import_photos(
         match fs::copy(source, &final_dest_file) {
             Ok(_) => {
                 // Add to database
-                let file_path = final_dest_file.to_string_lossy().to_string();
+                let file_path = paths::to_stored(&root_path, &final_dest_file.to_string_lossy());
                 let file_size = match fs::metadata(&final_dest_file) {
                     Ok(meta) => meta.len() as i64,
                     Err(_) => 0,
@@ -1676,8 +2049,8 @@ pub async fn import_photos(
                     // Insert into database only if not exists
                     // Use file_path for both file_path AND original_path since the photo is now in its final location
                     match sqlx::query(
-                        "INSERT INTO photos (file_path, original_path, file_name, file_size, width, height, folder_id)
-                         VALUES (?, ?, ?, ?, ?, ?, ?)"
+                        "INSERT INTO photos (file_path, original_path, file_name, file_size, width, height, folder_id, content_hash)
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
                     )
                     .bind(&file_path)
                     .bind(&file_path) // Use destination path as original_path, not source path
@@ -1686,6 +2059,7 @@ pub async fn import_photos(
                     .bind(width)
                     .bind(height)
                     .bind(folder_id)
+                    .bind(fileops::file_hash(&final_dest_file).ok())
                     .execute(pool.inner())
                     .await {
                         Ok(_) => {
@@ -1714,615 +2088,7 @@ pub async fn import_photos(
 
     Ok(ImportPhotosResult {
         imported: imported_count,
-        errors,
-    })
-}
-
-// ========== MOVE PHOTOS AND FOLDERS COMMAND ==========
-
-#[derive(Deserialize)]
-pub struct MoveItemsRequest {
-    pub photo_ids: Vec<i64>,
-    pub text_file_ids: Vec<i64>,
-    pub folder_paths: Vec<String>,
-    pub destination_path: String,
-    #[serde(default)]
-    pub delete_source_folder: bool,
-}
-
-#[derive(Serialize)]
-pub struct MoveItemsResult {
-    pub moved: i32,
-    pub errors: Vec<String>,
-}
-
-#[tauri::command]
-pub async fn move_photos_and_folders(
-    pool: State<'_, SqlitePool>,
-    request: MoveItemsRequest,
-) -> Result<MoveItemsResult, String> {
-    let dest_path = Path::new(&request.destination_path);
-
-    if !dest_path.exists() {
-        fs::create_dir_all(dest_path)
-            .map_err(|e| format!("Failed to create destination directory: {}", e))?;
-    }
-
-    let mut moved_count = 0;
-    let mut errors = Vec::new();
-
-    // Track source folders to delete (if delete_source_folder is true)
-    let mut source_folder_ids: std::collections::HashSet<i64> = std::collections::HashSet::new();
-
-    if request.delete_source_folder {
-        // Collect source folder IDs from photos
-        for photo_id in &request.photo_ids {
-            if let Ok(Some(photo)) = sqlx::query_as::<_, Photo>("SELECT * FROM photos WHERE id = ?")
-                .bind(photo_id)
-                .fetch_optional(pool.inner())
-                .await {
-                if let Some(folder_id) = photo.folder_id {
-                    source_folder_ids.insert(folder_id);
-                }
-            }
-        }
-    }
-
-    // Move individual photos
-    for photo_id in request.photo_ids {
-        let photo = sqlx::query_as::<_, Photo>("SELECT * FROM photos WHERE id = ?")
-            .bind(photo_id)
-            .fetch_optional(pool.inner())
-            .await
-            .map_err(|e| e.to_string())?;
-
-        if let Some(photo) = photo {
-            let source_path = Path::new(&photo.file_path);
-
-            if !source_path.exists() {
-                errors.push(format!("Source file not found: {}", photo.file_path));
-                continue;
-            }
-
-            let file_name = source_path.file_name().unwrap().to_string_lossy().to_string();
-            let mut dest_file = dest_path.join(&file_name);
-
-            // Handle duplicates
-            let mut copy_number = 1;
-            while dest_file.exists() {
-                let stem = source_path.file_stem().ok_or("Invalid file name").map_err(|e| e.to_string())?;
-                let extension = source_path.extension().map(|e| e.to_string_lossy()).unwrap_or_default();
-                let new_name = if extension.is_empty() {
-                    format!("{} ({})", stem.to_string_lossy(), copy_number)
-                } else {
-                    format!("{} ({}).{}", stem.to_string_lossy(), copy_number, extension)
-                };
-                dest_file = dest_path.join(&new_name);
-                copy_number += 1;
-            }
-
-            // Move the file
-            match fs::rename(source_path, &dest_file) {
-                Ok(_) => {
-                    // Update database with new paths and folder_id
-                    let new_file_path = dest_file.to_string_lossy().to_string();
-
-                    // Get the new folder path and ensure it exists in database
-                    let new_folder_path = dest_path.to_string_lossy().to_string();
-
-                    // Get root folder from settings to properly create folder hierarchy
-                    let root_folder: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = 'root_folder'")
-                        .fetch_optional(pool.inner())
-                        .await
-                        .map_err(|e| e.to_string())?;
-
-                    let root_path = root_folder.map(|(value,)| value).unwrap_or_default();
-
-                    // Ensure destination folder exists in DB and get its ID
-                    match ensure_folder_in_db(pool.inner(), &new_folder_path, &root_path).await {
-                        Ok(new_folder_id) => {
-                            // Update photo with new path and folder_id
-                            match sqlx::query(
-                                "UPDATE photos SET file_path = ?, original_path = ?, folder_id = ? WHERE id = ?"
-                            )
-                            .bind(&new_file_path)
-                            .bind(&new_file_path)
-                            .bind(new_folder_id)
-                            .bind(photo_id)
-                            .execute(pool.inner())
-                            .await {
-                                Ok(_) => moved_count += 1,
-                                Err(e) => errors.push(format!("Failed to update database for photo {}: {}", photo_id, e)),
-                            }
-                        }
-                        Err(e) => errors.push(format!("Failed to ensure destination folder for photo {}: {}", photo_id, e)),
-                    }
-                }
-                Err(e) => errors.push(format!("Failed to move file {}: {}", photo.file_path, e)),
-            }
-        } else {
-            errors.push(format!("Photo {} not found", photo_id));
-        }
-    }
-
-    // Move individual text files
-    for text_file_id in request.text_file_ids {
-        let text_file = sqlx::query_as::<_, TextFile>("SELECT * FROM text_files WHERE id = ?")
-            .bind(text_file_id)
-            .fetch_optional(pool.inner())
-            .await
-            .map_err(|e| e.to_string())?;
-
-        if let Some(text_file) = text_file {
-            let source_path = Path::new(&text_file.file_path);
-
-            if !source_path.exists() {
-                errors.push(format!("Source file not found: {}", text_file.file_path));
-                continue;
-            }
-
-            let file_name = source_path.file_name().unwrap().to_string_lossy().to_string();
-            let mut dest_file = dest_path.join(&file_name);
-
-            // Handle duplicates
-            let mut copy_number = 1;
-            while dest_file.exists() {
-                let stem = source_path.file_stem().ok_or("Invalid file name").map_err(|e| e.to_string())?;
-                let extension = source_path.extension().map(|e| e.to_string_lossy()).unwrap_or_default();
-                let new_name = if extension.is_empty() {
-                    format!("{} ({})", stem.to_string_lossy(), copy_number)
-                } else {
-                    format!("{} ({}).{}", stem.to_string_lossy(), copy_number, extension)
-                };
-                dest_file = dest_path.join(&new_name);
-                copy_number += 1;
-            }
-
-            // Move the file
-            match fs::rename(source_path, &dest_file) {
-                Ok(_) => {
-                    // Update database with new paths and folder_id
-                    let new_file_path = dest_file.to_string_lossy().to_string();
-
-                    // Get the new folder path and ensure it exists in database
-                    let new_folder_path = dest_path.to_string_lossy().to_string();
-
-                    // Get root folder from settings to properly create folder hierarchy
-                    let root_folder: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = 'root_folder'")
-                        .fetch_optional(pool.inner())
-                        .await
-                        .map_err(|e| e.to_string())?;
-
-                    let root_path = root_folder.map(|(value,)| value).unwrap_or_default();
-
-                    // Ensure destination folder exists in DB and get its ID
-                    match ensure_folder_in_db(pool.inner(), &new_folder_path, &root_path).await {
-                        Ok(new_folder_id) => {
-                            // Update text file size
-                            let metadata = fs::metadata(&new_file_path)
-                                .map_err(|e| format!("Failed to get file metadata: {}", e))?;
-                            let file_size = metadata.len() as i64;
-
-                            // Update text file with new path and folder_id
-                            match sqlx::query(
-                                "UPDATE text_files SET file_path = ?, folder_id = ?, file_size = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?"
-                            )
-                            .bind(&new_file_path)
-                            .bind(new_folder_id)
-                            .bind(file_size)
-                            .bind(text_file_id)
-                            .execute(pool.inner())
-                            .await {
-                                Ok(_) => moved_count += 1,
-                                Err(e) => errors.push(format!("Failed to update database for text file {}: {}", text_file_id, e)),
-                            }
-                        }
-                        Err(e) => errors.push(format!("Failed to ensure destination folder for text file {}: {}", text_file_id, e)),
-                    }
-                }
-                Err(e) => errors.push(format!("Failed to move file {}: {}", text_file.file_path, e)),
-            }
-        } else {
-            errors.push(format!("Text file {} not found", text_file_id));
-        }
-    }
-
-    // Move folders
-    for folder_path in request.folder_paths {
-        let source_folder = Path::new(&folder_path);
-
-        if !source_folder.exists() {
-            errors.push(format!("Source folder not found: {}", folder_path));
-            continue;
-        }
-
-        let folder_name = source_folder.file_name().unwrap().to_string_lossy().to_string();
-        let mut dest_folder = dest_path.join(&folder_name);
-
-        // Handle duplicates
-        let mut copy_number = 1;
-        while dest_folder.exists() {
-            let new_name = format!("{} ({})", folder_name, copy_number);
-            dest_folder = dest_path.join(&new_name);
-            copy_number += 1;
-        }
-
-        // Get all photos in this folder before moving
-        let photos_in_folder = sqlx::query_as::<_, Photo>("SELECT * FROM photos WHERE file_path LIKE ?")
-            .bind(format!("{}%", folder_path))
-            .fetch_all(pool.inner())
-            .await
-            .map_err(|e| e.to_string())?;
-
-        // Move the entire folder
-        match fs::rename(source_folder, &dest_folder) {
-            Ok(_) => {
-                let dest_folder_str = dest_folder.to_string_lossy().to_string();
-
-                // Get root folder from settings for folder hierarchy
-                let root_folder: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = 'root_folder'")
-                    .fetch_optional(pool.inner())
-                    .await
-                    .map_err(|e| e.to_string())?;
-
-                let root_path = root_folder.map(|(value,)| value).unwrap_or_default();
-
-                // Update all photos in this folder with new paths and folder_id
-                for photo in photos_in_folder {
-                    let old_path = photo.file_path.clone();
-                    let new_path = old_path.replace(&folder_path, &dest_folder_str);
-
-                    // Get the new folder path for this photo
-                    let photo_path = Path::new(&new_path);
-                    if let Some(parent) = photo_path.parent() {
-                        let photo_folder_path = parent.to_string_lossy().to_string();
-
-                        // Ensure folder exists and get its ID
-                        match ensure_folder_in_db(pool.inner(), &photo_folder_path, &root_path).await {
-                            Ok(new_folder_id) => {
-                                match sqlx::query(
-                                    "UPDATE photos SET file_path = ?, original_path = ?, folder_id = ? WHERE id = ?"
-                                )
-                                .bind(&new_path)
-                                .bind(&new_path)
-                                .bind(new_folder_id)
-                                .bind(photo.id)
-                                .execute(pool.inner())
-                                .await {
-                                    Ok(_) => moved_count += 1,
-                                    Err(e) => errors.push(format!("Failed to update database for photo in folder: {}", e)),
-                                }
-                            }
-                            Err(e) => errors.push(format!("Failed to ensure folder for photo {}: {}", photo.id, e)),
-                        }
-                    }
-                }
-
-                // Get all text files in this folder before moving
-                let text_files_in_folder = sqlx::query_as::<_, TextFile>("SELECT * FROM text_files WHERE file_path LIKE ?")
-                    .bind(format!("{}%", folder_path))
-                    .fetch_all(pool.inner())
-                    .await
-                    .map_err(|e| e.to_string())?;
-
-                // Update all text files in this folder with new paths and folder_id
-                for text_file in text_files_in_folder {
-                    let old_path = text_file.file_path.clone();
-                    let new_path = old_path.replace(&folder_path, &dest_folder_str);
-
-                    // Get the new folder path for this text file
-                    let file_path = Path::new(&new_path);
-                    if let Some(parent) = file_path.parent() {
-                        let file_folder_path = parent.to_string_lossy().to_string();
-
-                        // Ensure folder exists and get its ID
-                        match ensure_folder_in_db(pool.inner(), &file_folder_path, &root_path).await {
-                            Ok(new_folder_id) => {
-                                // Update text file size
-                                let file_size = if let Ok(metadata) = fs::metadata(&new_path) {
-                                    Some(metadata.len() as i64)
-                                } else {
-                                    text_file.file_size
-                                };
-
-                                match sqlx::query(
-                                    "UPDATE text_files SET file_path = ?, folder_id = ?, file_size = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?"
-                                )
-                                .bind(&new_path)
-                                .bind(new_folder_id)
-                                .bind(file_size)
-                                .bind(text_file.id)
-                                .execute(pool.inner())
-                                .await {
-                                    Ok(_) => moved_count += 1,
-                                    Err(e) => errors.push(format!("Failed to update database for text file in folder: {}", e)),
-                                }
-                            }
-                            Err(e) => errors.push(format!("Failed to ensure folder for text file {}: {}", text_file.id, e)),
-                        }
-                    }
-                }
-
-                // Update all folders in the folders table (the moved folder and its subfolders)
-                let folders_to_update = sqlx::query_as::<_, Folder>("SELECT * FROM folders WHERE path LIKE ?")
-                    .bind(format!("{}%", folder_path))
-                    .fetch_all(pool.inner())
-                    .await
-                    .map_err(|e| e.to_string())?;
-
-                for folder in folders_to_update {
-                    let new_folder_path = folder.path.replace(&folder_path, &dest_folder_str);
-
-                    match sqlx::query("UPDATE folders SET path = ? WHERE id = ?")
-                        .bind(&new_folder_path)
-                        .bind(folder.id)
-                        .execute(pool.inner())
-                        .await {
-                        Ok(_) => {},
-                        Err(e) => errors.push(format!("Failed to update folder path in DB: {}", e)),
-                    }
-                }
-            }
-            Err(e) => errors.push(format!("Failed to move folder {}: {}", folder_path, e)),
-        }
-    }
-
-    // Delete source folders if requested
-    if request.delete_source_folder && !source_folder_ids.is_empty() {
-        for folder_id in source_folder_ids {
-            // Get folder path before deletion
-            let folder_result = sqlx::query_as::<_, Folder>("SELECT * FROM folders WHERE id = ?")
-                .bind(folder_id)
-                .fetch_optional(pool.inner())
-                .await;
-
-            if let Ok(Some(folder)) = folder_result {
-                // Check if folder is now empty (no photos left)
-                let photo_count: (i64,) = sqlx::query_as(
-                    "SELECT COUNT(*) FROM photos WHERE folder_id = ?"
-                )
-                .bind(folder_id)
-                .fetch_one(pool.inner())
-                .await
-                .unwrap_or((0,));
-
-                if photo_count.0 == 0 {
-                    // Delete physical folder if it exists and is empty
-                    let folder_path = Path::new(&folder.path);
-                    if folder_path.exists() {
-                        match fs::remove_dir(folder_path) {
-                            Ok(_) => {
-                                // Delete from database
-                                match sqlx::query("DELETE FROM folders WHERE id = ?")
-                                    .bind(folder_id)
-                                    .execute(pool.inner())
-                                    .await {
-                                    Ok(_) => {},
-                                    Err(e) => errors.push(format!("Failed to delete folder from DB: {}", e)),
-                                }
-                            }
-                            Err(e) => {
-                                // If we can't delete because it's not empty or other error, just log it
-                                errors.push(format!("Could not delete source folder {}: {}", folder.path, e));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // Rebuild folder hierarchy to ensure correct parent_id values after moving folders
-    // Get root folder from settings
-    let root_folder: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = 'root_folder'")
-        .fetch_optional(pool.inner())
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if let Some((root_path,)) = root_folder {
-        match rebuild_folder_hierarchy(pool.inner(), &root_path).await {
-            Ok(_) => {},
-            Err(e) => errors.push(format!("Failed to rebuild folder hierarchy: {}", e)),
-        }
-    }
-
-    Ok(MoveItemsResult {
-        moved: moved_count,
-        errors,
-    })
-}
-
-#[derive(Serialize)]
-pub struct CopyItemsResult {
-    pub copied: i32,
-    pub errors: Vec<String>,
-}
-
-#[tauri::command]
-pub async fn copy_photos_and_folders(
-    pool: State<'_, SqlitePool>,
-    request: MoveItemsRequest, // Reuse same request structure
-) -> Result<CopyItemsResult, String> {
-    let dest_path = Path::new(&request.destination_path);
-
-    if !dest_path.exists() {
-        fs::create_dir_all(dest_path)
-            .map_err(|e| format!("Failed to create destination directory: {}", e))?;
-    }
-
-    let mut copied_count = 0;
-    let mut errors = Vec::new();
-
-    // Copy individual photos
-    for photo_id in request.photo_ids {
-        let photo = sqlx::query_as::<_, Photo>("SELECT * FROM photos WHERE id = ?")
-            .bind(photo_id)
-            .fetch_optional(pool.inner())
-            .await
-            .map_err(|e| e.to_string())?;
-
-        if let Some(photo) = photo {
-            let source_path = Path::new(&photo.file_path);
-
-            if !source_path.exists() {
-                errors.push(format!("Source file not found: {}", photo.file_path));
-                continue;
-            }
-
-            let file_name = source_path.file_name().unwrap().to_string_lossy().to_string();
-            let mut dest_file = dest_path.join(&file_name);
-
-            // Handle duplicates
-            let mut copy_number = 1;
-            while dest_file.exists() {
-                let stem = source_path.file_stem().ok_or("Invalid file name").map_err(|e| e.to_string())?;
-                let extension = source_path.extension().map(|e| e.to_string_lossy()).unwrap_or_default();
-                let new_name = if extension.is_empty() {
-                    format!("{} ({})", stem.to_string_lossy(), copy_number)
-                } else {
-                    format!("{} ({}).{}", stem.to_string_lossy(), copy_number, extension)
-                };
-                dest_file = dest_path.join(&new_name);
-                copy_number += 1;
-            }
-
-            // Copy the file
-            match fs::copy(source_path, &dest_file) {
-                Ok(_) => {
-                    // Create new database entry with new path and folder_id
-                    let new_file_path = dest_file.to_string_lossy().to_string();
-                    let new_folder_path = dest_path.to_string_lossy().to_string();
-
-                    // Get root folder from settings
-                    let root_folder: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = 'root_folder'")
-                        .fetch_optional(pool.inner())
-                        .await
-                        .map_err(|e| e.to_string())?;
-
-                    let root_path = root_folder.map(|(value,)| value).unwrap_or_default();
-
-                    // Ensure destination folder exists in DB and get its ID
-                    match ensure_folder_in_db(pool.inner(), &new_folder_path, &root_path).await {
-                        Ok(new_folder_id) => {
-                            // Insert new photo entry
-                            match sqlx::query(
-                                "INSERT INTO photos (file_path, original_path, file_name, file_size, width, height, folder_id)
-                                 VALUES (?, ?, ?, ?, ?, ?, ?)"
-                            )
-                            .bind(&new_file_path)
-                            .bind(&new_file_path)
-                            .bind(dest_file.file_name().unwrap().to_string_lossy().to_string())
-                            .bind(photo.file_size)
-                            .bind(photo.width)
-                            .bind(photo.height)
-                            .bind(new_folder_id)
-                            .execute(pool.inner())
-                            .await {
-                                Ok(_) => copied_count += 1,
-                                Err(e) => errors.push(format!("Failed to create database entry for copied photo: {}", e)),
-                            }
-                        }
-                        Err(e) => errors.push(format!("Failed to ensure destination folder for photo {}: {}", photo_id, e)),
-                    }
-                }
-                Err(e) => errors.push(format!("Failed to copy file {}: {}", photo.file_path, e)),
-            }
-        } else {
-            errors.push(format!("Photo {} not found", photo_id));
-        }
-    }
-
-    // Copy individual text files
-    for text_file_id in request.text_file_ids {
-        let text_file = sqlx::query_as::<_, TextFile>("SELECT * FROM text_files WHERE id = ?")
-            .bind(text_file_id)
-            .fetch_optional(pool.inner())
-            .await
-            .map_err(|e| e.to_string())?;
-
-        if let Some(text_file) = text_file {
-            let source_path = Path::new(&text_file.file_path);
-
-            if !source_path.exists() {
-                errors.push(format!("Source file not found: {}", text_file.file_path));
-                continue;
-            }
-
-            let file_name = source_path.file_name().unwrap().to_string_lossy().to_string();
-            let mut dest_file = dest_path.join(&file_name);
-
-            // Handle duplicates
-            let mut copy_number = 1;
-            while dest_file.exists() {
-                let stem = source_path.file_stem().ok_or("Invalid file name").map_err(|e| e.to_string())?;
-                let extension = source_path.extension().map(|e| e.to_string_lossy()).unwrap_or_default();
-                let new_name = if extension.is_empty() {
-                    format!("{} ({})", stem.to_string_lossy(), copy_number)
-                } else {
-                    format!("{} ({}).{}", stem.to_string_lossy(), copy_number, extension)
-                };
-                dest_file = dest_path.join(&new_name);
-                copy_number += 1;
-            }
-
-            // Copy the file
-            match fs::copy(source_path, &dest_file) {
-                Ok(_) => {
-                    // Create new database entry with new path and folder_id
-                    let new_file_path = dest_file.to_string_lossy().to_string();
-                    let new_folder_path = dest_path.to_string_lossy().to_string();
-
-                    // Get root folder from settings
-                    let root_folder: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = 'root_folder'")
-                        .fetch_optional(pool.inner())
-                        .await
-                        .map_err(|e| e.to_string())?;
-
-                    let root_path = root_folder.map(|(value,)| value).unwrap_or_default();
-
-                    // Ensure destination folder exists in DB and get its ID
-                    match ensure_folder_in_db(pool.inner(), &new_folder_path, &root_path).await {
-                        Ok(new_folder_id) => {
-                            // Get file size
-                            let file_size = if let Ok(metadata) = fs::metadata(&new_file_path) {
-                                Some(metadata.len() as i64)
-                            } else {
-                                text_file.file_size
-                            };
-
-                            // Insert new text file entry
-                            match sqlx::query(
-                                "INSERT INTO text_files (file_path, file_name, file_size, folder_id)
-                                 VALUES (?, ?, ?, ?)"
-                            )
-                            .bind(&new_file_path)
-                            .bind(dest_file.file_name().unwrap().to_string_lossy().to_string())
-                            .bind(file_size)
-                            .bind(new_folder_id)
-                            .execute(pool.inner())
-                            .await {
-                                Ok(_) => copied_count += 1,
-                                Err(e) => errors.push(format!("Failed to create database entry for copied text file: {}", e)),
-                            }
-                        }
-                        Err(e) => errors.push(format!("Failed to ensure destination folder for text file {}: {}", text_file_id, e)),
-                    }
-                }
-                Err(e) => errors.push(format!("Failed to copy file {}: {}", text_file.file_path, e)),
-            }
-        } else {
-            errors.push(format!("Text file {} not found", text_file_id));
-        }
-    }
-
-    // Note: For folders, we would need to recursively copy the folder structure
-    // For now, we'll just note that folder copying is not fully implemented
-    if !request.folder_paths.is_empty() {
-        errors.push("Folder copying is not yet fully implemented".to_string());
-    }
-
-    Ok(CopyItemsResult {
-        copied: copied_count,
+        skipped: skipped_count,
         errors,
     })
 }
@@ -2331,9 +2097,12 @@ pub async fn copy_photos_and_folders(
 
 #[tauri::command]
 pub async fn list_folders(pool: State<'_, SqlitePool>) -> Result<Vec<Folder>, String> {
+    let root = paths::library_root(pool.inner()).await?;
+
     sqlx::query_as::<_, Folder>("SELECT * FROM folders ORDER BY path")
         .fetch_all(pool.inner())
         .await
+        .map(|folders| folders.resolve_paths(&root))
         .map_err(|e| e.to_string())
 }
 
@@ -2360,9 +2129,11 @@ pub async fn create_folder(
         .unwrap_or("")
         .to_string();
 
+    let root = paths::library_root(pool.inner()).await?;
+
     // Determine parent_id by looking up parent folder in DB
     let parent_id: Option<i64> = if let Some(parent) = folder_path.parent() {
-        let parent_path = parent.to_string_lossy().to_string();
+        let parent_path = paths::to_stored(&root, &parent.to_string_lossy());
 
         // Check if parent exists in folders table
         let parent_folder: Option<(i64,)> = sqlx::query_as(
@@ -2382,7 +2153,7 @@ pub async fn create_folder(
     let result = sqlx::query(
         "INSERT INTO folders (path, name, parent_id) VALUES (?, ?, ?)"
     )
-    .bind(&request.folder_path)
+    .bind(paths::to_stored(&root, &request.folder_path))
     .bind(&folder_name)
     .bind(parent_id)
     .execute(pool.inner())
@@ -2396,6 +2167,7 @@ pub async fn create_folder(
         .bind(id)
         .fetch_one(pool.inner())
         .await
+        .map(|folder| folder.resolve_paths(&root))
         .map_err(|e| e.to_string())
 }
 
@@ -2413,198 +2185,6 @@ pub async fn delete_folder_from_db(
     Ok(())
 }
 
-// ========== DATABASE SYNC COMMAND ==========
-
-#[derive(Serialize)]
-pub struct SyncDatabaseResult {
-    pub photos_removed: i32,
-    pub photos_updated: i32,
-    pub text_files_removed: i32,
-    pub text_files_updated: i32,
-    pub folders_cleaned: i32,
-    pub errors: Vec<String>,
-}
-
-#[tauri::command]
-pub async fn sync_database(pool: State<'_, SqlitePool>) -> Result<SyncDatabaseResult, String> {
-    let mut photos_removed = 0;
-    let mut photos_updated = 0;
-    let mut text_files_removed = 0;
-    let mut text_files_updated = 0;
-    let mut folders_cleaned = 0;
-    let mut errors = Vec::new();
-
-    // Get root folder from settings
-    let root_folder: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = 'root_folder'")
-        .fetch_optional(pool.inner())
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let root_path = root_folder.map(|(value,)| value).unwrap_or_default();
-
-    // Get all photos from database
-    let photos = sqlx::query_as::<_, Photo>("SELECT * FROM photos")
-        .fetch_all(pool.inner())
-        .await
-        .map_err(|e| e.to_string())?;
-
-    // Check each photo
-    for photo in photos {
-        let photo_path = Path::new(&photo.file_path);
-
-        if !photo_path.exists() {
-            // Photo file doesn't exist, remove from database
-            match sqlx::query("DELETE FROM photos WHERE id = ?")
-                .bind(photo.id)
-                .execute(pool.inner())
-                .await
-            {
-                Ok(_) => photos_removed += 1,
-                Err(e) => errors.push(format!("Failed to remove photo {}: {}", photo.id, e)),
-            }
-        } else {
-            // Photo exists, ensure folder_id is correct
-            if let Some(parent) = photo_path.parent() {
-                let folder_path = parent.to_string_lossy().to_string();
-
-                // Use root_path if available, otherwise use folder_path itself as root
-                let effective_root = if !root_path.is_empty() { &root_path } else { &folder_path };
-                match ensure_folder_in_db(pool.inner(), &folder_path, effective_root).await {
-                    Ok(folder_id) => {
-                        // Update photo's folder_id if different
-                        if photo.folder_id != Some(folder_id) {
-                            match sqlx::query("UPDATE photos SET folder_id = ? WHERE id = ?")
-                                .bind(folder_id)
-                                .bind(photo.id)
-                                .execute(pool.inner())
-                                .await
-                            {
-                                Ok(_) => photos_updated += 1,
-                                Err(e) => errors.push(format!("Failed to update photo {}: {}", photo.id, e)),
-                            }
-                        }
-                    }
-                    Err(e) => errors.push(format!("Failed to ensure folder for photo {}: {}", photo.id, e)),
-                }
-            }
-        }
-    }
-
-    // Get all text files from database
-    let text_files = sqlx::query_as::<_, TextFile>("SELECT * FROM text_files")
-        .fetch_all(pool.inner())
-        .await
-        .map_err(|e| e.to_string())?;
-
-    // Check each text file
-    for text_file in text_files {
-        let file_path = Path::new(&text_file.file_path);
-
-        if !file_path.exists() {
-            // Text file doesn't exist, remove from database
-            match sqlx::query("DELETE FROM text_files WHERE id = ?")
-                .bind(text_file.id)
-                .execute(pool.inner())
-                .await
-            {
-                Ok(_) => text_files_removed += 1,
-                Err(e) => errors.push(format!("Failed to remove text file {}: {}", text_file.id, e)),
-            }
-        } else {
-            // Text file exists, ensure folder_id is correct
-            if let Some(parent) = file_path.parent() {
-                let folder_path = parent.to_string_lossy().to_string();
-
-                // Use root_path if available, otherwise use folder_path itself as root
-                let effective_root = if !root_path.is_empty() { &root_path } else { &folder_path };
-                match ensure_folder_in_db(pool.inner(), &folder_path, effective_root).await {
-                    Ok(folder_id) => {
-                        // Update text file's folder_id if different
-                        if text_file.folder_id != Some(folder_id) {
-                            match sqlx::query("UPDATE text_files SET folder_id = ? WHERE id = ?")
-                                .bind(folder_id)
-                                .bind(text_file.id)
-                                .execute(pool.inner())
-                                .await
-                            {
-                                Ok(_) => text_files_updated += 1,
-                                Err(e) => errors.push(format!("Failed to update text file {}: {}", text_file.id, e)),
-                            }
-                        }
-                    }
-                    Err(e) => errors.push(format!("Failed to ensure folder for text file {}: {}", text_file.id, e)),
-                }
-            }
-        }
-    }
-
-    // Scan and create all folders, then rebuild hierarchy based on root_path
-    if !root_path.is_empty() {
-        match scan_and_create_folders(pool.inner(), &root_path).await {
-            Ok(_) => {},
-            Err(e) => errors.push(format!("Failed to scan folders: {}", e)),
-        }
-
-        match rebuild_folder_hierarchy(pool.inner(), &root_path).await {
-            Ok(_) => {},
-            Err(e) => errors.push(format!("Failed to rebuild folder hierarchy: {}", e)),
-        }
-
-        // Scan for new photos in the root folder
-        let root_path_obj = Path::new(&root_path);
-        if root_path_obj.exists() && root_path_obj.is_dir() {
-            let mut imported_count = 0;
-            let mut scan_errors = Vec::new();
-
-            match scan_directory_recursive(pool.inner(), root_path_obj, root_path_obj, &mut imported_count, &mut scan_errors).await {
-                Ok(_) => {
-                    photos_updated += imported_count;
-                    if !scan_errors.is_empty() {
-                        errors.extend(scan_errors);
-                    }
-                },
-                Err(e) => errors.push(format!("Failed to scan for new photos: {}", e)),
-            }
-        }
-    }
-
-    // Clean up empty folders (folders with no photos and no text files)
-    let empty_folders = sqlx::query_as::<_, Folder>(
-        "SELECT f.* FROM folders f
-         LEFT JOIN photos p ON p.folder_id = f.id
-         LEFT JOIN text_files t ON t.folder_id = f.id
-         WHERE p.id IS NULL AND t.id IS NULL"
-    )
-    .fetch_all(pool.inner())
-    .await
-    .map_err(|e| e.to_string())?;
-
-    for folder in empty_folders {
-        let folder_path = Path::new(&folder.path);
-
-        // Only delete from DB if folder doesn't exist on filesystem
-        if !folder_path.exists() {
-            match sqlx::query("DELETE FROM folders WHERE id = ?")
-                .bind(folder.id)
-                .execute(pool.inner())
-                .await
-            {
-                Ok(_) => folders_cleaned += 1,
-                Err(e) => errors.push(format!("Failed to clean folder {}: {}", folder.id, e)),
-            }
-        }
-    }
-
-    Ok(SyncDatabaseResult {
-        photos_removed,
-        photos_updated,
-        text_files_removed,
-        text_files_updated,
-        folders_cleaned,
-        errors,
-    })
-}
-
 // Scan all subdirectories of root_path and create them in database
 async fn scan_and_create_folders(pool: &SqlitePool, root_path: &str) -> Result<i32, String> {
     let root = Path::new(root_path);
@@ -2613,12 +2193,14 @@ async fn scan_and_create_folders(pool: &SqlitePool, root_path: &str) -> Result<i
     }
 
     let mut folders_created = 0;
+    let rules = ignore::IgnoreRules::load(pool).await?;
 
     // Recursively scan all directories
     fn scan_dir_recursive<'a>(
         dir: &'a Path,
         root_path: &'a str,
         pool: &'a SqlitePool,
+        rules: &'a ignore::IgnoreRules,
         count: &'a mut i32,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), String>> + Send + 'a>> {
         Box::pin(async move {
@@ -2632,6 +2214,10 @@ async fn scan_and_create_folders(pool: &SqlitePool, root_path: &str) -> Result<i
                     let path = entry.path();
 
                     if path.is_dir() {
+                        if rules.is_ignored(Path::new(root_path), &path, true) {
+                            continue;
+                        }
+
                         let folder_path = path.to_string_lossy().to_string();
 
                         // Create folder in database
@@ -2640,7 +2226,7 @@ async fn scan_and_create_folders(pool: &SqlitePool, root_path: &str) -> Result<i
                         }
 
                         // Recursively scan subdirectories
-                        scan_dir_recursive(&path, root_path, pool, count).await?;
+                        scan_dir_recursive(&path, root_path, pool, rules, count).await?;
                     }
                 }
             }
@@ -2649,7 +2235,7 @@ async fn scan_and_create_folders(pool: &SqlitePool, root_path: &str) -> Result<i
         })
     }
 
-    scan_dir_recursive(root, root_path, pool, &mut folders_created).await?;
+    scan_dir_recursive(root, root_path, pool, &rules, &mut folders_created).await?;
 
     Ok(folders_created)
 }
@@ -2658,10 +2244,12 @@ async fn scan_and_create_folders(pool: &SqlitePool, root_path: &str) -> Result<i
 // Sets parent_id correctly for all existing folders
 async fn rebuild_folder_hierarchy(pool: &SqlitePool, root_path: &str) -> Result<(), String> {
     // Get all folders
+    let root = paths::library_root(pool).await?;
     let folders = sqlx::query_as::<_, Folder>("SELECT * FROM folders")
         .fetch_all(pool)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.to_string())?
+        .resolve_paths(&root);
 
     // For each folder, determine its correct parent_id
     for folder in folders {
@@ -2682,7 +2270,7 @@ async fn rebuild_folder_hierarchy(pool: &SqlitePool, root_path: &str) -> Result<
                 let parent_folder: Option<(i64,)> = sqlx::query_as(
                     "SELECT id FROM folders WHERE path = ?"
                 )
-                .bind(&parent_path)
+                .bind(paths::to_stored(&root, &parent_path))
                 .fetch_optional(pool)
                 .await
                 .map_err(|e| e.to_string())?;
@@ -2707,19 +2295,78 @@ async fn rebuild_folder_hierarchy(pool: &SqlitePool, root_path: &str) -> Result<
     Ok(())
 }
 
+// Replace the path prefix of every folder, photo and text file stored at or under `old_prefix`
+// (after the file or folder was renamed or moved on disk)
+pub(crate) async fn replace_path_prefix(
+    conn: &mut sqlx::SqliteConnection,
+    old_prefix: &str,
+    new_prefix: &str,
+) -> Result<(), String> {
+    let root = paths::library_root(&mut *conn).await?;
+    let (old_prefix, new_prefix) = (&paths::to_stored(&root, old_prefix), &paths::to_stored(&root, new_prefix));
+    let old_children = format!("{}{}", old_prefix, std::path::MAIN_SEPARATOR);
+
+    sqlx::query(
+        "UPDATE folders SET path = ? || substr(path, length(?) + 1)
+         WHERE path = ? OR substr(path, 1, length(?)) = ?"
+    )
+    .bind(new_prefix)
+    .bind(old_prefix)
+    .bind(old_prefix)
+    .bind(&old_children)
+    .bind(&old_children)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query(
+        "UPDATE photos SET
+            file_path = ? || substr(file_path, length(?) + 1),
+            original_path = CASE WHEN original_path = file_path THEN ? || substr(file_path, length(?) + 1) ELSE original_path END
+         WHERE file_path = ? OR substr(file_path, 1, length(?)) = ?"
+    )
+    .bind(new_prefix)
+    .bind(old_prefix)
+    .bind(new_prefix)
+    .bind(old_prefix)
+    .bind(old_prefix)
+    .bind(&old_children)
+    .bind(&old_children)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query(
+        "UPDATE text_files SET file_path = ? || substr(file_path, length(?) + 1), updated_at = CURRENT_TIMESTAMP
+         WHERE file_path = ? OR substr(file_path, 1, length(?)) = ?"
+    )
+    .bind(new_prefix)
+    .bind(old_prefix)
+    .bind(old_prefix)
+    .bind(&old_children)
+    .bind(&old_children)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
 // Helper function to ensure a folder exists in the database with proper parent_id hierarchy
 // Limited to root_path (doesn't create folders above root_path)
-fn ensure_folder_in_db<'a>(
+pub(crate) fn ensure_folder_in_db<'a>(
     pool: &'a SqlitePool,
     folder_path: &'a str,
     root_path: &'a str,
 ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<i64, String>> + Send + 'a>> {
     Box::pin(async move {
+        let stored_path = paths::to_stored(&paths::library_root(pool).await?, folder_path);
+
         // Check if folder already exists
         let existing: Option<Folder> = sqlx::query_as::<_, Folder>(
             "SELECT * FROM folders WHERE path = ?"
         )
-        .bind(folder_path)
+        .bind(&stored_path)
         .fetch_optional(pool)
         .await
         .map_err(|e| e.to_string())?;
@@ -2781,7 +2428,7 @@ fn ensure_folder_in_db<'a>(
         let result = sqlx::query(
             "INSERT INTO folders (path, name, parent_id) VALUES (?, ?, ?)"
         )
-        .bind(folder_path)
+        .bind(&stored_path)
         .bind(&folder_name)
         .bind(parent_id)
         .execute(pool)
@@ -2808,6 +2455,11 @@ pub async fn get_setting(pool: State<'_, SqlitePool>, key: String) -> Result<Opt
 
 #[tauri::command]
 pub async fn set_setting(pool: State<'_, SqlitePool>, key: String, value: String) -> Result<(), String> {
+    // Object creation and imports generate SKUs from this pattern
+    if key == "sku_pattern" {
+        skus::check_sku_pattern(&value)?;
+    }
+
     sqlx::query(
         "INSERT INTO settings (key, value, updated_at) VALUES (?, ?, CURRENT_TIMESTAMP)
          ON CONFLICT(key) DO UPDATE SET value = ?, updated_at = CURRENT_TIMESTAMP"
@@ -2851,15 +2503,14 @@ pub async fn get_root_folder(pool: State<'_, SqlitePool>) -> Result<Option<Strin
 
 #[tauri::command]
 pub async fn set_root_folder(pool: State<'_, SqlitePool>, path: String) -> Result<(), String> {
-    sqlx::query(
-        "INSERT INTO settings (key, value, updated_at) VALUES ('root_folder', ?, CURRENT_TIMESTAMP)
-         ON CONFLICT(key) DO UPDATE SET value = ?, updated_at = CURRENT_TIMESTAMP"
-    )
-    .bind(&path)
-    .bind(&path)
-    .execute(pool.inner())
-    .await
-    .map_err(|e| e.to_string())?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    // Photos, text files and folders keep pointing at the same files under the new root
+    let old_root = paths::library_root(&mut *tx).await?;
+    paths::rebase_paths(&mut tx, &old_root, &path).await?;
+    write_root_folder(&mut tx, &path).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
 
     // Create default folders "CATEGORIES" and "IMPORTS" if they don't exist
     let categories_path = Path::new(&path).join("CATEGORIES");
@@ -2883,3 +2534,17 @@ pub async fn set_root_folder(pool: State<'_, SqlitePool>, path: String) -> Resul
 
     Ok(())
 }
+
+pub(crate) async fn write_root_folder(conn: &mut sqlx::SqliteConnection, path: &str) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO settings (key, value, updated_at) VALUES ('root_folder', ?, CURRENT_TIMESTAMP)
+         ON CONFLICT(key) DO UPDATE SET value = ?, updated_at = CURRENT_TIMESTAMP"
+    )
+    .bind(path)
+    .bind(path)
+    .execute(conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use crate::models::{Folder, Photo, TextFile};
+use std::path::Path;
+
+use super::PhotoWithAssociation;
+
+// ========== LIBRARY PATHS ==========
+// Photos, text files and folders under the root folder are stored with a path relative to it,
+// so that the library keeps working when the root folder is moved or mounted elsewhere.
+// Paths outside the root folder stay absolute. Commands receive and return absolute paths.
+
+// Current root folder ("" when not configured)
+pub(crate) async fn library_root<'e, E: sqlx::SqliteExecutor<'e>>(executor: E) -> Result<String, String> {
+    let root = sqlx::query_scalar::<_, String>("SELECT value FROM settings WHERE key = 'root_folder'")
+        .fetch_optional(executor)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(root.unwrap_or_default())
+}
+
+// Path as stored in the database for an absolute path
+pub(crate) fn to_stored(root: &str, path: &str) -> String {
+    if root.is_empty() {
+        return path.to_string();
+    }
+
+    match Path::new(path).strip_prefix(root) {
+        Ok(relative) if !relative.as_os_str().is_empty() => relative.to_string_lossy().to_string(),
+        _ => path.to_string(),
+    }
+}
+
+// Absolute path of a path stored in the database
+pub(crate) fn to_absolute(root: &str, stored: &str) -> String {
+    if root.is_empty() || Path::new(stored).is_absolute() {
+        return stored.to_string();
+    }
+
+    Path::new(root).join(stored).to_string_lossy().to_string()
+}
+
+// Rows holding library paths, resolved to absolute paths before leaving a command
+pub(crate) trait LibraryPaths: Sized {
+    fn resolve_paths(self, root: &str) -> Self;
+}
+
+impl LibraryPaths for Photo {
+    fn resolve_paths(mut self, root: &str) -> Self {
+        self.file_path = to_absolute(root, &self.file_path);
+        self.original_path = to_absolute(root, &self.original_path);
+        self
+    }
+}
+
+impl LibraryPaths for PhotoWithAssociation {
+    fn resolve_paths(mut self, root: &str) -> Self {
+        self.file_path = to_absolute(root, &self.file_path);
+        self.original_path = to_absolute(root, &self.original_path);
+        self
+    }
+}
+
+impl LibraryPaths for TextFile {
+    fn resolve_paths(mut self, root: &str) -> Self {
+        self.file_path = to_absolute(root, &self.file_path);
+        self
+    }
+}
+
+impl LibraryPaths for Folder {
+    fn resolve_paths(mut self, root: &str) -> Self {
+        self.path = to_absolute(root, &self.path);
+        self
+    }
+}
+
+impl<T: LibraryPaths> LibraryPaths for Option<T> {
+    fn resolve_paths(self, root: &str) -> Self {
+        self.map(|row| row.resolve_paths(root))
+    }
+}
+
+impl<T: LibraryPaths> LibraryPaths for Vec<T> {
+    fn resolve_paths(self, root: &str) -> Self {
+        self.into_iter().map(|row| row.resolve_paths(root)).collect()
+    }
+}
+
+// Columns holding library paths
+const PATH_COLUMNS: [(&str, &str); 4] = [
+    ("folders", "path"),
+    ("photos", "file_path"),
+    ("photos", "original_path"),
+    ("text_files", "file_path"),
+];
+
+// Store the paths relative to a new root folder, keeping them pointing at the same files
+pub(crate) async fn rebase_paths(
+    conn: &mut sqlx::SqliteConnection,
+    old_root: &str,
+    new_root: &str,
+) -> Result<(), String> {
+    // All paths are made absolute first so that no intermediate value collides with another row
+    for step in [(old_root, ""), ("", new_root)] {
+        for (table, column) in PATH_COLUMNS {
+            let rows = sqlx::query_as::<_, (i64, String)>(&format!("SELECT id, {} FROM {}", column, table))
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            for (id, path) in rows {
+                let rebased = to_stored(step.1, &to_absolute(step.0, &path));
+                if rebased != path {
+                    sqlx::query(&format!("UPDATE {} SET {} = ? WHERE id = ?", table, column))
+                        .bind(&rebased)
+                        .bind(id)
+                        .execute(&mut *conn)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::Connection;
+
+    #[test]
+    fn stored_paths() {
+        assert_eq!(to_stored("/lib", "/lib/a/p.jpg"), "a/p.jpg");
+        assert_eq!(to_stored("/lib/", "/lib/a"), "a");
+        assert_eq!(to_stored("/lib", "/lib"), "/lib");
+        assert_eq!(to_stored("/lib", "/library/p.jpg"), "/library/p.jpg");
+        assert_eq!(to_stored("", "/lib/p.jpg"), "/lib/p.jpg");
+    }
+
+    #[test]
+    fn absolute_paths() {
+        assert_eq!(to_absolute("/lib", "a/p.jpg"), "/lib/a/p.jpg");
+        assert_eq!(to_absolute("/lib", "/elsewhere/p.jpg"), "/elsewhere/p.jpg");
+        assert_eq!(to_absolute("", "a/p.jpg"), "a/p.jpg");
+        assert_eq!(to_absolute("/lib", &to_stored("/lib", "/lib/a/p.jpg")), "/lib/a/p.jpg");
+    }
+
+    #[tokio::test]
+    async fn rebased_paths() {
+        let mut conn = sqlx::SqliteConnection::connect("sqlite::memory:").await.unwrap();
+        sqlx::raw_sql(
+            "CREATE TABLE folders (id INTEGER PRIMARY KEY, path TEXT UNIQUE);
+             CREATE TABLE photos (id INTEGER PRIMARY KEY, file_path TEXT UNIQUE, original_path TEXT);
+             CREATE TABLE text_files (id INTEGER PRIMARY KEY, file_path TEXT UNIQUE);
+             INSERT INTO folders (path) VALUES ('a'), ('/mnt/lib/b');
+             INSERT INTO photos (file_path, original_path) VALUES ('a/p.jpg', '/import/p.jpg'), ('/mnt/lib/b/q.jpg', 'b/q.jpg');
+             INSERT INTO text_files (file_path) VALUES ('a/description.txt');",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        rebase_paths(&mut conn, "/lib", "/mnt/lib").await.unwrap();
+
+        let folders = sqlx::query_scalar::<_, String>("SELECT path FROM folders ORDER BY id")
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(folders, ["/lib/a", "b"]);
+        let photos = sqlx::query_as::<_, (String, String)>("SELECT file_path, original_path FROM photos ORDER BY id")
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(
+            photos,
+            [
+                ("/lib/a/p.jpg".to_string(), "/import/p.jpg".to_string()),
+                ("b/q.jpg".to_string(), "/lib/b/q.jpg".to_string()),
+            ]
+        );
+        let text_file = sqlx::query_scalar::<_, String>("SELECT file_path FROM text_files")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(text_file, "/lib/a/description.txt");
+    }
+}
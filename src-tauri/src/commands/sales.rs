@@ -0,0 +1,328 @@
+use crate::models::{Bundle, Object, RecordBundleSale, RecordObjectSale, Sale, SaleItem};
+use serde::Serialize;
+use sqlx::{SqliteConnection, SqlitePool};
+use tauri::State;
+
+use super::bundles;
+
+// ========== SALES COMMANDS ==========
+
+#[derive(Debug, Serialize)]
+pub struct SaleWithItems {
+    #[serde(flatten)]
+    pub sale: Sale,
+    pub items: Vec<SaleItem>,
+}
+
+// Split an amount across weighted parts, to the cent. Rounding leftovers go to the first parts
+// so that the parts always add up to the total.
+pub(crate) fn split_proceeds(total: f64, weights: &[f64]) -> Vec<f64> {
+    if weights.is_empty() {
+        return Vec::new();
+    }
+
+    let weight_sum: f64 = weights.iter().sum();
+    let weights: Vec<f64> = if weight_sum > 0.0 {
+        weights.to_vec()
+    } else {
+        vec![1.0; weights.len()]
+    };
+    let weight_sum: f64 = weights.iter().sum();
+
+    let total_cents = (total * 100.0).round() as i64;
+    let mut cents: Vec<i64> = weights
+        .iter()
+        .map(|w| ((total_cents as f64) * w / weight_sum).floor() as i64)
+        .collect();
+
+    let leftover = total_cents - cents.iter().sum::<i64>();
+    let count = cents.len() as i64;
+    for (index, part) in cents.iter_mut().enumerate() {
+        *part += leftover / count + if (index as i64) < leftover % count { 1 } else { 0 };
+    }
+
+    cents.iter().map(|c| *c as f64 / 100.0).collect()
+}
+
+// Take `quantity` items of an object out of the stock. Listings of sold out objects are marked as sold.
+async fn decrement_stock(conn: &mut SqliteConnection, object_id: i64, quantity: i64) -> Result<(), String> {
+    let object = sqlx::query_as::<_, Object>("SELECT * FROM objects WHERE id = ?")
+        .bind(object_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Object {} not found", object_id))?;
+
+    if object.quantity < quantity {
+        return Err(format!(
+            "Not enough stock for '{}': {} requested, {} available",
+            object.name, quantity, object.quantity
+        ));
+    }
+
+    sqlx::query("UPDATE objects SET quantity = quantity - ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(quantity)
+        .bind(object_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if object.quantity == quantity {
+        sqlx::query(
+            "UPDATE object_platforms SET status = 'sold', updated_at = CURRENT_TIMESTAMP
+             WHERE object_id = ? AND status != 'sold'"
+        )
+        .bind(object_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+async fn fetch_sale(conn: &mut SqliteConnection, id: i64) -> Result<SaleWithItems, String> {
+    let sale = sqlx::query_as::<_, Sale>("SELECT * FROM sales WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Sale not found".to_string())?;
+
+    let items = sqlx::query_as::<_, SaleItem>("SELECT * FROM sale_items WHERE sale_id = ? ORDER BY id ASC")
+        .bind(id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(SaleWithItems { sale, items })
+}
+
+async fn insert_sale(
+    conn: &mut SqliteConnection,
+    bundle_id: Option<i64>,
+    platform_id: Option<i64>,
+    total_price: f64,
+    currency: &str,
+    notes: &Option<String>,
+) -> Result<i64, String> {
+    if total_price < 0.0 {
+        return Err("The sale price can't be negative".to_string());
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO sales (bundle_id, platform_id, total_price, currency, notes) VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(bundle_id)
+    .bind(platform_id)
+    .bind(total_price)
+    .bind(currency.trim().to_uppercase())
+    .bind(notes)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(result.last_insert_rowid())
+}
+
+async fn insert_sale_item(
+    conn: &mut SqliteConnection,
+    sale_id: i64,
+    object_id: i64,
+    quantity: i64,
+    amount: f64,
+) -> Result<(), String> {
+    sqlx::query("INSERT INTO sale_items (sale_id, object_id, quantity, amount) VALUES (?, ?, ?, ?)")
+        .bind(sale_id)
+        .bind(object_id)
+        .bind(quantity)
+        .bind(amount)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn record_object_sale(
+    pool: State<'_, SqlitePool>,
+    sale: RecordObjectSale,
+) -> Result<SaleWithItems, String> {
+    let quantity = sale.quantity.unwrap_or(1);
+    if quantity < 1 {
+        return Err("The quantity sold must be at least 1".to_string());
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    decrement_stock(&mut tx, sale.object_id, quantity).await?;
+
+    let currency = sale.currency.unwrap_or_else(|| "EUR".to_string());
+    let sale_id = insert_sale(&mut tx, None, sale.platform_id, sale.total_price, &currency, &sale.notes).await?;
+    insert_sale_item(&mut tx, sale_id, sale.object_id, quantity, sale.total_price).await?;
+
+    let recorded = fetch_sale(&mut tx, sale_id).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(recorded)
+}
+
+// Sell a bundle: every object of the bundle leaves the stock and gets its part of the proceeds,
+// weighted by its share (or by its quantity when no share is set)
+#[tauri::command]
+pub async fn record_bundle_sale(
+    pool: State<'_, SqlitePool>,
+    sale: RecordBundleSale,
+) -> Result<SaleWithItems, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let bundle = sqlx::query_as::<_, Bundle>("SELECT * FROM bundles WHERE id = ?")
+        .bind(sale.bundle_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Bundle not found".to_string())?;
+
+    if bundle.status == "sold" {
+        return Err("This bundle has already been sold".to_string());
+    }
+
+    let total_price = sale
+        .total_price
+        .or(bundle.price)
+        .ok_or_else(|| "The bundle has no price, a sale price is required".to_string())?;
+
+    let items = bundles::fetch_bundle_items(&mut tx, bundle.id).await?;
+    if items.is_empty() {
+        return Err("This bundle contains no objects".to_string());
+    }
+
+    let weights: Vec<f64> = items
+        .iter()
+        .map(|item| item.share.unwrap_or(item.quantity as f64))
+        .collect();
+    let amounts = split_proceeds(total_price, &weights);
+
+    let currency = bundle.currency.clone().unwrap_or_else(|| "EUR".to_string());
+    let platform_id = sale.platform_id.or(bundle.platform_id);
+    let sale_id = insert_sale(&mut tx, Some(bundle.id), platform_id, total_price, &currency, &sale.notes).await?;
+
+    for (item, amount) in items.iter().zip(amounts) {
+        decrement_stock(&mut tx, item.object_id, item.quantity).await?;
+        insert_sale_item(&mut tx, sale_id, item.object_id, item.quantity, amount).await?;
+    }
+
+    sqlx::query("UPDATE bundles SET status = 'sold', updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(bundle.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let recorded = fetch_sale(&mut tx, sale_id).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(recorded)
+}
+
+// Sales, optionally only those involving an object
+#[tauri::command]
+pub async fn list_sales(
+    pool: State<'_, SqlitePool>,
+    object_id: Option<i64>,
+) -> Result<Vec<SaleWithItems>, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+
+    let sale_ids = sqlx::query_scalar::<_, i64>(
+        "SELECT id FROM sales
+         WHERE ? IS NULL OR id IN (SELECT sale_id FROM sale_items WHERE object_id = ?)
+         ORDER BY sold_at DESC, id DESC"
+    )
+    .bind(object_id)
+    .bind(object_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut sales = Vec::new();
+    for sale_id in sale_ids {
+        sales.push(fetch_sale(&mut conn, sale_id).await?);
+    }
+
+    Ok(sales)
+}
+
+// Cancel a sale: the objects go back to the stock and a sold bundle becomes available again.
+// Listings marked as sold are left untouched.
+#[tauri::command]
+pub async fn cancel_sale(pool: State<'_, SqlitePool>, id: i64) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let sale = fetch_sale(&mut tx, id).await?;
+
+    for item in &sale.items {
+        if let Some(object_id) = item.object_id {
+            sqlx::query("UPDATE objects SET quantity = quantity + ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+                .bind(item.quantity)
+                .bind(object_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    if let Some(bundle_id) = sale.sale.bundle_id {
+        sqlx::query(
+            "UPDATE bundles SET
+                status = CASE WHEN platform_url IS NOT NULL THEN 'listed' ELSE 'draft' END,
+                updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?"
+        )
+        .bind(bundle_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    sqlx::query("DELETE FROM sales WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(parts: &[f64]) -> f64 {
+        (parts.iter().sum::<f64>() * 100.0).round() / 100.0
+    }
+
+    #[test]
+    fn split_by_weight() {
+        assert_eq!(split_proceeds(100.0, &[3.0, 1.0]), [75.0, 25.0]);
+        assert_eq!(split_proceeds(10.0, &[1.0, 1.0, 1.0]), [3.34, 3.33, 3.33]);
+        assert_eq!(total(&split_proceeds(10.0, &[1.0, 1.0, 1.0])), 10.0);
+    }
+
+    #[test]
+    fn split_rounding_leftovers() {
+        let parts = split_proceeds(99.99, &[0.7, 0.2, 0.1]);
+        assert_eq!(total(&parts), 99.99);
+        assert_eq!(parts, [70.0, 20.0, 9.99]);
+    }
+
+    #[test]
+    fn split_without_weights() {
+        assert!(split_proceeds(10.0, &[]).is_empty());
+        assert_eq!(split_proceeds(1.0, &[0.0, 0.0]), [0.5, 0.5]);
+        assert_eq!(split_proceeds(0.0, &[1.0, 2.0]), [0.0, 0.0]);
+    }
+}
@@ -0,0 +1,568 @@
+use crate::models::{Category, Object};
+use chrono::{Datelike, Local};
+use pdf_writer::{Content, Name, Pdf, Rect, Ref, Str};
+use qrcode::{Color, QrCode};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use super::EXPORTS_FOLDER;
+
+const DEFAULT_SKU_PATTERN: &str = "{prefix}-{yy}{mm}-{seq:4}";
+const DEFAULT_SKU_PREFIX: &str = "SL";
+
+// ========== SKU GENERATOR ==========
+
+// Build a SKU from the pattern. Supported placeholders:
+// {prefix}, {yyyy}, {yy}, {mm}, {dd}, {id}, {seq} and {seq:N} (zero-padded to N digits)
+pub(crate) fn render_sku_pattern(
+    pattern: &str,
+    prefix: &str,
+    sequence: i64,
+    object_id: i64,
+) -> Result<String, String> {
+    let now = Local::now();
+    let mut sku = String::new();
+    let mut rest = pattern;
+
+    while let Some(start) = rest.find('{') {
+        sku.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| format!("Unclosed placeholder in SKU pattern '{}'", pattern))?;
+        let token = &rest[start + 1..end];
+
+        match token {
+            "prefix" => sku.push_str(prefix),
+            "yyyy" => sku.push_str(&format!("{:04}", now.year())),
+            "yy" => sku.push_str(&format!("{:02}", now.year() % 100)),
+            "mm" => sku.push_str(&format!("{:02}", now.month())),
+            "dd" => sku.push_str(&format!("{:02}", now.day())),
+            "id" => sku.push_str(&object_id.to_string()),
+            "seq" => sku.push_str(&sequence.to_string()),
+            _ => match token.strip_prefix("seq:").and_then(|width| width.parse::<usize>().ok()) {
+                Some(width) => sku.push_str(&format!("{:0width$}", sequence, width = width)),
+                None => return Err(format!("Unknown placeholder '{{{}}}' in SKU pattern", token)),
+            },
+        }
+
+        rest = &rest[end + 1..];
+    }
+    sku.push_str(rest);
+
+    if !pattern.contains("{seq") && !pattern.contains("{id}") {
+        return Err("SKU pattern must contain {seq} or {id} to generate unique codes".to_string());
+    }
+
+    Ok(sku)
+}
+
+// Refuse a pattern that can't generate SKUs
+pub(crate) fn check_sku_pattern(pattern: &str) -> Result<(), String> {
+    render_sku_pattern(pattern, DEFAULT_SKU_PREFIX, 1, 1).map(|_| ())
+}
+
+async fn read_setting(conn: &mut SqliteConnection, key: &str) -> Result<Option<String>, String> {
+    sqlx::query_scalar::<_, String>("SELECT value FROM settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Prefix used for the objects of a category: its own prefix, or the default prefix
+async fn sku_prefix_for(conn: &mut SqliteConnection, category_id: Option<i64>) -> Result<String, String> {
+    if let Some(category_id) = category_id {
+        let prefix = sqlx::query_scalar::<_, Option<String>>("SELECT sku_prefix FROM categories WHERE id = ?")
+            .bind(category_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?
+            .flatten()
+            .filter(|p| !p.trim().is_empty());
+        if let Some(prefix) = prefix {
+            return Ok(prefix);
+        }
+    }
+
+    Ok(read_setting(conn, "sku_default_prefix")
+        .await?
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_SKU_PREFIX.to_string()))
+}
+
+// Give the object a new SKU (keeps the existing one unless `regenerate` is set)
+pub(crate) async fn assign_sku(
+    conn: &mut SqliteConnection,
+    object_id: i64,
+    regenerate: bool,
+) -> Result<String, String> {
+    let object = sqlx::query_as::<_, Object>("SELECT * FROM objects WHERE id = ?")
+        .bind(object_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Object not found".to_string())?;
+
+    if let Some(sku) = object.sku.as_ref().filter(|s| !s.trim().is_empty()) {
+        if !regenerate {
+            return Ok(sku.clone());
+        }
+    }
+
+    let prefix = sku_prefix_for(conn, object.category_id).await?;
+    // A stored pattern that doesn't render (e.g. edited in the settings table) falls back to the default
+    let pattern = read_setting(conn, "sku_pattern")
+        .await?
+        .filter(|p| !p.trim().is_empty() && render_sku_pattern(p, &prefix, 1, object.id).is_ok())
+        .unwrap_or_else(|| DEFAULT_SKU_PATTERN.to_string());
+
+    // Skip sequence numbers whose SKU is already taken (e.g. entered by hand)
+    let sku = loop {
+        let sequence = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO sku_sequences (prefix, last_value) VALUES (?, 1)
+             ON CONFLICT(prefix) DO UPDATE SET last_value = last_value + 1
+             RETURNING last_value"
+        )
+        .bind(&prefix)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let sku = render_sku_pattern(&pattern, &prefix, sequence, object.id)?;
+
+        let taken = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM objects WHERE sku = ? AND id != ?")
+            .bind(&sku)
+            .bind(object.id)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if taken == 0 {
+            break sku;
+        }
+        if !pattern.contains("{seq") {
+            return Err(format!("SKU '{}' is already used by another object", sku));
+        }
+    };
+
+    sqlx::query("UPDATE objects SET sku = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(&sku)
+        .bind(object.id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(sku)
+}
+
+#[tauri::command]
+pub async fn generate_object_sku(
+    pool: State<'_, SqlitePool>,
+    object_id: i64,
+    regenerate: Option<bool>,
+) -> Result<Object, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    assign_sku(&mut tx, object_id, regenerate.unwrap_or(false)).await?;
+
+    let object = sqlx::query_as::<_, Object>("SELECT * FROM objects WHERE id = ?")
+        .bind(object_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(object)
+}
+
+// Generate a SKU for every object that doesn't have one yet; returns the number of SKUs generated
+#[tauri::command]
+pub async fn generate_missing_skus(pool: State<'_, SqlitePool>) -> Result<i32, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let object_ids = sqlx::query_scalar::<_, i64>(
+        "SELECT id FROM objects WHERE sku IS NULL OR TRIM(sku) = '' ORDER BY created_at ASC, id ASC"
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for object_id in &object_ids {
+        assign_sku(&mut tx, *object_id, true).await?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(object_ids.len() as i32)
+}
+
+// Preview the SKU the pattern would give, without consuming a sequence number
+#[tauri::command]
+pub async fn preview_sku_pattern(
+    pool: State<'_, SqlitePool>,
+    pattern: String,
+    category_id: Option<i64>,
+) -> Result<String, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+
+    let prefix = sku_prefix_for(&mut conn, category_id).await?;
+
+    let sequence = sqlx::query_scalar::<_, i64>("SELECT last_value FROM sku_sequences WHERE prefix = ?")
+        .bind(&prefix)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .unwrap_or(0)
+        + 1;
+
+    render_sku_pattern(&pattern, &prefix, sequence, 0)
+}
+
+// Save the SKU pattern, once checked; an empty pattern restores the default one
+#[tauri::command]
+pub async fn set_sku_pattern(pool: State<'_, SqlitePool>, pattern: String) -> Result<String, String> {
+    let pattern = match pattern.trim() {
+        "" => DEFAULT_SKU_PATTERN.to_string(),
+        pattern => pattern.to_string(),
+    };
+    check_sku_pattern(&pattern)?;
+
+    sqlx::query(
+        "INSERT INTO settings (key, value, updated_at) VALUES ('sku_pattern', ?, CURRENT_TIMESTAMP)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = CURRENT_TIMESTAMP"
+    )
+    .bind(&pattern)
+    .execute(pool.inner())
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(pattern)
+}
+
+#[tauri::command]
+pub async fn set_category_sku_prefix(
+    pool: State<'_, SqlitePool>,
+    category_id: i64,
+    prefix: Option<String>,
+) -> Result<Category, String> {
+    let prefix = prefix
+        .map(|p| p.trim().to_uppercase())
+        .filter(|p| !p.is_empty());
+
+    if let Some(prefix) = &prefix {
+        if !prefix.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err("SKU prefixes may only contain letters, digits, '-' and '_'".to_string());
+        }
+    }
+
+    let result = sqlx::query("UPDATE categories SET sku_prefix = ? WHERE id = ?")
+        .bind(&prefix)
+        .bind(category_id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if result.rows_affected() == 0 {
+        return Err("Category not found".to_string());
+    }
+
+    sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE id = ?")
+        .bind(category_id)
+        .fetch_one(pool.inner())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ========== LABEL SHEETS ==========
+
+// A4 portrait, in PDF points
+const PAGE_WIDTH: f32 = 595.28;
+const PAGE_HEIGHT: f32 = 841.89;
+const PAGE_MARGIN: f32 = 28.35;
+const LABEL_PADDING: f32 = 6.0;
+
+#[derive(Debug, Deserialize)]
+pub struct PrintLabelsRequest {
+    pub object_ids: Vec<i64>,
+    pub columns: Option<u32>,
+    pub rows: Option<u32>,
+    // Number of labels already used on the first sheet
+    pub skip_labels: Option<u32>,
+    pub copies: Option<u32>,
+    pub destination_path: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PrintLabelsResult {
+    pub file_path: String,
+    pub label_count: i32,
+    pub page_count: i32,
+}
+
+// Text shown with the standard Helvetica font (WinAnsi encoding)
+fn pdf_text(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| if (c as u32) < 256 && !c.is_control() { c as u8 } else { b'?' })
+        .collect()
+}
+
+// Shorten the text so that it fits in `width` points (Helvetica averages half an em per character)
+fn fit_text(text: &str, font_size: f32, width: f32) -> String {
+    let max_chars = (width / (font_size * 0.5)).floor().max(1.0) as usize;
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        let truncated: String = text.chars().take(max_chars.saturating_sub(3)).collect();
+        format!("{}...", truncated.trim_end())
+    }
+}
+
+fn draw_qr_code(content: &mut Content, data: &str, x: f32, y: f32, size: f32) -> Result<(), String> {
+    let code = QrCode::new(data.as_bytes()).map_err(|e| format!("Failed to create QR code: {}", e))?;
+    let width = code.width();
+    let module = size / width as f32;
+
+    content.set_fill_gray(0.0);
+    for (index, color) in code.to_colors().iter().enumerate() {
+        if *color == Color::Dark {
+            let column = (index % width) as f32;
+            let row = (index / width) as f32;
+            content.rect(x + column * module, y + size - (row + 1.0) * module, module, module);
+        }
+    }
+    content.fill_nonzero();
+
+    Ok(())
+}
+
+fn draw_label(
+    content: &mut Content,
+    sku: &str,
+    name: &str,
+    category: Option<&str>,
+    area: Rect,
+) -> Result<(), String> {
+    let (x, y) = (area.x1, area.y1);
+    let (width, height) = (area.x2 - area.x1, area.y2 - area.y1);
+    let qr_size = (height - 2.0 * LABEL_PADDING).min(width / 2.0);
+    draw_qr_code(content, sku, x + LABEL_PADDING, y + (height - qr_size) / 2.0, qr_size)?;
+
+    let text_x = x + 2.0 * LABEL_PADDING + qr_size;
+    let text_width = x + width - LABEL_PADDING - text_x;
+    let sku_size = 11.0;
+    let name_size = 8.0;
+    let mut text_y = y + height - LABEL_PADDING - sku_size;
+
+    content.begin_text();
+    content.set_font(Name(b"F2"), sku_size);
+    content.next_line(text_x, text_y);
+    content.show(Str(&pdf_text(&fit_text(sku, sku_size * 1.1, text_width))));
+    content.end_text();
+
+    text_y -= sku_size + 2.0;
+    let lines = if category.is_some() { 2 } else { 3 };
+    let mut remaining = name.trim().to_string();
+    for line in 0..lines {
+        if remaining.is_empty() || text_y < y + LABEL_PADDING {
+            break;
+        }
+        let max_chars = (text_width / (name_size * 0.5)).floor().max(1.0) as usize;
+        let text = if line + 1 == lines || remaining.chars().count() <= max_chars {
+            let text = fit_text(&remaining, name_size, text_width);
+            remaining.clear();
+            text
+        } else {
+            // Wrap on the last space that fits
+            let head: String = remaining.chars().take(max_chars).collect();
+            let cut = head.rfind(' ').filter(|i| *i > 0).unwrap_or(head.len());
+            let text = remaining[..cut].trim_end().to_string();
+            remaining = remaining[cut..].trim_start().to_string();
+            text
+        };
+
+        content.begin_text();
+        content.set_font(Name(b"F1"), name_size);
+        content.next_line(text_x, text_y);
+        content.show(Str(&pdf_text(&text)));
+        content.end_text();
+        text_y -= name_size + 2.0;
+    }
+
+    if let Some(category) = category {
+        if text_y >= y + LABEL_PADDING {
+            content.set_fill_gray(0.4);
+            content.begin_text();
+            content.set_font(Name(b"F1"), name_size);
+            content.next_line(text_x, text_y);
+            content.show(Str(&pdf_text(&fit_text(category, name_size, text_width))));
+            content.end_text();
+            content.set_fill_gray(0.0);
+        }
+    }
+
+    Ok(())
+}
+
+fn default_labels_path(root_path: &str) -> PathBuf {
+    let timestamp = Local::now().format("%y-%m-%d %H-%M-%S").to_string();
+    Path::new(root_path)
+        .join(EXPORTS_FOLDER)
+        .join("LABELS")
+        .join(format!("labels {}.pdf", timestamp))
+}
+
+// Print labels (SKU, name and a QR code of the SKU) on A4 sheets of `columns` x `rows` labels.
+// Objects without a SKU get one generated first.
+#[tauri::command]
+pub async fn print_object_labels(
+    pool: State<'_, SqlitePool>,
+    request: PrintLabelsRequest,
+) -> Result<PrintLabelsResult, String> {
+    if request.object_ids.is_empty() {
+        return Err("No objects selected".to_string());
+    }
+
+    let columns = request.columns.unwrap_or(3).clamp(1, 10);
+    let rows = request.rows.unwrap_or(8).clamp(1, 20);
+    let per_page = columns * rows;
+    let skip = request.skip_labels.unwrap_or(0) % per_page;
+    let copies = request.copies.unwrap_or(1).clamp(1, 100);
+
+    // Collect the labels, generating the missing SKUs
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut labels: Vec<(String, String, Option<String>)> = Vec::new();
+
+    for object_id in &request.object_ids {
+        let sku = assign_sku(&mut tx, *object_id, false).await?;
+        let (name, category) = sqlx::query_as::<_, (String, Option<String>)>(
+            "SELECT o.name, c.name FROM objects o LEFT JOIN categories c ON c.id = o.category_id WHERE o.id = ?"
+        )
+        .bind(object_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        for _ in 0..copies {
+            labels.push((sku.clone(), name.clone(), category.clone()));
+        }
+    }
+
+    let root_folder = read_setting(&mut tx, "root_folder").await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    let file_path = match request.destination_path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let root_path = root_folder.ok_or_else(|| "Root folder not configured".to_string())?;
+            default_labels_path(&root_path)
+        }
+    };
+
+    // Lay out the labels
+    let label_width = (PAGE_WIDTH - 2.0 * PAGE_MARGIN) / columns as f32;
+    let label_height = (PAGE_HEIGHT - 2.0 * PAGE_MARGIN) / rows as f32;
+
+    let mut pages: Vec<Content> = Vec::new();
+    for (index, (sku, name, category)) in labels.iter().enumerate() {
+        let position = index as u32 + skip;
+        let slot = position % per_page;
+        if slot == 0 || pages.is_empty() {
+            pages.push(Content::new());
+        }
+        let content = pages.last_mut().unwrap();
+
+        let x = PAGE_MARGIN + (slot % columns) as f32 * label_width;
+        let y = PAGE_HEIGHT - PAGE_MARGIN - (slot / columns + 1) as f32 * label_height;
+        let area = Rect::new(x, y, x + label_width, y + label_height);
+        draw_label(content, sku, name, category.as_deref(), area)?;
+    }
+
+    // Write the PDF
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let font_id = Ref::new(3);
+    let bold_font_id = Ref::new(4);
+    let page_ids: Vec<Ref> = (0..pages.len()).map(|i| Ref::new(5 + 2 * i as i32)).collect();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id)
+        .kids(page_ids.iter().copied())
+        .count(page_ids.len() as i32);
+    pdf.type1_font(font_id)
+        .base_font(Name(b"Helvetica"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+    pdf.type1_font(bold_font_id)
+        .base_font(Name(b"Helvetica-Bold"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+
+    let page_count = pages.len();
+    for (page_id, content) in page_ids.iter().zip(pages) {
+        let content_id = Ref::new(page_id.get() + 1);
+        {
+            let mut page = pdf.page(*page_id);
+            page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT));
+            page.parent(page_tree_id);
+            page.contents(content_id);
+            let mut resources = page.resources();
+            let mut fonts = resources.fonts();
+            fonts.pair(Name(b"F1"), font_id);
+            fonts.pair(Name(b"F2"), bold_font_id);
+        }
+        pdf.stream(content_id, &content.finish());
+    }
+
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create folder: {}", e))?;
+    }
+    fs::write(&file_path, pdf.finish()).map_err(|e| format!("Failed to write labels: {}", e))?;
+
+    Ok(PrintLabelsResult {
+        file_path: file_path.to_string_lossy().to_string(),
+        label_count: labels.len() as i32,
+        page_count: page_count as i32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequence_placeholders() {
+        assert_eq!(render_sku_pattern("{prefix}-{seq:4}", "SL", 42, 7).unwrap(), "SL-0042");
+        assert_eq!(render_sku_pattern("{prefix}{seq}", "LAMP", 42, 7).unwrap(), "LAMP42");
+        assert_eq!(render_sku_pattern("OBJ-{id}", "SL", 42, 7).unwrap(), "OBJ-7");
+        // Wider sequences are not cut
+        assert_eq!(render_sku_pattern("{seq:2}", "SL", 12345, 7).unwrap(), "12345");
+        assert_eq!(render_sku_pattern("{seq:0}", "SL", 5, 7).unwrap(), "5");
+    }
+
+    #[test]
+    fn date_placeholders() {
+        let now = Local::now();
+        let sku = render_sku_pattern("{yyyy}/{yy}{mm}{dd}-{seq}", "SL", 1, 1).unwrap();
+        let expected = format!(
+            "{:04}/{:02}{:02}{:02}-1",
+            now.year(),
+            now.year() % 100,
+            now.month(),
+            now.day()
+        );
+        assert_eq!(sku, expected);
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        assert!(render_sku_pattern("{prefix}-{seq", "SL", 1, 1).unwrap_err().starts_with("Unclosed placeholder"));
+        assert!(render_sku_pattern("{prefix}-{size}-{seq}", "SL", 1, 1).unwrap_err().contains("'{size}'"));
+        assert!(render_sku_pattern("{seq:x}", "SL", 1, 1).unwrap_err().contains("'{seq:x}'"));
+        assert!(render_sku_pattern("{prefix}-{yy}{mm}", "SL", 1, 1).unwrap_err().contains("{seq} or {id}"));
+        assert!(render_sku_pattern("", "SL", 1, 1).is_err());
+    }
+}
@@ -0,0 +1,1188 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection, SqlitePool};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use super::descriptions::{self, DescriptionSyncResult, Linked};
+use super::transfers::ensure_folder_row;
+use super::ignore::IgnoreRules;
+use super::{fileops, is_image_file, is_text_file, library, paths};
+
+// ========== DATABASE SYNC ==========
+// The sync is planned first: the plan lists every row it would remove or change and every file
+// it would import. A dry run only returns the plan; applying a reviewed plan re-checks each entry
+// so that nothing is removed if the files came back in the meantime.
+// The modification times of the folders and files are stored by each sync: the next one only
+// reads the folders that changed since.
+
+// Rows written by a single query when importing or storing modification times
+const WRITE_BATCH_SIZE: usize = 500;
+// Photos hashed by each sync among the rows recorded without a content hash
+const HASH_FILL_LIMIT: i64 = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncRow {
+    pub id: i64,
+    pub path: String,
+}
+
+// Object association lost with a removed photo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncAssociation {
+    pub photo_id: i64,
+    pub object_id: i64,
+    pub object_name: String,
+}
+
+// Folder row getting the folder of `parent_path` as parent (root level when None)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncFolderParent {
+    pub id: i64,
+    pub path: String,
+    pub old_parent_id: Option<i64>,
+    pub parent_path: Option<String>,
+}
+
+// Photo or text file row moving to the folder row of `folder_path`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncFileFolder {
+    pub id: i64,
+    pub path: String,
+    pub old_folder_id: Option<i64>,
+    pub folder_path: String,
+}
+
+// Photo row following its file, renamed or moved outside of the application
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncMovedPhoto {
+    pub id: i64,
+    pub old_path: String,
+    pub new_path: String,
+    // 'content_hash', 'size_mtime' or 'size_name'
+    pub matched_by: String,
+    // Hash of the new file when it was read
+    pub content_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncNewFile {
+    pub path: String,
+    // 'photo' or 'text_file'
+    pub kind: String,
+    pub file_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncMetadataChange {
+    // 'photos' or 'text_files'
+    pub table: String,
+    pub id: i64,
+    pub path: String,
+    // 'file_size', 'width' or 'height'
+    pub field: String,
+    pub old_value: Option<i64>,
+    pub new_value: Option<i64>,
+}
+
+// Modification time of a folder read by the sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncFolderFingerprint {
+    pub path: String,
+    pub mtime: Option<i64>,
+}
+
+// New modification time of a photo or text file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncFileFingerprint {
+    // 'photos' or 'text_files'
+    pub table: String,
+    pub id: i64,
+    pub path: String,
+    pub file_mtime: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncPlan {
+    pub root_folder: String,
+    // False when every folder was read (full sync)
+    pub incremental: bool,
+    // Rows whose file was looked at on disk
+    pub files_checked: usize,
+    pub photos_to_remove: Vec<SyncRow>,
+    pub text_files_to_remove: Vec<SyncRow>,
+    pub associations_to_remove: Vec<SyncAssociation>,
+    pub photos_to_move: Vec<SyncMovedPhoto>,
+    pub folders_to_create: Vec<String>,
+    pub folders_to_reparent: Vec<SyncFolderParent>,
+    pub photos_to_refolder: Vec<SyncFileFolder>,
+    pub text_files_to_refolder: Vec<SyncFileFolder>,
+    pub files_to_import: Vec<SyncNewFile>,
+    pub metadata_changes: Vec<SyncMetadataChange>,
+    pub folders_to_remove: Vec<SyncRow>,
+    pub scanned_folders: Vec<SyncFolderFingerprint>,
+    pub fingerprints: Vec<SyncFileFingerprint>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SyncDatabaseResult {
+    pub photos_removed: i32,
+    pub photos_moved: i32,
+    pub photos_updated: i32,
+    pub text_files_removed: i32,
+    pub text_files_updated: i32,
+    pub folders_cleaned: i32,
+    pub folders_created: i32,
+    pub folders_reparented: i32,
+    pub files_imported: i32,
+    pub metadata_updated: i32,
+    // Photos recorded without a content hash that were hashed
+    pub content_hashes_recorded: i32,
+    // description.txt files and descriptions of the objects linked to their folders
+    pub descriptions: DescriptionSyncResult,
+    pub errors: Vec<String>,
+    // False for a dry run
+    pub applied: bool,
+    pub plan: SyncPlan,
+}
+
+// Library folder row compared with the disk
+struct KnownFolder {
+    id: i64,
+    path: String,
+    parent_id: Option<i64>,
+    // Modification time seen by the last sync
+    mtime: Option<i64>,
+    exists: bool,
+    disk_mtime: Option<i64>,
+}
+
+impl KnownFolder {
+    // Nothing was added, removed or renamed in the folder since the last sync
+    fn unchanged(&self) -> bool {
+        self.exists && self.mtime.is_some() && self.mtime == self.disk_mtime
+    }
+}
+
+// Photo or text file row compared with the disk
+struct KnownFile {
+    // 'photos' or 'text_files'
+    table: &'static str,
+    id: i64,
+    path: String,
+    folder_id: Option<i64>,
+    file_size: Option<i64>,
+    file_mtime: Option<i64>,
+    width: Option<i32>,
+    height: Option<i32>,
+    content_hash: Option<String>,
+}
+
+#[derive(Default)]
+struct LibraryWalk {
+    // Every folder found under the root folder
+    folders: Vec<PathBuf>,
+    // Photos and text files of the folders that were read
+    files: Vec<PathBuf>,
+    read: Vec<SyncFolderFingerprint>,
+    unchanged: HashSet<PathBuf>,
+}
+
+// Walk the root folder. Only the folders whose modification time changed (or all of them with
+// `full`) are read; the sub-folders of the unchanged ones are known from the database.
+fn walk_library(
+    root: &Path,
+    known: &HashMap<PathBuf, &KnownFolder>,
+    rules: &IgnoreRules,
+    full: bool,
+    errors: &mut Vec<String>,
+) -> LibraryWalk {
+    let mut children: HashMap<&Path, Vec<&Path>> = HashMap::new();
+    for path in known.keys() {
+        if let Some(parent) = path.parent() {
+            children.entry(parent).or_default().push(path);
+        }
+    }
+
+    let mut walk = LibraryWalk::default();
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let folder = known.get(&dir);
+        if folder.is_some_and(|folder| !folder.exists) {
+            continue;
+        }
+        if dir != root {
+            walk.folders.push(dir.clone());
+        }
+
+        if !full && folder.is_some_and(|folder| folder.unchanged()) {
+            let sub_folders = children.get(dir.as_path()).into_iter().flatten();
+            stack.extend(sub_folders.filter(|path| !rules.is_ignored(root, path, true)).map(|path| path.to_path_buf()));
+            walk.unchanged.insert(dir);
+            continue;
+        }
+
+        // Read before listing the folder: a file added meanwhile is seen again by the next sync
+        let mtime = match folder {
+            Some(folder) => folder.disk_mtime,
+            None => fs::metadata(&dir).ok().and_then(|m| fileops::modified_millis(&m)),
+        };
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                errors.push(format!("Failed to read directory {}: {}", dir.display(), e));
+                continue;
+            }
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if !rules.is_ignored(root, &path, true) {
+                    stack.push(path);
+                }
+            } else if is_image_file(&path) || is_text_file(&path) {
+                walk.files.push(path);
+            }
+        }
+
+        if dir != root {
+            walk.read.push(SyncFolderFingerprint {
+                path: dir.to_string_lossy().to_string(),
+                mtime,
+            });
+        }
+    }
+
+    walk
+}
+
+// Without `full`, the files of the folders that didn't change since the last sync are not looked
+// at (a file edited in place is only seen by a full sync). Ignored files are not imported; the
+// rows already in the library are left as they are.
+async fn build_sync_plan(
+    conn: &mut SqliteConnection,
+    root: &str,
+    rules: &IgnoreRules,
+    full: bool,
+    errors: &mut Vec<String>,
+) -> Result<SyncPlan, String> {
+    let mut plan = SyncPlan {
+        root_folder: root.to_string(),
+        incremental: !full,
+        ..Default::default()
+    };
+    let root_path = Path::new(root);
+    let under_root = |path: &Path| !root.is_empty() && path != root_path && path.starts_with(root_path);
+
+    let rows = sqlx::query_as::<_, (i64, String, Option<i64>, Option<i64>)>("SELECT id, path, parent_id, mtime FROM folders")
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    let folder_paths: Vec<String> = rows.iter().map(|(_, path, _, _)| paths::to_absolute(root, path)).collect();
+    let on_disk = fileops::parallel_map(&folder_paths, |path| {
+        fs::metadata(path).ok().filter(|m| m.is_dir()).map(|m| fileops::modified_millis(&m))
+    });
+    let folders: Vec<KnownFolder> = rows
+        .into_iter()
+        .zip(folder_paths)
+        .zip(on_disk)
+        .map(|(((id, _, parent_id, mtime), path), disk)| KnownFolder {
+            id,
+            path,
+            parent_id,
+            mtime,
+            exists: disk.is_some(),
+            disk_mtime: disk.flatten(),
+        })
+        .collect();
+    let folder_ids: HashMap<String, i64> = folders.iter().map(|folder| (folder.path.clone(), folder.id)).collect();
+
+    let walk = if root_path.is_dir() {
+        let known: HashMap<PathBuf, &KnownFolder> = folders
+            .iter()
+            .filter(|folder| under_root(Path::new(&folder.path)))
+            .map(|folder| (PathBuf::from(&folder.path), folder))
+            .collect();
+        walk_library(root_path, &known, rules, full, errors)
+    } else {
+        LibraryWalk::default()
+    };
+
+    let photos = sqlx::query_as::<_, (i64, String, Option<i64>, Option<i64>, Option<i64>, Option<i32>, Option<i32>, Option<String>)>(
+        "SELECT id, file_path, folder_id, file_size, file_mtime, width, height, content_hash FROM photos"
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    let text_files = sqlx::query_as::<_, (i64, String, Option<i64>, Option<i64>, Option<i64>)>(
+        "SELECT id, file_path, folder_id, file_size, file_mtime FROM text_files"
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let photos = photos.into_iter().map(|(id, stored, folder_id, file_size, file_mtime, width, height, content_hash)| KnownFile {
+        table: "photos",
+        id,
+        path: paths::to_absolute(root, &stored),
+        folder_id,
+        file_size,
+        file_mtime,
+        width,
+        height,
+        content_hash,
+    });
+    let text_files = text_files.into_iter().map(|(id, stored, folder_id, file_size, file_mtime)| KnownFile {
+        table: "text_files",
+        id,
+        path: paths::to_absolute(root, &stored),
+        folder_id,
+        file_size,
+        file_mtime,
+        width: None,
+        height: None,
+        content_hash: None,
+    });
+    let files: Vec<KnownFile> = photos.chain(text_files).collect();
+
+    // Files can't appear, disappear or be renamed in a folder whose modification time didn't change
+    let to_check: Vec<&KnownFile> = files
+        .iter()
+        .filter(|file| !Path::new(&file.path).parent().is_some_and(|parent| walk.unchanged.contains(parent)))
+        .collect();
+    plan.files_checked = to_check.len();
+    let on_disk = fileops::parallel_map(&to_check, |file| {
+        fs::metadata(&file.path).ok().map(|m| (m.len() as i64, fileops::modified_millis(&m)))
+    });
+
+    let mut missing: HashSet<(&str, i64)> = HashSet::new();
+    let mut resized = Vec::new();
+
+    for (file, disk) in to_check.iter().zip(on_disk) {
+        let Some((size, mtime)) = disk else {
+            let row = SyncRow { id: file.id, path: file.path.clone() };
+            if file.table == "photos" {
+                plan.photos_to_remove.push(row);
+            } else {
+                plan.text_files_to_remove.push(row);
+            }
+            missing.insert((file.table, file.id));
+            continue;
+        };
+
+        if file.file_size != Some(size) {
+            plan.metadata_changes.push(metadata_change(file, "file_size", file.file_size, Some(size)));
+            if file.table == "photos" {
+                resized.push(*file);
+            }
+        }
+        if file.file_mtime != mtime {
+            plan.fingerprints.push(SyncFileFingerprint {
+                table: file.table.to_string(),
+                id: file.id,
+                path: file.path.clone(),
+                file_mtime: mtime,
+            });
+        }
+    }
+
+    // The photos edited outside of the application may have new dimensions too (only the image
+    // header is read)
+    let dimensions = fileops::parallel_map(&resized, |file| image::image_dimensions(&file.path).ok());
+    for (file, dimensions) in resized.iter().zip(dimensions) {
+        let Some((width, height)) = dimensions else {
+            continue;
+        };
+        if file.width != Some(width as i32) {
+            plan.metadata_changes.push(metadata_change(file, "width", file.width.map(i64::from), Some(width as i64)));
+        }
+        if file.height != Some(height as i32) {
+            plan.metadata_changes.push(metadata_change(file, "height", file.height.map(i64::from), Some(height as i64)));
+        }
+    }
+
+    // Folder row a file should belong to: None when there is none to give (root folder, outside
+    // of the library without a row)
+    let expected_folder = |file: &Path| -> Option<(Option<i64>, String)> {
+        let parent = file.parent()?;
+        let parent_str = parent.to_string_lossy().to_string();
+        match folder_ids.get(&parent_str) {
+            Some(id) => Some((Some(*id), parent_str)),
+            None if under_root(parent) => Some((None, parent_str)),
+            None => None,
+        }
+    };
+
+    let mut kept_folder_ids: HashSet<i64> = HashSet::new();
+
+    for file in files.iter().filter(|file| !missing.contains(&(file.table, file.id))) {
+        kept_folder_ids.extend(file.folder_id);
+
+        if let Some((expected_id, folder_path)) = expected_folder(Path::new(&file.path)) {
+            if expected_id.is_none() || expected_id != file.folder_id {
+                let row = SyncFileFolder {
+                    id: file.id,
+                    path: file.path.clone(),
+                    old_folder_id: file.folder_id,
+                    folder_path,
+                };
+                if file.table == "photos" {
+                    plan.photos_to_refolder.push(row);
+                } else {
+                    plan.text_files_to_refolder.push(row);
+                }
+            }
+        }
+    }
+
+    // New folders and files under the root folder
+    let mut disk_folders = walk.folders;
+    disk_folders.sort();
+    for folder in disk_folders {
+        let folder_str = folder.to_string_lossy().to_string();
+        if !folder_ids.contains_key(&folder_str) {
+            plan.folders_to_create.push(folder_str);
+        }
+    }
+
+    let known_files: HashSet<&str> = files.iter().map(|file| file.path.as_str()).collect();
+    let mut new_files: Vec<PathBuf> = walk
+        .files
+        .into_iter()
+        .filter(|file| !known_files.contains(file.to_string_lossy().as_ref()))
+        .collect();
+    new_files.sort();
+    let skipped = fileops::parallel_map(&new_files, |file| rules.skips_file(root_path, file));
+    let new_files: Vec<PathBuf> = new_files
+        .into_iter()
+        .zip(skipped)
+        .filter_map(|(file, skipped)| (!skipped).then_some(file))
+        .collect();
+    let on_disk = fileops::parallel_map(&new_files, |file| {
+        fs::metadata(file).map(|m| (m.len() as i64, fileops::modified_millis(&m))).unwrap_or((0, None))
+    });
+
+    let mut new_mtimes = Vec::new();
+    for (file, (file_size, mtime)) in new_files.into_iter().zip(on_disk) {
+        plan.files_to_import.push(SyncNewFile {
+            kind: if is_image_file(&file) { "photo" } else { "text_file" }.to_string(),
+            path: file.to_string_lossy().to_string(),
+            file_size,
+        });
+        new_mtimes.push(mtime);
+    }
+
+    // Photos renamed or moved outside of the application keep their row (and their objects)
+    // instead of being removed and imported again
+    let missing_photos: Vec<&KnownFile> = files
+        .iter()
+        .filter(|file| file.table == "photos" && missing.contains(&(file.table, file.id)))
+        .collect();
+    let moves = match_moved_photos(&missing_photos, &plan.files_to_import, &new_mtimes);
+    let moved_ids: HashSet<i64> = moves.iter().map(|moved| moved.id).collect();
+    let moved_paths: HashSet<&str> = moves.iter().map(|moved| moved.new_path.as_str()).collect();
+    plan.photos_to_remove.retain(|photo| !moved_ids.contains(&photo.id));
+    plan.files_to_import.retain(|file| !moved_paths.contains(file.path.as_str()));
+    plan.photos_to_move = moves;
+
+    // Associations that go away with the removed photos
+    for photo in &plan.photos_to_remove {
+        let associations = sqlx::query_as::<_, (i64, String)>(
+            "SELECT o.id, o.name FROM object_photos op INNER JOIN objects o ON o.id = op.object_id WHERE op.photo_id = ?"
+        )
+        .bind(photo.id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        for (object_id, object_name) in associations {
+            plan.associations_to_remove.push(SyncAssociation { photo_id: photo.id, object_id, object_name });
+        }
+    }
+
+    for folder in &folders {
+        let path = Path::new(&folder.path);
+
+        if !folder.exists {
+            // Folders still holding files that stay in the library are kept
+            if !kept_folder_ids.contains(&folder.id) {
+                plan.folders_to_remove.push(SyncRow { id: folder.id, path: folder.path.clone() });
+            }
+            continue;
+        }
+        if !under_root(path) {
+            continue;
+        }
+
+        // Folders directly under the root folder have no parent
+        let parent_path = path
+            .parent()
+            .filter(|parent| *parent != root_path)
+            .map(|parent| parent.to_string_lossy().to_string());
+        let up_to_date = match &parent_path {
+            None => folder.parent_id.is_none(),
+            Some(parent) => folder.parent_id.is_some() && folder_ids.get(parent) == folder.parent_id.as_ref(),
+        };
+        if !up_to_date {
+            plan.folders_to_reparent.push(SyncFolderParent {
+                id: folder.id,
+                path: folder.path.clone(),
+                old_parent_id: folder.parent_id,
+                parent_path,
+            });
+        }
+    }
+
+    plan.scanned_folders = walk.read;
+    Ok(plan)
+}
+
+// Pair missing photos with new files holding the same picture: same content hash when the hash of
+// the photo is known, otherwise the same size and modification time (kept by a rename or a move),
+// or the same size and file name when the modification time was never recorded. Ambiguous
+// matches are left to the removal and the import.
+fn match_moved_photos(missing: &[&KnownFile], new_files: &[SyncNewFile], new_mtimes: &[Option<i64>]) -> Vec<SyncMovedPhoto> {
+    let same_size = |photo: &KnownFile| -> Vec<usize> {
+        new_files
+            .iter()
+            .enumerate()
+            .filter(|(_, file)| file.kind == "photo" && photo.file_size == Some(file.file_size))
+            .map(|(index, _)| index)
+            .collect()
+    };
+    let file_name = |path: &str| Path::new(path).file_name().map(|name| name.to_os_string());
+
+    // Only the new files that may be one of the photos with a known hash are read
+    let mut to_hash: Vec<usize> = missing
+        .iter()
+        .filter(|photo| photo.content_hash.is_some())
+        .flat_map(|photo| same_size(photo))
+        .collect();
+    to_hash.sort_unstable();
+    to_hash.dedup();
+    let hashes: HashMap<usize, String> = to_hash
+        .iter()
+        .zip(fileops::parallel_map(&to_hash, |index| fileops::file_hash(Path::new(&new_files[*index].path)).ok()))
+        .filter_map(|(index, hash)| Some((*index, hash?)))
+        .collect();
+
+    let mut taken: HashSet<usize> = HashSet::new();
+    let mut moves = Vec::new();
+
+    for photo in missing {
+        let candidates: Vec<usize> = same_size(photo).into_iter().filter(|index| !taken.contains(index)).collect();
+        let (mut matches, matched_by): (Vec<usize>, &str) = match &photo.content_hash {
+            Some(hash) => (
+                candidates.into_iter().filter(|index| hashes.get(index) == Some(hash)).collect(),
+                "content_hash",
+            ),
+            None if photo.file_mtime.is_some() => (
+                candidates.into_iter().filter(|index| new_mtimes[*index] == photo.file_mtime).collect(),
+                "size_mtime",
+            ),
+            None => (
+                candidates
+                    .into_iter()
+                    .filter(|index| file_name(&new_files[*index].path) == file_name(&photo.path))
+                    .collect(),
+                "size_name",
+            ),
+        };
+
+        // Several candidates: the one that kept the file name
+        if matches.len() > 1 {
+            let same_name: Vec<usize> = matches
+                .iter()
+                .copied()
+                .filter(|index| file_name(&new_files[*index].path) == file_name(&photo.path))
+                .collect();
+            if !same_name.is_empty() {
+                matches = same_name;
+            }
+        }
+        // Copies with the same content are all the same picture
+        let index = match matches[..] {
+            [index] => index,
+            [index, ..] if matched_by == "content_hash" => index,
+            _ => continue,
+        };
+        taken.insert(index);
+        moves.push(SyncMovedPhoto {
+            id: photo.id,
+            old_path: photo.path.clone(),
+            new_path: new_files[index].path.clone(),
+            matched_by: matched_by.to_string(),
+            content_hash: hashes.get(&index).cloned(),
+        });
+    }
+
+    moves
+}
+
+fn metadata_change(file: &KnownFile, field: &str, old_value: Option<i64>, new_value: Option<i64>) -> SyncMetadataChange {
+    SyncMetadataChange {
+        table: file.table.to_string(),
+        id: file.id,
+        path: file.path.clone(),
+        field: field.to_string(),
+        old_value,
+        new_value,
+    }
+}
+
+// Folder row id for a folder on disk (created if needed under the root folder)
+async fn folder_row_id(conn: &mut SqliteConnection, path: &str, root: &str, created: &mut Vec<i64>) -> Result<Option<i64>, String> {
+    let existing = sqlx::query_scalar::<_, i64>("SELECT id FROM folders WHERE path = ?")
+        .bind(paths::to_stored(root, path))
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    match existing {
+        Some(id) => Ok(Some(id)),
+        None if Path::new(path).is_dir() => ensure_folder_row(conn, Path::new(path), root, created).await,
+        None => Ok(None),
+    }
+}
+
+// Row of a new photo or text file
+struct NewFileRow {
+    stored: String,
+    file_name: String,
+    file_size: i64,
+    file_mtime: Option<i64>,
+    width: Option<i32>,
+    height: Option<i32>,
+    folder_id: Option<i64>,
+    content_hash: Option<String>,
+}
+
+async fn import_files(
+    conn: &mut SqliteConnection,
+    files: &[SyncNewFile],
+    root: &str,
+    created: &mut Vec<i64>,
+    result: &mut SyncDatabaseResult,
+) -> Result<(), String> {
+    // Sizes, dimensions (only the image headers) and content hashes of the photos are read on all
+    // the cores before writing
+    let on_disk = fileops::parallel_map(files, |file| {
+        let metadata = fs::metadata(&file.path).ok()?;
+        let (dimensions, content_hash) = if file.kind == "photo" {
+            let path = Path::new(&file.path);
+            (image::image_dimensions(path).ok(), fileops::file_hash(path).ok())
+        } else {
+            (None, None)
+        };
+        Some((metadata.len() as i64, fileops::modified_millis(&metadata), dimensions, content_hash))
+    });
+
+    let mut folder_rows: HashMap<PathBuf, Option<i64>> = HashMap::new();
+    let (mut photos, mut text_files) = (Vec::new(), Vec::new());
+
+    for (file, disk) in files.iter().zip(on_disk) {
+        let Some((file_size, file_mtime, dimensions, content_hash)) = disk else {
+            result.errors.push(format!("Source file not found: {}", file.path));
+            continue;
+        };
+        let path = Path::new(&file.path);
+        let folder_id = match path.parent() {
+            Some(parent) => match folder_rows.get(parent) {
+                Some(id) => *id,
+                None => {
+                    let id = folder_row_id(conn, &parent.to_string_lossy(), root, created).await?;
+                    folder_rows.insert(parent.to_path_buf(), id);
+                    id
+                }
+            },
+            None => None,
+        };
+
+        let row = NewFileRow {
+            stored: paths::to_stored(root, &file.path),
+            file_name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+            file_size,
+            file_mtime,
+            width: dimensions.map(|(width, _)| width as i32),
+            height: dimensions.map(|(_, height)| height as i32),
+            folder_id,
+            content_hash,
+        };
+        if file.kind == "photo" {
+            photos.push(row);
+        } else {
+            text_files.push(row);
+        }
+    }
+
+    for batch in photos.chunks(WRITE_BATCH_SIZE) {
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "INSERT OR IGNORE INTO photos (file_path, original_path, file_name, file_size, file_mtime, width, height, folder_id, content_hash) "
+        );
+        query.push_values(batch, |mut values, row| {
+            values
+                .push_bind(row.stored.clone())
+                .push_bind(row.stored.clone())
+                .push_bind(row.file_name.clone())
+                .push_bind(row.file_size)
+                .push_bind(row.file_mtime)
+                .push_bind(row.width)
+                .push_bind(row.height)
+                .push_bind(row.folder_id)
+                .push_bind(row.content_hash.clone());
+        });
+        let inserted = query.build().execute(&mut *conn).await.map_err(|e| e.to_string())?.rows_affected() as i32;
+        result.files_imported += inserted;
+        result.photos_updated += inserted;
+    }
+
+    for batch in text_files.chunks(WRITE_BATCH_SIZE) {
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "INSERT OR IGNORE INTO text_files (file_path, file_name, file_size, file_mtime, folder_id) "
+        );
+        query.push_values(batch, |mut values, row| {
+            values
+                .push_bind(row.stored.clone())
+                .push_bind(row.file_name.clone())
+                .push_bind(row.file_size)
+                .push_bind(row.file_mtime)
+                .push_bind(row.folder_id);
+        });
+        let inserted = query.build().execute(&mut *conn).await.map_err(|e| e.to_string())?.rows_affected() as i32;
+        result.files_imported += inserted;
+        result.photos_updated += inserted;
+    }
+
+    Ok(())
+}
+
+// Store modification times, a batch of rows per query. Rows are matched on their stored path, and
+// on their id when given.
+async fn store_mtimes(
+    conn: &mut SqliteConnection,
+    table: &str,
+    path_column: &str,
+    mtime_column: &str,
+    rows: &[(Option<i64>, String, Option<i64>)],
+) -> Result<(), String> {
+    for batch in rows.chunks(WRITE_BATCH_SIZE) {
+        let mut query = QueryBuilder::<Sqlite>::new("WITH seen (id, path, mtime) AS (");
+        query.push_values(batch, |mut values, (id, path, mtime)| {
+            values.push_bind(*id).push_bind(path.clone()).push_bind(*mtime);
+        });
+        query.push(format!(
+            ") UPDATE {table} SET {mtime_column} = seen.mtime FROM seen
+             WHERE {table}.{path_column} = seen.path AND (seen.id IS NULL OR {table}.id = seen.id)"
+        ));
+        query.build().execute(&mut *conn).await.map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+async fn apply_plan(conn: &mut SqliteConnection, plan: &SyncPlan, result: &mut SyncDatabaseResult) -> Result<(), String> {
+    let root = plan.root_folder.as_str();
+    let mut created = Vec::new();
+
+    // Rows of missing files, only if the row and the file didn't change since the plan was made
+    let removals = [
+        ("photos", &plan.photos_to_remove),
+        ("text_files", &plan.text_files_to_remove),
+    ];
+    for (table, rows) in removals {
+        for row in rows {
+            if Path::new(&row.path).exists() {
+                result.errors.push(format!("{} is back on disk, its row was kept", row.path));
+                continue;
+            }
+            let removed = sqlx::query(&format!("DELETE FROM {} WHERE id = ? AND file_path = ?", table))
+                .bind(row.id)
+                .bind(paths::to_stored(root, &row.path))
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+            if removed.rows_affected() == 0 {
+                result.errors.push(format!("{} changed since the plan was made, skipped", row.path));
+            } else if table == "photos" {
+                result.photos_removed += 1;
+            } else {
+                result.text_files_removed += 1;
+            }
+        }
+    }
+
+    for folder in &plan.folders_to_create {
+        if folder_row_id(conn, folder, root, &mut created).await?.is_none() {
+            result.errors.push(format!("Folder not found: {}", folder));
+        }
+    }
+
+    // Moved photos keep their id: object associations and main photo flags stay in place
+    for moved in &plan.photos_to_move {
+        let new_path = Path::new(&moved.new_path);
+        if Path::new(&moved.old_path).exists() {
+            result.errors.push(format!("{} is back on disk, its row was kept", moved.old_path));
+            continue;
+        }
+        let Ok(metadata) = fs::metadata(new_path) else {
+            result.errors.push(format!("Source file not found: {}", moved.new_path));
+            continue;
+        };
+        let folder_id = match new_path.parent() {
+            Some(parent) => folder_row_id(conn, &parent.to_string_lossy(), root, &mut created).await?,
+            None => None,
+        };
+        let new_stored = paths::to_stored(root, &moved.new_path);
+
+        let updated = sqlx::query(
+            "UPDATE photos SET file_path = ?1,
+                original_path = CASE WHEN original_path = file_path THEN ?1 ELSE original_path END,
+                file_name = ?2, folder_id = ?3, file_mtime = ?4, content_hash = COALESCE(?5, content_hash)
+             WHERE id = ?6 AND file_path = ?7 AND NOT EXISTS (SELECT 1 FROM photos WHERE file_path = ?1)"
+        )
+        .bind(&new_stored)
+        .bind(new_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default())
+        .bind(folder_id)
+        .bind(fileops::modified_millis(&metadata))
+        .bind(&moved.content_hash)
+        .bind(moved.id)
+        .bind(paths::to_stored(root, &moved.old_path))
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+        if updated.rows_affected() == 0 {
+            result.errors.push(format!("{} changed since the plan was made, skipped", moved.old_path));
+        } else {
+            result.photos_moved += 1;
+        }
+    }
+
+    import_files(conn, &plan.files_to_import, root, &mut created, result).await?;
+
+    let refolders = [
+        ("photos", &plan.photos_to_refolder),
+        ("text_files", &plan.text_files_to_refolder),
+    ];
+    for (table, rows) in refolders {
+        for row in rows {
+            let Some(folder_id) = folder_row_id(conn, &row.folder_path, root, &mut created).await? else {
+                result.errors.push(format!("Folder not found: {}", row.folder_path));
+                continue;
+            };
+            let updated = sqlx::query(&format!("UPDATE {} SET folder_id = ? WHERE id = ? AND file_path = ?", table))
+                .bind(folder_id)
+                .bind(row.id)
+                .bind(paths::to_stored(root, &row.path))
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+            if updated.rows_affected() == 0 {
+                result.errors.push(format!("{} changed since the plan was made, skipped", row.path));
+            } else if table == "photos" {
+                result.photos_updated += 1;
+            } else {
+                result.text_files_updated += 1;
+            }
+        }
+    }
+
+    for folder in &plan.folders_to_reparent {
+        let parent_id = match &folder.parent_path {
+            Some(parent_path) => match folder_row_id(conn, parent_path, root, &mut created).await? {
+                Some(id) => Some(id),
+                None => {
+                    result.errors.push(format!("Folder not found: {}", parent_path));
+                    continue;
+                }
+            },
+            None => None,
+        };
+        let updated = sqlx::query("UPDATE folders SET parent_id = ? WHERE id = ? AND path = ?")
+            .bind(parent_id)
+            .bind(folder.id)
+            .bind(paths::to_stored(root, &folder.path))
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        if updated.rows_affected() > 0 {
+            result.folders_reparented += 1;
+        }
+    }
+
+    for change in &plan.metadata_changes {
+        let (table, field) = match (change.table.as_str(), change.field.as_str()) {
+            ("photos", field @ ("file_size" | "width" | "height")) => ("photos", field),
+            ("text_files", "file_size") => ("text_files", "file_size"),
+            _ => return Err(format!("Unknown metadata change: {}.{}", change.table, change.field)),
+        };
+        let updated = sqlx::query(&format!("UPDATE {} SET {} = ? WHERE id = ? AND file_path = ?", table, field))
+            .bind(change.new_value)
+            .bind(change.id)
+            .bind(paths::to_stored(root, &change.path))
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        if updated.rows_affected() > 0 {
+            result.metadata_updated += 1;
+        }
+    }
+
+    // Folders removed from disk, once the rows of their files are gone
+    for folder in &plan.folders_to_remove {
+        if Path::new(&folder.path).exists() {
+            result.errors.push(format!("{} is back on disk, its row was kept", folder.path));
+            continue;
+        }
+        let still_used = sqlx::query_scalar::<_, i64>(
+            "SELECT (SELECT COUNT(*) FROM photos WHERE folder_id = ?) + (SELECT COUNT(*) FROM text_files WHERE folder_id = ?)"
+        )
+        .bind(folder.id)
+        .bind(folder.id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+        if still_used > 0 {
+            result.errors.push(format!("{} still holds files, its row was kept", folder.path));
+            continue;
+        }
+
+        let removed = sqlx::query("DELETE FROM folders WHERE id = ? AND path = ?")
+            .bind(folder.id)
+            .bind(paths::to_stored(root, &folder.path))
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        if removed.rows_affected() > 0 {
+            result.folders_cleaned += 1;
+        }
+    }
+
+    // Modification times last, once the rows of the new folders exist
+    for table in ["photos", "text_files"] {
+        let rows: Vec<_> = plan
+            .fingerprints
+            .iter()
+            .filter(|fingerprint| fingerprint.table == table)
+            .map(|fingerprint| (Some(fingerprint.id), paths::to_stored(root, &fingerprint.path), fingerprint.file_mtime))
+            .collect();
+        store_mtimes(conn, table, "file_path", "file_mtime", &rows).await?;
+    }
+    let folder_rows: Vec<_> = plan
+        .scanned_folders
+        .iter()
+        .map(|folder| (None, paths::to_stored(root, &folder.path), folder.mtime))
+        .collect();
+    store_mtimes(conn, "folders", "path", "mtime", &folder_rows).await?;
+
+    result.folders_created = created.len() as i32;
+    Ok(())
+}
+
+// Refuse to work on a library whose drive is unplugged (every file would look deleted)
+async fn online_root(conn: &mut SqliteConnection) -> Result<String, String> {
+    let root = paths::library_root(&mut *conn).await?;
+    if let Some(reason) = library::offline_reason(conn, &root).await? {
+        return Err(format!("{}. The library is offline, nothing was removed.", reason));
+    }
+    Ok(root)
+}
+
+// Compare the database with the files on disk. With `dry_run`, only the planned changes are
+// returned (see `apply_sync_plan` to apply them once reviewed). Only the folders that changed
+// since the last sync are read, unless `full` is set.
+#[tauri::command]
+pub async fn sync_database(
+    pool: State<'_, SqlitePool>,
+    dry_run: Option<bool>,
+    full: Option<bool>,
+) -> Result<SyncDatabaseResult, String> {
+    let rules = IgnoreRules::load(pool.inner()).await?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    let root = online_root(&mut conn).await?;
+
+    let mut result = SyncDatabaseResult::default();
+    result.plan = build_sync_plan(&mut conn, &root, &rules, full.unwrap_or(false), &mut result.errors).await?;
+    drop(conn);
+
+    if dry_run.unwrap_or(false) {
+        return Ok(result);
+    }
+
+    let plan = std::mem::take(&mut result.plan);
+    apply_reviewed_plan(pool.inner(), plan, result).await
+}
+
+#[tauri::command]
+pub async fn apply_sync_plan(pool: State<'_, SqlitePool>, plan: SyncPlan) -> Result<SyncDatabaseResult, String> {
+    apply_reviewed_plan(pool.inner(), plan, SyncDatabaseResult::default()).await
+}
+
+async fn apply_reviewed_plan(
+    pool: &SqlitePool,
+    plan: SyncPlan,
+    mut result: SyncDatabaseResult,
+) -> Result<SyncDatabaseResult, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let root = online_root(&mut tx).await?;
+    if root != plan.root_folder {
+        return Err("The root folder changed since the plan was made, sync again".to_string());
+    }
+
+    apply_plan(&mut tx, &plan, &mut result)
+        .await
+        .map_err(|e| format!("Nothing was changed: {}", e))?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    // The plan is committed: failures from here on are reported with the other errors
+    match fill_content_hashes(pool, &root).await {
+        Ok(count) => result.content_hashes_recorded = count,
+        Err(e) => result.errors.push(format!("Content hashes were not recorded: {}", e)),
+    }
+
+    // Description files edited outside the application since the last sync
+    match sync_linked_descriptions(pool, &root).await {
+        Ok(mut descriptions) => {
+            result.errors.append(&mut descriptions.errors);
+            result.descriptions = descriptions;
+        }
+        Err(e) => result.errors.push(format!("Descriptions were not synced: {}", e)),
+    }
+
+    result.applied = true;
+    result.plan = plan;
+    Ok(result)
+}
+
+// Hash the photos recorded without a content hash (imported before hashes were kept), a batch
+// per sync, so that they can be matched by content if they are moved later
+async fn fill_content_hashes(pool: &SqlitePool, root: &str) -> Result<i32, String> {
+    let rows = sqlx::query_as::<_, (i64, String)>(
+        "SELECT id, file_path FROM photos WHERE content_hash IS NULL ORDER BY id LIMIT ?"
+    )
+    .bind(HASH_FILL_LIMIT)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let hashes = fileops::parallel_map(&rows, |(_, stored)| fileops::file_hash(Path::new(&paths::to_absolute(root, stored))).ok());
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut recorded = 0;
+    for ((id, stored), hash) in rows.iter().zip(hashes) {
+        let Some(hash) = hash else {
+            continue;
+        };
+        // Only if the row still points to the file that was read
+        let updated = sqlx::query("UPDATE photos SET content_hash = ? WHERE id = ? AND file_path = ? AND content_hash IS NULL")
+            .bind(hash)
+            .bind(id)
+            .bind(stored)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        recorded += updated.rows_affected() as i32;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(recorded)
+}
+
+async fn sync_linked_descriptions(pool: &SqlitePool, root: &str) -> Result<DescriptionSyncResult, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let result = descriptions::sync_linked(&mut tx, root, Linked::All).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn known(id: i64, path: &str, size: i64, mtime: Option<i64>, hash: Option<&str>) -> KnownFile {
+        KnownFile {
+            table: "photos",
+            id,
+            path: path.to_string(),
+            folder_id: None,
+            file_size: Some(size),
+            file_mtime: mtime,
+            width: None,
+            height: None,
+            content_hash: hash.map(str::to_string),
+        }
+    }
+
+    fn new_file(path: &str, kind: &str, size: i64) -> SyncNewFile {
+        SyncNewFile { path: path.to_string(), kind: kind.to_string(), file_size: size }
+    }
+
+    fn matched(moves: &[SyncMovedPhoto]) -> Vec<(i64, &str, &str)> {
+        moves.iter().map(|m| (m.id, m.new_path.as_str(), m.matched_by.as_str())).collect()
+    }
+
+    #[test]
+    fn moved_photos_by_size_and_mtime() {
+        let photo = known(1, "/lib/a/p.jpg", 10, Some(5), None);
+        let new_files = [
+            new_file("/lib/b/renamed.jpg", "photo", 10),
+            new_file("/lib/b/other.jpg", "photo", 10),
+            new_file("/lib/b/bigger.jpg", "photo", 11),
+            new_file("/lib/b/notes.txt", "text_file", 10),
+        ];
+        let moves = match_moved_photos(&[&photo], &new_files, &[Some(5), Some(6), Some(5), Some(5)]);
+        assert_eq!(matched(&moves), [(1, "/lib/b/renamed.jpg", "size_mtime")]);
+
+        // Two files with the same size and time: the one that kept the name, otherwise none
+        let new_files = [new_file("/lib/b/p.jpg", "photo", 10), new_file("/lib/c/q.jpg", "photo", 10)];
+        let moves = match_moved_photos(&[&photo], &new_files, &[Some(5), Some(5)]);
+        assert_eq!(matched(&moves), [(1, "/lib/b/p.jpg", "size_mtime")]);
+        let new_files = [new_file("/lib/b/x.jpg", "photo", 10), new_file("/lib/c/y.jpg", "photo", 10)];
+        assert!(match_moved_photos(&[&photo], &new_files, &[Some(5), Some(5)]).is_empty());
+    }
+
+    #[test]
+    fn moved_photos_by_size_and_name() {
+        let photo = known(1, "/lib/a/p.jpg", 10, None, None);
+        let new_files = [new_file("/lib/b/q.jpg", "photo", 10), new_file("/lib/b/p.jpg", "photo", 10)];
+        let moves = match_moved_photos(&[&photo], &new_files, &[None, None]);
+        assert_eq!(matched(&moves), [(1, "/lib/b/p.jpg", "size_name")]);
+    }
+
+    #[test]
+    fn moved_photos_are_matched_once() {
+        let first = known(1, "/lib/a/p.jpg", 10, Some(5), None);
+        let second = known(2, "/lib/a/q.jpg", 10, Some(5), None);
+        let new_files = [new_file("/lib/b/p.jpg", "photo", 10)];
+        let moves = match_moved_photos(&[&first, &second], &new_files, &[Some(5)]);
+        assert_eq!(matched(&moves), [(1, "/lib/b/p.jpg", "size_mtime")]);
+    }
+
+    #[test]
+    fn moved_photos_by_content_hash() {
+        let dir = std::env::temp_dir().join(format!("seller-library-sync-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let same = dir.join("same.jpg");
+        let other = dir.join("other.jpg");
+        fs::write(&same, "picture").unwrap();
+        fs::write(&other, "another").unwrap();
+        let hash = fileops::file_hash(&same).unwrap();
+
+        let photo = known(1, "/lib/a/p.jpg", 7, Some(5), Some(&hash));
+        let new_files = [
+            new_file(&other.to_string_lossy(), "photo", 7),
+            new_file(&same.to_string_lossy(), "photo", 7),
+        ];
+        // The modification time is not used when the hash is known
+        let moves = match_moved_photos(&[&photo], &new_files, &[Some(5), Some(9)]);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(matched(&moves), [(1, same.to_str().unwrap(), "content_hash")]);
+        assert_eq!(moves[0].content_hash.as_deref(), Some(hash.as_str()));
+    }
+}
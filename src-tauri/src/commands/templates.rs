@@ -0,0 +1,555 @@
+use crate::models::{Object, ObjectPlatform, Platform, Template, CreateTemplate, UpdateTemplate, RenderedTemplate, TextFile};
+use super::attributes;
+use super::paths::{self, LibraryPaths};
+use sqlx::{SqliteConnection, SqlitePool};
+use tauri::State;
+use std::collections::HashMap;
+use std::fs;
+
+// ========== TEMPLATE ENGINE ==========
+//
+// Minimal Handlebars-like syntax:
+//   {{name}}                          replaced by the value of the placeholder
+//   {{#if year}}...{{else}}...{{/if}} rendered when the placeholder is not empty
+
+// Render a template, returning the text and the placeholders that are not known
+pub(crate) fn render_template(
+    template: &str,
+    context: &HashMap<String, String>,
+) -> Result<(String, Vec<String>), String> {
+    let mut output = String::new();
+    let mut unknown = Vec::new();
+    render_into(template, context, &mut output, &mut unknown)?;
+    Ok((output, unknown))
+}
+
+fn render_into(
+    template: &str,
+    context: &HashMap<String, String>,
+    output: &mut String,
+    unknown: &mut Vec<String>,
+) -> Result<(), String> {
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| "Unclosed '{{' in template".to_string())?;
+        let tag = after[..end].trim();
+        rest = &after[end + 2..];
+
+        if let Some(condition) = tag.strip_prefix("#if ") {
+            let condition = condition.trim();
+            let (if_body, else_body, remaining) = split_if_block(rest)?;
+
+            if !context.contains_key(condition) && !unknown.iter().any(|u| u == condition) {
+                unknown.push(condition.to_string());
+            }
+            let truthy = context
+                .get(condition)
+                .is_some_and(|value| !value.trim().is_empty());
+
+            render_into(if truthy { if_body } else { else_body }, context, output, unknown)?;
+            rest = remaining;
+        } else if tag == "/if" || tag == "else" {
+            return Err(format!("Unexpected '{{{{{}}}}}' in template", tag));
+        } else {
+            match context.get(tag) {
+                Some(value) => output.push_str(value),
+                None => {
+                    if !unknown.iter().any(|u| u == tag) {
+                        unknown.push(tag.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    output.push_str(rest);
+    Ok(())
+}
+
+// Split the text following a {{#if}} tag into (if body, else body, text after {{/if}})
+fn split_if_block(text: &str) -> Result<(&str, &str, &str), String> {
+    let mut depth = 0;
+    let mut position = 0;
+    let mut else_tag: Option<(usize, usize)> = None;
+
+    loop {
+        let start = text[position..]
+            .find("{{")
+            .map(|i| position + i)
+            .ok_or_else(|| "Missing '{{/if}}' in template".to_string())?;
+        let end = text[start..]
+            .find("}}")
+            .map(|i| start + i)
+            .ok_or_else(|| "Unclosed '{{' in template".to_string())?;
+        let tag = text[start + 2..end].trim();
+        let tag_end = end + 2;
+
+        if tag.starts_with("#if ") {
+            depth += 1;
+        } else if tag == "/if" {
+            if depth == 0 {
+                return Ok(match else_tag {
+                    Some((else_start, else_end)) => (&text[..else_start], &text[else_end..start], &text[tag_end..]),
+                    None => (&text[..start], "", &text[tag_end..]),
+                });
+            }
+            depth -= 1;
+        } else if tag == "else" && depth == 0 {
+            if else_tag.is_some() {
+                return Err("Duplicate '{{else}}' in '{{#if}}' block".to_string());
+            }
+            else_tag = Some((start, tag_end));
+        }
+
+        position = tag_end;
+    }
+}
+
+// Find the description.txt stored next to the object's photos (main photo folder first)
+pub(crate) async fn find_object_description_file(
+    conn: &mut SqliteConnection,
+    object_id: i64,
+) -> Result<Option<TextFile>, String> {
+    let root = paths::library_root(&mut *conn).await?;
+
+    sqlx::query_as::<_, TextFile>(
+        "SELECT t.* FROM text_files t
+         INNER JOIN photos p ON p.folder_id = t.folder_id
+         INNER JOIN object_photos op ON op.photo_id = p.id
+         WHERE op.object_id = ? AND t.file_name = 'description.txt'
+         ORDER BY op.display_order
+         LIMIT 1"
+    )
+    .bind(object_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map(|text_file| text_file.resolve_paths(&root))
+    .map_err(|e| e.to_string())
+}
+
+// Build the placeholder values for an object, and for its listing on a platform if given
+pub(crate) async fn build_template_context(
+    conn: &mut SqliteConnection,
+    object_id: i64,
+    platform_id: Option<i64>,
+) -> Result<HashMap<String, String>, String> {
+    let object = sqlx::query_as::<_, Object>("SELECT * FROM objects WHERE id = ?")
+        .bind(object_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Object not found".to_string())?;
+
+    let category = match object.category_id {
+        Some(category_id) => sqlx::query_scalar::<_, String>("SELECT name FROM categories WHERE id = ?")
+            .bind(category_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?,
+        None => None,
+    };
+
+    let description_txt = match find_object_description_file(conn, object_id).await? {
+        Some(text_file) => fs::read_to_string(&text_file.file_path).unwrap_or_default(),
+        None => String::new(),
+    };
+
+    let mut context = HashMap::new();
+    context.insert("name".to_string(), object.name.clone());
+    context.insert("description".to_string(), object.description.clone().unwrap_or_default());
+    context.insert("year".to_string(), object.year.map(|y| y.to_string()).unwrap_or_default());
+    context.insert("weight".to_string(), object.weight.map(|w| w.to_string()).unwrap_or_default());
+    context.insert("category".to_string(), category.unwrap_or_default());
+    context.insert("brand".to_string(), object.brand.clone().unwrap_or_default());
+    context.insert("dimensions".to_string(), attributes::format_dimensions(&object));
+    context.insert("description_txt".to_string(), description_txt.trim().to_string());
+
+    // Listing placeholders are always defined so templates render the same with or without a platform
+    let mut platform_name = String::new();
+    let mut listing: Option<ObjectPlatform> = None;
+
+    if let Some(platform_id) = platform_id {
+        let platform = sqlx::query_as::<_, Platform>("SELECT * FROM platforms WHERE id = ?")
+            .bind(platform_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Platform not found".to_string())?;
+        platform_name = platform.name;
+
+        listing = sqlx::query_as::<_, ObjectPlatform>(
+            "SELECT * FROM object_platforms WHERE object_id = ? AND platform_id = ?"
+        )
+        .bind(object_id)
+        .bind(platform_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    context.insert("platform".to_string(), platform_name);
+    context.insert(
+        "title".to_string(),
+        listing.as_ref().and_then(|l| l.title.clone()).unwrap_or_else(|| object.name.clone()),
+    );
+    context.insert(
+        "price".to_string(),
+        listing.as_ref().and_then(|l| l.price).map(|p| format!("{:.2}", p)).unwrap_or_default(),
+    );
+    context.insert(
+        "currency".to_string(),
+        listing.as_ref().and_then(|l| l.currency.clone()).unwrap_or_default(),
+    );
+    context.insert(
+        "condition".to_string(),
+        listing
+            .as_ref()
+            .and_then(|l| l.condition.clone())
+            .filter(|c| !c.trim().is_empty())
+            .or_else(|| object.condition.clone())
+            .unwrap_or_default(),
+    );
+    context.insert(
+        "shipping_profile".to_string(),
+        listing.as_ref().and_then(|l| l.shipping_profile.clone()).unwrap_or_default(),
+    );
+
+    Ok(context)
+}
+
+// Default template for a platform, falling back to the generic default template
+pub(crate) async fn find_default_template(
+    conn: &mut SqliteConnection,
+    platform_id: Option<i64>,
+) -> Result<Option<Template>, String> {
+    sqlx::query_as::<_, Template>(
+        "SELECT * FROM templates
+         WHERE is_default = 1 AND (platform_id = ? OR platform_id IS NULL)
+         ORDER BY platform_id IS NULL, id
+         LIMIT 1"
+    )
+    .bind(platform_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Render a template (or the platform default) for an object
+pub(crate) async fn render_for_object(
+    conn: &mut SqliteConnection,
+    object_id: i64,
+    platform_id: Option<i64>,
+    template_id: Option<i64>,
+) -> Result<RenderedTemplate, String> {
+    let template = match template_id {
+        Some(template_id) => sqlx::query_as::<_, Template>("SELECT * FROM templates WHERE id = ?")
+            .bind(template_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Template not found".to_string())?,
+        None => find_default_template(conn, platform_id)
+            .await?
+            .ok_or_else(|| "No default template configured".to_string())?,
+    };
+
+    let context = build_template_context(conn, object_id, platform_id).await?;
+    render_with_context(Some(template.id), template.title_template.as_deref(), &template.body, &context)
+}
+
+fn render_with_context(
+    template_id: Option<i64>,
+    title_template: Option<&str>,
+    body: &str,
+    context: &HashMap<String, String>,
+) -> Result<RenderedTemplate, String> {
+    let (body, mut unknown_placeholders) = render_template(body, context)?;
+
+    let title = match title_template.filter(|t| !t.trim().is_empty()) {
+        Some(title_template) => {
+            let (title, unknown) = render_template(title_template, context)?;
+            for placeholder in unknown {
+                if !unknown_placeholders.contains(&placeholder) {
+                    unknown_placeholders.push(placeholder);
+                }
+            }
+            Some(title.trim().to_string())
+        }
+        None => None,
+    };
+
+    Ok(RenderedTemplate {
+        template_id,
+        title,
+        body: body.trim().to_string(),
+        unknown_placeholders,
+    })
+}
+
+// Only one default template per platform (and one generic default)
+async fn clear_other_defaults(
+    conn: &mut SqliteConnection,
+    template_id: i64,
+    platform_id: Option<i64>,
+) -> Result<(), String> {
+    sqlx::query("UPDATE templates SET is_default = 0 WHERE id != ? AND platform_id IS ?")
+        .bind(template_id)
+        .bind(platform_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// ========== TEMPLATES COMMANDS ==========
+
+#[tauri::command]
+pub async fn list_templates(pool: State<'_, SqlitePool>) -> Result<Vec<Template>, String> {
+    sqlx::query_as::<_, Template>("SELECT * FROM templates ORDER BY name ASC")
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_template(
+    pool: State<'_, SqlitePool>,
+    template: CreateTemplate,
+) -> Result<Template, String> {
+    // Reject templates that can't be parsed before storing them
+    render_template(&template.body, &HashMap::new())?;
+    if let Some(title_template) = &template.title_template {
+        render_template(title_template, &HashMap::new())?;
+    }
+
+    let is_default = template.is_default.unwrap_or(false);
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let result = sqlx::query(
+        "INSERT INTO templates (name, platform_id, title_template, body, is_default) VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(&template.name)
+    .bind(template.platform_id)
+    .bind(&template.title_template)
+    .bind(&template.body)
+    .bind(is_default)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let id = result.last_insert_rowid();
+
+    if is_default {
+        clear_other_defaults(&mut tx, id, template.platform_id).await?;
+    }
+
+    let created = sqlx::query_as::<_, Template>("SELECT * FROM templates WHERE id = ?")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(created)
+}
+
+#[tauri::command]
+pub async fn update_template(
+    pool: State<'_, SqlitePool>,
+    id: i64,
+    template: UpdateTemplate,
+) -> Result<Template, String> {
+    if let Some(body) = &template.body {
+        render_template(body, &HashMap::new())?;
+    }
+    if let Some(title_template) = &template.title_template {
+        render_template(title_template, &HashMap::new())?;
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let result = sqlx::query(
+        "UPDATE templates SET
+            name = COALESCE(?, name),
+            platform_id = CASE WHEN ? THEN NULL ELSE COALESCE(?, platform_id) END,
+            title_template = COALESCE(?, title_template),
+            body = COALESCE(?, body),
+            is_default = COALESCE(?, is_default),
+            updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?"
+    )
+    .bind(&template.name)
+    .bind(template.clear_platform.unwrap_or(false))
+    .bind(template.platform_id)
+    .bind(&template.title_template)
+    .bind(&template.body)
+    .bind(template.is_default)
+    .bind(id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if result.rows_affected() == 0 {
+        return Err("Template not found".to_string());
+    }
+
+    let updated = sqlx::query_as::<_, Template>("SELECT * FROM templates WHERE id = ?")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if updated.is_default {
+        clear_other_defaults(&mut tx, id, updated.platform_id).await?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(updated)
+}
+
+#[tauri::command]
+pub async fn delete_template(pool: State<'_, SqlitePool>, id: i64) -> Result<(), String> {
+    let result = sqlx::query("DELETE FROM templates WHERE id = ?")
+        .bind(id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if result.rows_affected() == 0 {
+        Err("Template not found".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct PreviewTemplateRequest {
+    pub object_id: i64,
+    pub platform_id: Option<i64>,
+    pub template_id: Option<i64>,
+    // Unsaved template text from the editor; takes precedence over template_id
+    pub title_template: Option<String>,
+    pub body: Option<String>,
+}
+
+#[tauri::command]
+pub async fn preview_template(
+    pool: State<'_, SqlitePool>,
+    request: PreviewTemplateRequest,
+) -> Result<RenderedTemplate, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+
+    match &request.body {
+        Some(body) => {
+            let context = build_template_context(&mut conn, request.object_id, request.platform_id).await?;
+            render_with_context(None, request.title_template.as_deref(), body, &context)
+        }
+        None => render_for_object(&mut conn, request.object_id, request.platform_id, request.template_id).await,
+    }
+}
+
+// Render a template for a listing and store the result as its title/description
+#[tauri::command]
+pub async fn apply_template_to_listing(
+    pool: State<'_, SqlitePool>,
+    object_platform_id: i64,
+    template_id: Option<i64>,
+) -> Result<ObjectPlatform, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let listing = sqlx::query_as::<_, ObjectPlatform>("SELECT * FROM object_platforms WHERE id = ?")
+        .bind(object_platform_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Platform association not found".to_string())?;
+
+    let rendered = render_for_object(&mut tx, listing.object_id, Some(listing.platform_id), template_id).await?;
+
+    if let Some(title) = &rendered.title {
+        let platform = sqlx::query_as::<_, Platform>("SELECT * FROM platforms WHERE id = ?")
+            .bind(listing.platform_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Some(error) = super::listings::check_title_length(title, &platform) {
+            return Err(error);
+        }
+    }
+
+    sqlx::query(
+        "UPDATE object_platforms SET title = COALESCE(?, title), description = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?"
+    )
+    .bind(&rendered.title)
+    .bind(&rendered.body)
+    .bind(object_platform_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let updated = sqlx::query_as::<_, ObjectPlatform>("SELECT * FROM object_platforms WHERE id = ?")
+        .bind(object_platform_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(values: &[(&str, &str)]) -> HashMap<String, String> {
+        values.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn placeholders() {
+        let (text, unknown) = render_template("{{ name }} ({{year}}) {{colour}}", &context(&[("name", "Lamp"), ("year", "1970")])).unwrap();
+        assert_eq!(text, "Lamp (1970) ");
+        assert_eq!(unknown, ["colour"]);
+    }
+
+    #[test]
+    fn if_blocks() {
+        let template = "{{#if year}}Year: {{year}}{{else}}Unknown year{{/if}}.";
+        assert_eq!(render_template(template, &context(&[("year", "1970")])).unwrap().0, "Year: 1970.");
+        assert_eq!(render_template(template, &context(&[("year", " ")])).unwrap().0, "Unknown year.");
+        assert_eq!(render_template("{{#if brand}}{{brand}}{{/if}}", &context(&[])).unwrap(), (String::new(), vec!["brand".to_string()]));
+    }
+
+    #[test]
+    fn nested_if_blocks() {
+        let template = "{{#if a}}A{{#if b}}B{{else}}-{{/if}}{{else}}none{{/if}}";
+        assert_eq!(render_template(template, &context(&[("a", "1"), ("b", "1")])).unwrap().0, "AB");
+        assert_eq!(render_template(template, &context(&[("a", "1"), ("b", "")])).unwrap().0, "A-");
+        assert_eq!(render_template(template, &context(&[("a", ""), ("b", "1")])).unwrap().0, "none");
+    }
+
+    #[test]
+    fn split_if_block_parts() {
+        assert_eq!(split_if_block("yes{{else}}no{{/if}} after").unwrap(), ("yes", "no", " after"));
+        assert_eq!(split_if_block("{{#if x}}a{{else}}b{{/if}}{{/if}}").unwrap(), ("{{#if x}}a{{else}}b{{/if}}", "", ""));
+    }
+
+    #[test]
+    fn template_errors() {
+        assert!(split_if_block("a{{else}}b{{else}}c{{/if}}").unwrap_err().contains("Duplicate"));
+        assert!(render_template("{{#if a}}text", &context(&[])).unwrap_err().contains("Missing"));
+        assert!(render_template("{{name", &context(&[])).unwrap_err().contains("Unclosed"));
+        assert!(render_template("text{{/if}}", &context(&[])).unwrap_err().contains("Unexpected"));
+        assert!(render_template("{{else}}", &context(&[])).unwrap_err().contains("Unexpected"));
+    }
+}
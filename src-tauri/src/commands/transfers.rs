@@ -0,0 +1,748 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{State, Window};
+
+use super::fileops::{self, Progress};
+use super::journal::{self, JournalStep};
+use super::{paths, replace_path_prefix, trash};
+
+// ========== MOVE / COPY PHOTOS AND FOLDERS ==========
+// Moves and copies are planned first (what goes where), then run in a single transaction:
+// if a file operation or a database update fails, the files already moved or copied are put
+// back and nothing is changed in the database. Progress is sent to the window as
+// "transfer-progress" events.
+
+#[derive(Deserialize)]
+pub struct MoveItemsRequest {
+    pub photo_ids: Vec<i64>,
+    pub text_file_ids: Vec<i64>,
+    pub folder_paths: Vec<String>,
+    pub destination_path: String,
+    #[serde(default)]
+    pub delete_source_folder: bool,
+}
+
+#[derive(Serialize)]
+pub struct MoveItemsResult {
+    pub moved: i32,
+    // Items that were skipped (not found, already in the destination...)
+    pub errors: Vec<String>,
+    pub report: TransferReport,
+}
+
+#[derive(Serialize)]
+pub struct CopyItemsResult {
+    pub copied: i32,
+    pub errors: Vec<String>,
+    pub report: TransferReport,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferItem {
+    // 'photo', 'text_file' or 'folder'
+    pub kind: String,
+    pub id: Option<i64>,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FileOperation {
+    CreateFolder { path: String },
+    Move { from: String, to: String },
+    Copy { from: String, to: String },
+    RemoveFolder { path: String },
+}
+
+// What a move or copy changed, on disk and in the database
+#[derive(Debug, Default, Serialize)]
+pub struct TransferReport {
+    pub items: Vec<TransferItem>,
+    pub file_operations: Vec<FileOperation>,
+    pub photos_updated: Vec<i64>,
+    pub text_files_updated: Vec<i64>,
+    pub folders_updated: Vec<i64>,
+    pub photos_created: Vec<i64>,
+    pub text_files_created: Vec<i64>,
+    pub folders_created: Vec<i64>,
+    pub folders_removed: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TransferMode {
+    Move,
+    Copy,
+}
+
+struct TransferPlan {
+    destination: PathBuf,
+    create_destination: bool,
+    items: Vec<TransferItem>,
+    // Folders (id, path) the moved files come from, removed once empty
+    source_folders: Vec<(i64, String)>,
+    skipped: Vec<String>,
+}
+
+// A path is taken if it exists on disk, is already planned, or is still used by a row
+pub(crate) async fn path_taken(conn: &mut SqliteConnection, path: &Path, reserved: &HashSet<PathBuf>) -> Result<bool, String> {
+    if path.exists() || reserved.contains(path) {
+        return Ok(true);
+    }
+
+    let path_str = paths::to_stored(&paths::library_root(&mut *conn).await?, &path.to_string_lossy());
+    let rows = sqlx::query_scalar::<_, i64>(
+        "SELECT (SELECT COUNT(*) FROM folders WHERE path = ?)
+              + (SELECT COUNT(*) FROM photos WHERE file_path = ?)
+              + (SELECT COUNT(*) FROM text_files WHERE file_path = ?)"
+    )
+    .bind(&path_str)
+    .bind(&path_str)
+    .bind(&path_str)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows > 0)
+}
+
+// Free path for `source` in `destination`, numbering the name on duplicates ("name (1).jpg")
+async fn destination_for(
+    conn: &mut SqliteConnection,
+    source: &Path,
+    destination: &Path,
+    reserved: &mut HashSet<PathBuf>,
+) -> Result<PathBuf, String> {
+    let file_name = source
+        .file_name()
+        .ok_or_else(|| format!("Invalid path: {}", source.display()))?
+        .to_string_lossy()
+        .to_string();
+    let mut target = destination.join(&file_name);
+
+    let mut copy_number = 1;
+    while path_taken(conn, &target, reserved).await? {
+        let stem = source.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+        let extension = source.extension().map(|e| e.to_string_lossy()).unwrap_or_default();
+        let new_name = if source.is_dir() || extension.is_empty() {
+            format!("{} ({})", file_name, copy_number)
+        } else {
+            format!("{} ({}).{}", stem, copy_number, extension)
+        };
+        target = destination.join(new_name);
+        copy_number += 1;
+    }
+
+    reserved.insert(target.clone());
+    Ok(target)
+}
+
+// Decide where every requested item goes, without touching anything
+async fn plan_transfer(
+    conn: &mut SqliteConnection,
+    request: &MoveItemsRequest,
+    mode: TransferMode,
+) -> Result<TransferPlan, String> {
+    let destination = PathBuf::from(&request.destination_path);
+    if destination.exists() && !destination.is_dir() {
+        return Err(format!("Destination is not a folder: {}", request.destination_path));
+    }
+
+    let root_path = paths::library_root(&mut *conn).await?;
+    let mut plan = TransferPlan {
+        create_destination: !destination.exists(),
+        destination: destination.clone(),
+        items: Vec::new(),
+        source_folders: Vec::new(),
+        skipped: Vec::new(),
+    };
+    let mut reserved: HashSet<PathBuf> = HashSet::new();
+    let mut planned_folders: Vec<PathBuf> = Vec::new();
+    let mut source_folder_ids: Vec<i64> = Vec::new();
+
+    // Folders first: the files inside a planned folder go with it
+    for folder_path in &request.folder_paths {
+        let source = Path::new(folder_path);
+        if !source.is_dir() {
+            plan.skipped.push(format!("Source folder not found: {}", folder_path));
+            continue;
+        }
+        if destination.starts_with(source) {
+            plan.skipped.push(format!("Can't put {} inside itself", folder_path));
+            continue;
+        }
+        if planned_folders.iter().any(|planned| source.starts_with(planned)) {
+            continue;
+        }
+        if mode == TransferMode::Move && source.parent() == Some(destination.as_path()) {
+            plan.skipped.push(format!("{} is already in the destination folder", folder_path));
+            continue;
+        }
+
+        let id = sqlx::query_scalar::<_, i64>("SELECT id FROM folders WHERE path = ?")
+            .bind(paths::to_stored(&root_path, folder_path))
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        let target = destination_for(conn, source, &destination, &mut reserved).await?;
+
+        planned_folders.push(source.to_path_buf());
+        plan.items.push(TransferItem {
+            kind: "folder".to_string(),
+            id,
+            from: folder_path.clone(),
+            to: target.to_string_lossy().to_string(),
+        });
+    }
+
+    let files = [
+        ("photos", "photo", "Photo", &request.photo_ids),
+        ("text_files", "text_file", "Text file", &request.text_file_ids),
+    ];
+    for (table, kind, label, ids) in files {
+        for id in ids {
+            let row = sqlx::query_as::<_, (String, Option<i64>)>(&format!(
+                "SELECT file_path, folder_id FROM {} WHERE id = ?",
+                table
+            ))
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+
+            let Some((file_path, folder_id)) = row else {
+                plan.skipped.push(format!("{} {} not found", label, id));
+                continue;
+            };
+            let file_path = paths::to_absolute(&root_path, &file_path);
+            let source = Path::new(&file_path);
+            if planned_folders.iter().any(|planned| source.starts_with(planned)) {
+                continue;
+            }
+            if !source.exists() {
+                plan.skipped.push(format!("Source file not found: {}", file_path));
+                continue;
+            }
+            if mode == TransferMode::Move && source.parent() == Some(destination.as_path()) {
+                plan.skipped.push(format!("{} is already in the destination folder", file_path));
+                continue;
+            }
+
+            let target = destination_for(conn, source, &destination, &mut reserved).await?;
+            plan.items.push(TransferItem {
+                kind: kind.to_string(),
+                id: Some(*id),
+                from: file_path.clone(),
+                to: target.to_string_lossy().to_string(),
+            });
+
+            if let Some(folder_id) = folder_id {
+                if !source_folder_ids.contains(&folder_id) {
+                    source_folder_ids.push(folder_id);
+                }
+            }
+        }
+    }
+
+    if mode == TransferMode::Move && request.delete_source_folder {
+        for folder_id in source_folder_ids {
+            let path = sqlx::query_scalar::<_, String>("SELECT path FROM folders WHERE id = ?")
+                .bind(folder_id)
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+            if let Some(path) = path {
+                plan.source_folders.push((folder_id, paths::to_absolute(&root_path, &path)));
+            }
+        }
+    }
+
+    Ok(plan)
+}
+
+// Id of the folder row for `path`, creating the missing rows up to the root folder.
+// Returns None for the root folder itself and for paths outside of it.
+pub(crate) async fn ensure_folder_row(
+    conn: &mut SqliteConnection,
+    path: &Path,
+    root_path: &str,
+    created: &mut Vec<i64>,
+) -> Result<Option<i64>, String> {
+    let root = Path::new(root_path);
+    if root_path.is_empty() || path == root || !path.starts_with(root) {
+        return Ok(None);
+    }
+
+    // Folders between the root and `path`, top-most first
+    let mut chain: Vec<&Path> = path.ancestors().take_while(|folder| *folder != root).collect();
+    chain.reverse();
+
+    let mut parent_id: Option<i64> = None;
+    for folder in chain {
+        let folder_str = paths::to_stored(root_path, &folder.to_string_lossy());
+        let existing = sqlx::query_scalar::<_, i64>("SELECT id FROM folders WHERE path = ?")
+            .bind(&folder_str)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let id = match existing {
+            Some(id) => id,
+            None => {
+                let name = folder.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                let id = sqlx::query("INSERT INTO folders (path, name, parent_id) VALUES (?, ?, ?)")
+                    .bind(&folder_str)
+                    .bind(name)
+                    .bind(parent_id)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .last_insert_rowid();
+                created.push(id);
+                id
+            }
+        };
+        parent_id = Some(id);
+    }
+
+    Ok(parent_id)
+}
+
+fn file_name_of(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+// Update the rows of a moved item
+async fn move_item_rows(
+    conn: &mut SqliteConnection,
+    item: &TransferItem,
+    root_path: &str,
+    report: &mut TransferReport,
+) -> Result<(), String> {
+    let to = Path::new(&item.to);
+    let parent = to.parent().unwrap_or(to);
+    let folder_id = ensure_folder_row(conn, parent, root_path, &mut report.folders_created).await?;
+
+    match item.kind.as_str() {
+        "folder" => {
+            report.folders_updated.extend(trash::ids_under_path(conn, "folders", "path", &item.from).await?);
+            report.photos_updated.extend(trash::ids_under_path(conn, "photos", "file_path", &item.from).await?);
+            report.text_files_updated.extend(trash::ids_under_path(conn, "text_files", "file_path", &item.from).await?);
+
+            replace_path_prefix(conn, &item.from, &item.to).await?;
+
+            match item.id {
+                Some(id) => {
+                    sqlx::query("UPDATE folders SET name = ?, parent_id = ? WHERE id = ?")
+                        .bind(file_name_of(to))
+                        .bind(folder_id)
+                        .bind(id)
+                        .execute(&mut *conn)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                }
+                None => {
+                    ensure_folder_row(conn, to, root_path, &mut report.folders_created).await?;
+                }
+            }
+        }
+        "photo" => {
+            let stored_to = paths::to_stored(root_path, &item.to);
+            sqlx::query("UPDATE photos SET file_path = ?, original_path = ?, file_name = ?, folder_id = ? WHERE id = ?")
+                .bind(&stored_to)
+                .bind(&stored_to)
+                .bind(file_name_of(to))
+                .bind(folder_id)
+                .bind(item.id)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+            report.photos_updated.extend(item.id);
+        }
+        _ => {
+            sqlx::query(
+                "UPDATE text_files SET file_path = ?, file_name = ?, folder_id = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?"
+            )
+            .bind(paths::to_stored(root_path, &item.to))
+            .bind(file_name_of(to))
+            .bind(folder_id)
+            .bind(item.id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+            report.text_files_updated.extend(item.id);
+        }
+    }
+
+    Ok(())
+}
+
+async fn copy_photo_row(
+    conn: &mut SqliteConnection,
+    source_id: i64,
+    path: &Path,
+    root_path: &str,
+    report: &mut TransferReport,
+) -> Result<(), String> {
+    let folder_id = ensure_folder_row(conn, path.parent().unwrap_or(path), root_path, &mut report.folders_created).await?;
+    let path_str = paths::to_stored(root_path, &path.to_string_lossy());
+    let id = sqlx::query(
+        "INSERT INTO photos (file_path, original_path, file_name, file_size, width, height, folder_id, content_hash)
+         SELECT ?, ?, ?, file_size, width, height, ?, content_hash FROM photos WHERE id = ?"
+    )
+    .bind(&path_str)
+    .bind(&path_str)
+    .bind(file_name_of(path))
+    .bind(folder_id)
+    .bind(source_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?
+    .last_insert_rowid();
+    report.photos_created.push(id);
+    Ok(())
+}
+
+async fn copy_text_file_row(
+    conn: &mut SqliteConnection,
+    source_id: i64,
+    path: &Path,
+    root_path: &str,
+    report: &mut TransferReport,
+) -> Result<(), String> {
+    let folder_id = ensure_folder_row(conn, path.parent().unwrap_or(path), root_path, &mut report.folders_created).await?;
+    let id = sqlx::query(
+        "INSERT INTO text_files (file_path, file_name, file_size, folder_id)
+         SELECT ?, ?, file_size, ? FROM text_files WHERE id = ?"
+    )
+    .bind(paths::to_stored(root_path, &path.to_string_lossy()))
+    .bind(file_name_of(path))
+    .bind(folder_id)
+    .bind(source_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?
+    .last_insert_rowid();
+    report.text_files_created.push(id);
+    Ok(())
+}
+
+// Path in the copy of a folder of a path stored under it (None when the path isn't under it).
+// Paths are compared by components, whatever their separators
+fn copied_path(root_path: &str, from: &str, to: &str, stored: &str) -> Option<PathBuf> {
+    let path = paths::to_absolute(root_path, stored);
+    let relative = Path::new(&path).strip_prefix(from).ok()?;
+    if relative.as_os_str().is_empty() {
+        return Some(PathBuf::from(to));
+    }
+    Some(Path::new(to).join(relative))
+}
+
+// Create the rows of a copied item (for a folder: its sub-folders, photos and text files)
+async fn copy_item_rows(
+    conn: &mut SqliteConnection,
+    item: &TransferItem,
+    root_path: &str,
+    report: &mut TransferReport,
+) -> Result<(), String> {
+    let to = Path::new(&item.to);
+
+    match item.kind.as_str() {
+        "folder" => {
+            ensure_folder_row(conn, to, root_path, &mut report.folders_created).await?;
+
+            let folders = trash::ids_under_path(conn, "folders", "path", &item.from).await?;
+            for folder_id in folders {
+                let path = sqlx::query_scalar::<_, String>("SELECT path FROM folders WHERE id = ?")
+                    .bind(folder_id)
+                    .fetch_one(&mut *conn)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                if let Some(copied) = copied_path(root_path, &item.from, &item.to, &path) {
+                    ensure_folder_row(conn, &copied, root_path, &mut report.folders_created).await?;
+                }
+            }
+
+            for photo_id in trash::ids_under_path(conn, "photos", "file_path", &item.from).await? {
+                let path = sqlx::query_scalar::<_, String>("SELECT file_path FROM photos WHERE id = ?")
+                    .bind(photo_id)
+                    .fetch_one(&mut *conn)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                if let Some(copied) = copied_path(root_path, &item.from, &item.to, &path) {
+                    copy_photo_row(conn, photo_id, &copied, root_path, report).await?;
+                }
+            }
+
+            for text_file_id in trash::ids_under_path(conn, "text_files", "file_path", &item.from).await? {
+                let path = sqlx::query_scalar::<_, String>("SELECT file_path FROM text_files WHERE id = ?")
+                    .bind(text_file_id)
+                    .fetch_one(&mut *conn)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                if let Some(copied) = copied_path(root_path, &item.from, &item.to, &path) {
+                    copy_text_file_row(conn, text_file_id, &copied, root_path, report).await?;
+                }
+            }
+        }
+        "photo" => copy_photo_row(conn, item.id.unwrap_or_default(), to, root_path, report).await?,
+        _ => copy_text_file_row(conn, item.id.unwrap_or_default(), to, root_path, report).await?,
+    }
+
+    Ok(())
+}
+
+// Undo a file operation after a failure (best effort)
+fn revert_file_operation(operation: &FileOperation) {
+    match operation {
+        FileOperation::CreateFolder { path } => {
+            let _ = fs::remove_dir(path);
+        }
+        FileOperation::Move { from, to } => {
+            let _ = trash::move_path(Path::new(to), Path::new(from));
+        }
+        FileOperation::Copy { to, .. } => {
+            let _ = trash::remove_path(Path::new(to));
+        }
+        FileOperation::RemoveFolder { path } => {
+            let _ = fs::create_dir_all(path);
+        }
+    }
+}
+
+// Rows a move can change, for the journal
+async fn moved_row_ids(
+    conn: &mut SqliteConnection,
+    plan: &TransferPlan,
+) -> Result<[(&'static str, Vec<i64>); 3], String> {
+    let mut folder_ids: Vec<i64> = plan.source_folders.iter().map(|(id, _)| *id).collect();
+    let mut photo_ids = Vec::new();
+    let mut text_file_ids = Vec::new();
+
+    for item in &plan.items {
+        match item.kind.as_str() {
+            "folder" => {
+                folder_ids.extend(trash::ids_under_path(conn, "folders", "path", &item.from).await?);
+                photo_ids.extend(trash::ids_under_path(conn, "photos", "file_path", &item.from).await?);
+                text_file_ids.extend(trash::ids_under_path(conn, "text_files", "file_path", &item.from).await?);
+            }
+            "photo" => photo_ids.extend(item.id),
+            _ => text_file_ids.extend(item.id),
+        }
+    }
+
+    Ok([("folders", folder_ids), ("photos", photo_ids), ("text_files", text_file_ids)])
+}
+
+async fn run_transfer(
+    conn: &mut SqliteConnection,
+    plan: &TransferPlan,
+    mode: TransferMode,
+    progress: &mut Progress<'_>,
+    report: &mut TransferReport,
+) -> Result<(), String> {
+    let root_path = paths::library_root(&mut *conn).await?;
+
+    let mut touched = if mode == TransferMode::Move {
+        moved_row_ids(conn, plan).await?
+    } else {
+        Default::default()
+    };
+    let mut before = Vec::new();
+    for (table, ids) in &touched {
+        before.push(trash::snapshot_rows(conn, table, "id", ids).await?);
+    }
+
+    // Files
+    if plan.create_destination {
+        fs::create_dir_all(&plan.destination).map_err(|e| format!("Failed to create destination directory: {}", e))?;
+        report.file_operations.push(FileOperation::CreateFolder {
+            path: plan.destination.to_string_lossy().to_string(),
+        });
+    }
+
+    for item in &plan.items {
+        let (from, to) = (Path::new(&item.from), Path::new(&item.to));
+        match mode {
+            TransferMode::Move => {
+                fileops::move_path(from, to, progress)?;
+                report.file_operations.push(FileOperation::Move { from: item.from.clone(), to: item.to.clone() });
+            }
+            TransferMode::Copy => {
+                fileops::copy_path(from, to, progress)?;
+                report.file_operations.push(FileOperation::Copy { from: item.from.clone(), to: item.to.clone() });
+            }
+        }
+    }
+
+    // Source folders left empty by the move (folders still holding files are kept)
+    let mut removed_folders = Vec::new();
+    for (id, path) in &plan.source_folders {
+        if fs::remove_dir(path).is_ok() {
+            report.file_operations.push(FileOperation::RemoveFolder { path: path.clone() });
+            removed_folders.push(*id);
+        }
+    }
+
+    // Database
+    for item in &plan.items {
+        match mode {
+            TransferMode::Move => move_item_rows(conn, item, &root_path, report).await?,
+            TransferMode::Copy => copy_item_rows(conn, item, &root_path, report).await?,
+        }
+    }
+
+    for id in removed_folders {
+        sqlx::query("DELETE FROM folders WHERE id = ?")
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        report.folders_removed.push(id);
+    }
+
+    if mode == TransferMode::Move {
+        touched[0].1.extend(report.folders_created.iter().copied());
+
+        let mut steps: Vec<JournalStep> = report
+            .file_operations
+            .iter()
+            .filter_map(|operation| match operation {
+                FileOperation::Move { from, to } => Some(JournalStep::MovePath { from: from.clone(), to: to.clone() }),
+                FileOperation::RemoveFolder { path } => Some(JournalStep::RemoveDir { path: path.clone() }),
+                _ => None,
+            })
+            .collect();
+        for ((table, ids), before) in touched.iter().zip(before) {
+            let after = trash::snapshot_rows(conn, table, "id", ids).await?;
+            steps.push(JournalStep::rows("id", ids, before, after));
+        }
+
+        journal::record_operation(
+            conn,
+            "move_photos_and_folders",
+            &format!("Move {} items to {}", plan.items.len(), plan.destination.display()),
+            steps,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+// Plan and run a move or copy. Either everything is done or nothing is.
+async fn transfer(
+    pool: &SqlitePool,
+    request: &MoveItemsRequest,
+    mode: TransferMode,
+    window: &Window,
+) -> Result<(TransferReport, Vec<String>), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let plan = plan_transfer(&mut tx, request, mode).await?;
+    let mut report = TransferReport {
+        items: plan.items.clone(),
+        ..Default::default()
+    };
+
+    let (files_total, bytes_total) = plan
+        .items
+        .iter()
+        .map(|item| fileops::tree_stats(Path::new(&item.from)))
+        .fold((0, 0), |(files, bytes), (f, b)| (files + f, bytes + b));
+    let mut progress = Progress::new(files_total, bytes_total, |state| {
+        let _ = window.emit("transfer-progress", state.clone());
+    });
+
+    let outcome = run_transfer(&mut tx, &plan, mode, &mut progress, &mut report).await;
+    let outcome = match outcome {
+        Ok(()) => tx.commit().await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = outcome {
+        revert_file_operations(&report);
+        return Err(format!("Nothing was changed: {}", e));
+    }
+
+    Ok((report, plan.skipped))
+}
+
+pub(crate) fn revert_file_operations(report: &TransferReport) {
+    for operation in report.file_operations.iter().rev() {
+        revert_file_operation(operation);
+    }
+}
+
+// Move items inside the caller's transaction, without progress events. The files are put back
+// if the move fails; if the caller fails afterwards, it puts them back with
+// `revert_file_operations`.
+pub(crate) async fn move_items(
+    conn: &mut SqliteConnection,
+    request: &MoveItemsRequest,
+) -> Result<(TransferReport, Vec<String>), String> {
+    let plan = plan_transfer(conn, request, TransferMode::Move).await?;
+    let mut report = TransferReport {
+        items: plan.items.clone(),
+        ..Default::default()
+    };
+
+    if let Err(e) = run_transfer(conn, &plan, TransferMode::Move, &mut Progress::silent(), &mut report).await {
+        revert_file_operations(&report);
+        return Err(e);
+    }
+
+    Ok((report, plan.skipped))
+}
+
+#[tauri::command]
+pub async fn move_photos_and_folders(
+    window: Window,
+    pool: State<'_, SqlitePool>,
+    request: MoveItemsRequest,
+) -> Result<MoveItemsResult, String> {
+    let (report, errors) = transfer(pool.inner(), &request, TransferMode::Move, &window).await?;
+
+    Ok(MoveItemsResult {
+        moved: (report.photos_updated.len() + report.text_files_updated.len()) as i32,
+        errors,
+        report,
+    })
+}
+
+#[tauri::command]
+pub async fn copy_photos_and_folders(
+    window: Window,
+    pool: State<'_, SqlitePool>,
+    request: MoveItemsRequest, // Reuse same request structure
+) -> Result<CopyItemsResult, String> {
+    let (report, errors) = transfer(pool.inner(), &request, TransferMode::Copy, &window).await?;
+
+    Ok(CopyItemsResult {
+        copied: (report.photos_created.len() + report.text_files_created.len()) as i32,
+        errors,
+        report,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copied_paths() {
+        let copy = |stored: &str| copied_path("/lib", "/lib/a", "/lib/b", stored);
+        assert_eq!(copy("a"), Some(PathBuf::from("/lib/b")));
+        assert_eq!(copy("a/x/p.jpg"), Some(PathBuf::from("/lib/b/x/p.jpg")));
+        assert_eq!(copy("/lib/a/été/p.jpg"), Some(PathBuf::from("/lib/b/été/p.jpg")));
+        assert_eq!(copy("ab/p.jpg"), None);
+        assert_eq!(copy("/elsewhere/p.jpg"), None);
+
+        // Trailing and repeated separators
+        assert_eq!(copied_path("/lib", "/lib/a/", "/lib/b", "a//p.jpg"), Some(PathBuf::from("/lib/b/p.jpg")));
+        assert_eq!(copied_path("", "/été/a", "/x", "/été/a/p.jpg"), Some(PathBuf::from("/x/p.jpg")));
+    }
+}
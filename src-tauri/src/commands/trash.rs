@@ -0,0 +1,644 @@
+use crate::models::TrashEntry;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::{Column, Row, SqliteConnection, SqlitePool, TypeInfo, ValueRef};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use super::{fileops, paths};
+
+// ========== TRASH ==========
+
+// Folder where deleted files are kept instead of being unlinked: ~/.seller-library/trash
+pub(crate) fn trash_dir() -> Result<PathBuf, String> {
+    let home_dir = dirs::home_dir().ok_or_else(|| "Unable to determine home directory".to_string())?;
+    Ok(home_dir.join(".seller-library").join("trash"))
+}
+
+// Move a file or folder (copied, verified then deleted when the trash is on another disk)
+pub(crate) fn move_path(source: &Path, destination: &Path) -> Result<(), String> {
+    fileops::move_path(source, destination, &mut fileops::Progress::silent())
+}
+
+pub(crate) fn remove_path(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+fn path_size(path: &Path) -> u64 {
+    if path.is_dir() {
+        fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|entry| path_size(&entry.path())).sum())
+            .unwrap_or(0)
+    } else {
+        fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+    }
+}
+
+// Move a file or folder to the trash. Each deletion gets its own sub-folder so that
+// files with the same name never overwrite each other. Returns the path in the trash.
+pub(crate) fn move_to_trash(path: &Path) -> Result<PathBuf, String> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("Invalid path: {}", path.display()))?;
+
+    let now = Local::now();
+    let entry_dir = trash_dir()?.join(format!(
+        "{}-{:09}",
+        now.format("%Y%m%d-%H%M%S"),
+        now.timestamp_subsec_nanos()
+    ));
+    let destination = entry_dir.join(file_name);
+
+    move_path(path, &destination)?;
+
+    Ok(destination)
+}
+
+// ========== TRASHED DATABASE ROWS ==========
+
+// Rows of a table removed along with a trash entry, put back as they were on restore.
+// The "object_categories" pseudo-table holds the objects that lost their category.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct TrashedRecords {
+    pub table: String,
+    pub rows: Vec<Map<String, Value>>,
+}
+
+fn row_to_json(row: &sqlx::sqlite::SqliteRow) -> Map<String, Value> {
+    let mut map = Map::new();
+
+    for (index, column) in row.columns().iter().enumerate() {
+        let value = match row.try_get_raw(index) {
+            Ok(raw) if !raw.is_null() => match raw.type_info().name() {
+                "INTEGER" => row.try_get_unchecked::<i64, _>(index).map(Value::from).unwrap_or(Value::Null),
+                "REAL" => row.try_get_unchecked::<f64, _>(index).map(Value::from).unwrap_or(Value::Null),
+                _ => row.try_get_unchecked::<String, _>(index).map(Value::from).unwrap_or(Value::Null),
+            },
+            _ => Value::Null,
+        };
+        map.insert(column.name().to_string(), value);
+    }
+
+    map
+}
+
+// Rows of `table` whose `column` is one of `ids`
+pub(crate) async fn snapshot_rows(
+    conn: &mut SqliteConnection,
+    table: &str,
+    column: &str,
+    ids: &[i64],
+) -> Result<TrashedRecords, String> {
+    let mut records = TrashedRecords {
+        table: table.to_string(),
+        rows: Vec::new(),
+    };
+    if ids.is_empty() {
+        return Ok(records);
+    }
+
+    let mut query = sqlx::QueryBuilder::<sqlx::Sqlite>::new(format!("SELECT * FROM {} WHERE {} IN (", table, column));
+    let mut separated = query.separated(", ");
+    for id in ids {
+        separated.push_bind(*id);
+    }
+    separated.push_unseparated(") ORDER BY id ASC");
+
+    let rows = query
+        .build()
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    records.rows = rows.iter().map(row_to_json).collect();
+    Ok(records)
+}
+
+pub(crate) async fn ids_under_path(
+    conn: &mut SqliteConnection,
+    table: &str,
+    column: &str,
+    path: &str,
+) -> Result<Vec<i64>, String> {
+    let path = &paths::to_stored(&paths::library_root(&mut *conn).await?, path);
+    let children = format!("{}{}", path, std::path::MAIN_SEPARATOR);
+    sqlx::query_scalar::<_, i64>(&format!(
+        "SELECT id FROM {table} WHERE {column} = ? OR substr({column}, 1, length(?)) = ?
+         ORDER BY length({column}) ASC, id ASC"
+    ))
+    .bind(path)
+    .bind(&children)
+    .bind(&children)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Folders, photos (with their object associations) and text files stored at or under a path,
+// parents first so that they can be restored in order
+pub(crate) async fn snapshot_path(conn: &mut SqliteConnection, path: &str) -> Result<Vec<TrashedRecords>, String> {
+    let folder_ids = ids_under_path(conn, "folders", "path", path).await?;
+    let photo_ids = ids_under_path(conn, "photos", "file_path", path).await?;
+    let text_file_ids = ids_under_path(conn, "text_files", "file_path", path).await?;
+
+    let mut folders = snapshot_rows(conn, "folders", "id", &folder_ids).await?;
+    // Parents before their children
+    folders.rows.sort_by_key(|row| row.get("path").and_then(|p| p.as_str()).map_or(0, |p| p.len()));
+
+    Ok(vec![
+        folders,
+        snapshot_rows(conn, "photos", "id", &photo_ids).await?,
+        snapshot_rows(conn, "object_photos", "photo_id", &photo_ids).await?,
+        snapshot_rows(conn, "text_files", "id", &text_file_ids).await?,
+    ])
+}
+
+// Remove the folders, photos and text files stored at or under a path
+pub(crate) async fn delete_path_rows(conn: &mut SqliteConnection, path: &str) -> Result<(i64, i64), String> {
+    let path = &paths::to_stored(&paths::library_root(&mut *conn).await?, path);
+    let children = format!("{}{}", path, std::path::MAIN_SEPARATOR);
+
+    let photos = sqlx::query("DELETE FROM photos WHERE file_path = ? OR substr(file_path, 1, length(?)) = ?")
+        .bind(path)
+        .bind(&children)
+        .bind(&children)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    let text_files = sqlx::query("DELETE FROM text_files WHERE file_path = ? OR substr(file_path, 1, length(?)) = ?")
+        .bind(path)
+        .bind(&children)
+        .bind(&children)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM folders WHERE path = ? OR substr(path, 1, length(?)) = ?")
+        .bind(path)
+        .bind(&children)
+        .bind(&children)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok((photos.rows_affected() as i64, text_files.rows_affected() as i64))
+}
+
+async fn row_exists(conn: &mut SqliteConnection, table: &str, id: &Value) -> Result<bool, String> {
+    let Some(id) = id.as_i64() else {
+        return Ok(false);
+    };
+    let count = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM {} WHERE id = ?", table))
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(count > 0)
+}
+
+fn push_value(values: &mut sqlx::query_builder::Separated<'_, '_, sqlx::Sqlite, &'static str>, value: &Value) {
+    match value {
+        Value::Null => values.push_bind(None::<String>),
+        Value::Bool(b) => values.push_bind(*b as i64),
+        Value::Number(n) if n.is_i64() => values.push_bind(n.as_i64()),
+        Value::Number(n) => values.push_bind(n.as_f64()),
+        Value::String(s) => values.push_bind(s.clone()),
+        other => values.push_bind(other.to_string()),
+    };
+}
+
+// Insert a row saved with `snapshot_rows` as it was (same id)
+pub(crate) async fn insert_row(conn: &mut SqliteConnection, table: &str, row: &Map<String, Value>) -> Result<(), String> {
+    let mut query = sqlx::QueryBuilder::<sqlx::Sqlite>::new(format!("INSERT INTO {} (", table));
+    let mut columns = query.separated(", ");
+    for column in row.keys() {
+        columns.push(column.as_str());
+    }
+    query.push(") VALUES (");
+    let mut values = query.separated(", ");
+    for value in row.values() {
+        push_value(&mut values, value);
+    }
+    query.push(")");
+
+    query.build().execute(&mut *conn).await.map_err(|e| {
+        if e.to_string().contains("UNIQUE") {
+            format!("Can't restore: an entry of {} already uses the same name or path", table)
+        } else {
+            e.to_string()
+        }
+    })?;
+
+    Ok(())
+}
+
+// Put back the values of a row saved with `snapshot_rows`, inserting it when it no longer exists
+pub(crate) async fn write_row(conn: &mut SqliteConnection, table: &str, row: &Map<String, Value>) -> Result<(), String> {
+    let id = row.get("id").cloned().unwrap_or(Value::Null);
+    if !row_exists(conn, table, &id).await? {
+        return insert_row(conn, table, row).await;
+    }
+
+    let columns: Vec<&str> = row.keys().map(String::as_str).collect();
+    update_columns(conn, table, row, &columns).await
+}
+
+// Put back some of the values of a row saved with `snapshot_rows`
+pub(crate) async fn update_columns(
+    conn: &mut SqliteConnection,
+    table: &str,
+    row: &Map<String, Value>,
+    columns: &[&str],
+) -> Result<(), String> {
+    let mut query = sqlx::QueryBuilder::<sqlx::Sqlite>::new(format!("UPDATE {} SET id = id", table));
+    for (column, value) in row.iter().filter(|(column, _)| column.as_str() != "id" && columns.contains(&column.as_str())) {
+        query.push(format!(", {} = ", column));
+        let mut assignment = query.separated("");
+        push_value(&mut assignment, value);
+    }
+    query.push(" WHERE id = ");
+    query.push_bind(row.get("id").and_then(|id| id.as_i64()));
+
+    query.build().execute(&mut *conn).await.map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// Put the rows of a trash entry back. Links to rows that no longer exist are dropped
+// (folders that are gone, objects deleted since).
+async fn restore_records(conn: &mut SqliteConnection, records: &TrashedRecords) -> Result<(), String> {
+    for row in &records.rows {
+        let mut row = row.clone();
+
+        match records.table.as_str() {
+            "object_categories" => {
+                sqlx::query("UPDATE objects SET category_id = ? WHERE id = ? AND category_id IS NULL")
+                    .bind(row.get("category_id").and_then(|v| v.as_i64()))
+                    .bind(row.get("id").and_then(|v| v.as_i64()))
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                continue;
+            }
+            "object_photos" | "object_attributes" => {
+                let object_id = row.get("object_id").cloned().unwrap_or(Value::Null);
+                if !row_exists(conn, "objects", &object_id).await? {
+                    continue;
+                }
+            }
+            "folders" => {
+                let parent_id = row.get("parent_id").cloned().unwrap_or(Value::Null);
+                if !parent_id.is_null() && !row_exists(conn, "folders", &parent_id).await? {
+                    row.insert("parent_id".to_string(), Value::Null);
+                }
+            }
+            "photos" => {
+                let folder_id = row.get("folder_id").cloned().unwrap_or(Value::Null);
+                if !folder_id.is_null() && !row_exists(conn, "folders", &folder_id).await? {
+                    row.insert("folder_id".to_string(), Value::Null);
+                }
+            }
+            "text_files" => {
+                let folder_id = row.get("folder_id").cloned().unwrap_or(Value::Null);
+                if !folder_id.is_null() && !row_exists(conn, "folders", &folder_id).await? {
+                    row.insert("folder_id".to_string(), Value::Null);
+                }
+            }
+            "categories" => {
+                let parent_id = row.get("parent_id").cloned().unwrap_or(Value::Null);
+                if !parent_id.is_null() && !row_exists(conn, "categories", &parent_id).await? {
+                    row.insert("parent_id".to_string(), Value::Null);
+                }
+            }
+            _ => {}
+        }
+
+        insert_row(conn, &records.table, &row).await?;
+    }
+
+    Ok(())
+}
+
+// ========== TRASH ENTRIES ==========
+
+// Move a file or folder to the trash if it is still on disk. Done before the rows are removed so
+// that the write transaction stays short: the caller puts the file back with
+// `move_path(trash_path, original_path)` if that transaction fails.
+pub(crate) fn trash_if_exists(path: &Path) -> Result<Option<PathBuf>, String> {
+    if path.exists() {
+        move_to_trash(path).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+// Record a trash entry: the file or folder already moved to `trash_path` (if any) and the database
+// rows removed along with it. Runs inside the transaction removing the rows.
+pub(crate) async fn add_trash_entry(
+    conn: &mut SqliteConnection,
+    kind: &str,
+    name: &str,
+    original_path: Option<&Path>,
+    trash_path: Option<&Path>,
+    records: &[TrashedRecords],
+) -> Result<i64, String> {
+    let records_json = serde_json::to_string(records).map_err(|e| e.to_string())?;
+    let file_size = trash_path.map_or(0, path_size) as i64;
+
+    let result = sqlx::query(
+        "INSERT INTO trash_entries (kind, name, original_path, trash_path, records, file_size) VALUES (?, ?, ?, ?, ?, ?)"
+    )
+    .bind(kind)
+    .bind(name)
+    .bind(original_path.map(|p| p.to_string_lossy().to_string()))
+    .bind(trash_path.map(|p| p.to_string_lossy().to_string()))
+    .bind(&records_json)
+    .bind(file_size)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(result.last_insert_rowid())
+}
+
+async fn fetch_trash_entry(conn: &mut SqliteConnection, id: i64) -> Result<TrashEntry, String> {
+    sqlx::query_as::<_, TrashEntry>("SELECT * FROM trash_entries WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Trash entry not found".to_string())
+}
+
+// Delete the files of a trash entry for good (and its sub-folder of the trash when empty)
+fn remove_trashed_files(entry: &TrashEntry) -> Result<(), String> {
+    if let Some(trash_path) = &entry.trash_path {
+        let trash_path = Path::new(trash_path);
+        if trash_path.exists() {
+            remove_path(trash_path).map_err(|e| format!("Failed to purge {}: {}", trash_path.display(), e))?;
+        }
+        if let Some(entry_dir) = trash_path.parent() {
+            if trash_dir().is_ok_and(|dir| entry_dir.starts_with(&dir) && entry_dir != dir) {
+                let _ = fs::remove_dir(entry_dir);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_trash(pool: State<'_, SqlitePool>) -> Result<Vec<TrashEntry>, String> {
+    sqlx::query_as::<_, TrashEntry>("SELECT * FROM trash_entries ORDER BY deleted_at DESC, id DESC")
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Put the rows of a trash entry back and its file where it was. Runs inside the caller's
+// transaction: returns the entry and the (trash, original) paths of the restored file so that
+// the file can go back to the trash if the transaction fails.
+pub(crate) async fn restore_entry(
+    conn: &mut SqliteConnection,
+    id: i64,
+) -> Result<(TrashEntry, Option<(PathBuf, PathBuf)>), String> {
+    let entry = fetch_trash_entry(conn, id).await?;
+
+    let records: Vec<TrashedRecords> = serde_json::from_str(&entry.records).map_err(|e| e.to_string())?;
+    for records in &records {
+        restore_records(conn, records).await?;
+    }
+
+    sqlx::query("DELETE FROM trash_entries WHERE id = ?")
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut restored_file: Option<(PathBuf, PathBuf)> = None;
+    if let (Some(trash_path), Some(original_path)) = (&entry.trash_path, &entry.original_path) {
+        let (trash_path, original_path) = (PathBuf::from(trash_path), PathBuf::from(original_path));
+        if original_path.exists() {
+            return Err(format!("{} already exists", original_path.display()));
+        }
+        if !trash_path.exists() {
+            return Err(format!("The trashed file is missing: {}", trash_path.display()));
+        }
+        move_path(&trash_path, &original_path)?;
+        restored_file = Some((trash_path, original_path));
+    }
+
+    Ok((entry, restored_file))
+}
+
+// Delete again what a restored trash entry put back (rows and file), as a new trash entry.
+// The file is put back if the rows can't be removed.
+pub(crate) async fn retrash_entry(
+    conn: &mut SqliteConnection,
+    entry: &TrashEntry,
+) -> Result<(i64, Option<PathBuf>), String> {
+    let records: Vec<TrashedRecords> = serde_json::from_str(&entry.records).map_err(|e| e.to_string())?;
+
+    let original_path = entry.original_path.as_ref().map(PathBuf::from);
+    let trash_path = match &original_path {
+        Some(path) => trash_if_exists(path)?,
+        None => None,
+    };
+
+    let outcome: Result<i64, String> = async {
+        for records in records.iter().rev() {
+            for row in &records.rows {
+                let id = row.get("id").and_then(|v| v.as_i64());
+                if records.table == "object_categories" {
+                    sqlx::query("UPDATE objects SET category_id = NULL WHERE id = ? AND category_id = ?")
+                        .bind(id)
+                        .bind(row.get("category_id").and_then(|v| v.as_i64()))
+                        .execute(&mut *conn)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                } else {
+                    sqlx::query(&format!("DELETE FROM {} WHERE id = ?", records.table))
+                        .bind(id)
+                        .execute(&mut *conn)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+        }
+
+        add_trash_entry(conn, &entry.kind, &entry.name, original_path.as_deref(), trash_path.as_deref(), &records).await
+    }
+    .await;
+
+    match outcome {
+        Ok(id) => Ok((id, trash_path)),
+        Err(e) => {
+            if let (Some(trash_path), Some(original_path)) = (&trash_path, &original_path) {
+                let _ = move_path(trash_path, original_path);
+            }
+            Err(e)
+        }
+    }
+}
+
+// Put a trashed file or folder back where it was, with its database rows
+#[tauri::command]
+pub async fn restore_trash_entry(pool: State<'_, SqlitePool>, id: i64) -> Result<TrashEntry, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let (entry, restored_file) = restore_entry(&mut tx, id).await?;
+
+    if let Err(e) = tx.commit().await {
+        if let Some((trash_path, original_path)) = &restored_file {
+            let _ = move_path(original_path, trash_path);
+        }
+        return Err(e.to_string());
+    }
+
+    if let Some((trash_path, _)) = &restored_file {
+        if let Some(entry_dir) = trash_path.parent() {
+            let _ = fs::remove_dir(entry_dir);
+        }
+    }
+
+    Ok(entry)
+}
+
+// Delete a trash entry and its files for good
+#[tauri::command]
+pub async fn purge_trash_entry(pool: State<'_, SqlitePool>, id: i64) -> Result<(), String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+
+    let entry = fetch_trash_entry(&mut conn, id).await?;
+    remove_trashed_files(&entry)?;
+
+    sqlx::query("DELETE FROM trash_entries WHERE id = ?")
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+async fn purge_entries(pool: &SqlitePool, entries: Vec<TrashEntry>) -> Result<usize, String> {
+    let mut purged = 0;
+    for entry in entries {
+        remove_trashed_files(&entry)?;
+        sqlx::query("DELETE FROM trash_entries WHERE id = ?")
+            .bind(entry.id)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        purged += 1;
+    }
+    Ok(purged)
+}
+
+#[tauri::command]
+pub async fn empty_trash(pool: State<'_, SqlitePool>) -> Result<usize, String> {
+    let entries = sqlx::query_as::<_, TrashEntry>("SELECT * FROM trash_entries")
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    purge_entries(pool.inner(), entries).await
+}
+
+// Purge the entries older than the retention period (`trash_retention_days` setting, 0 = never)
+pub(crate) async fn purge_expired_entries(pool: &SqlitePool) -> Result<usize, String> {
+    let retention_days = sqlx::query_scalar::<_, String>("SELECT value FROM settings WHERE key = 'trash_retention_days'")
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .unwrap_or(30);
+
+    if retention_days <= 0 {
+        return Ok(0);
+    }
+
+    let entries = sqlx::query_as::<_, TrashEntry>(
+        "SELECT * FROM trash_entries WHERE deleted_at < datetime('now', ?)"
+    )
+    .bind(format!("-{} days", retention_days))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    purge_entries(pool, entries).await
+}
+
+#[tauri::command]
+pub async fn purge_expired_trash(pool: State<'_, SqlitePool>) -> Result<usize, String> {
+    purge_expired_entries(pool.inner()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::Connection;
+
+    #[tokio::test]
+    async fn restored_rows() {
+        let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+        sqlx::raw_sql(
+            "CREATE TABLE objects (id INTEGER PRIMARY KEY, name TEXT);
+             CREATE TABLE folders (id INTEGER PRIMARY KEY, path TEXT UNIQUE);
+             CREATE TABLE photos (id INTEGER PRIMARY KEY, file_path TEXT UNIQUE, folder_id INTEGER, width INTEGER, ratio REAL);
+             CREATE TABLE object_photos (id INTEGER PRIMARY KEY, object_id INTEGER, photo_id INTEGER);
+             INSERT INTO objects (id, name) VALUES (1, 'Lamp'), (2, 'Vase');
+             INSERT INTO folders (id, path) VALUES (1, 'a');
+             INSERT INTO photos (id, file_path, folder_id, width, ratio) VALUES (3, 'a/p.jpg', 1, 800, 1.5), (4, 'a/q.jpg', 1, NULL, NULL);
+             INSERT INTO object_photos (object_id, photo_id) VALUES (1, 3), (2, 4);",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        // Stored as JSON with the trash entry
+        let records = vec![
+            snapshot_rows(&mut conn, "photos", "id", &[3, 4]).await.unwrap(),
+            snapshot_rows(&mut conn, "object_photos", "photo_id", &[3, 4]).await.unwrap(),
+        ];
+        let records: Vec<TrashedRecords> = serde_json::from_str(&serde_json::to_string(&records).unwrap()).unwrap();
+
+        // The folder and the second object are deleted while the photos are in the trash
+        sqlx::raw_sql(
+            "DELETE FROM object_photos; DELETE FROM photos; DELETE FROM folders; DELETE FROM objects WHERE id = 2;",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        for records in &records {
+            restore_records(&mut conn, records).await.unwrap();
+        }
+
+        let photos = sqlx::query_as::<_, (i64, String, Option<i64>, Option<i64>, Option<f64>)>(
+            "SELECT id, file_path, folder_id, width, ratio FROM photos ORDER BY id",
+        )
+        .fetch_all(&mut conn)
+        .await
+        .unwrap();
+        assert_eq!(
+            photos,
+            [
+                (3, "a/p.jpg".to_string(), None, Some(800), Some(1.5)),
+                (4, "a/q.jpg".to_string(), None, None, None),
+            ]
+        );
+        let links = sqlx::query_as::<_, (i64, i64)>("SELECT object_id, photo_id FROM object_photos")
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(links, [(1, 3)]);
+
+        let error = insert_row(&mut conn, "photos", &records[0].rows[0]).await.unwrap_err();
+        assert_eq!(error, "Can't restore: an entry of photos already uses the same name or path");
+    }
+}
@@ -34,31 +34,8 @@ pub async fn init_db() -> Result<SqlitePool, sqlx::Error> {
         .await?;
 
     // Execute migrations - statement by statement
-    let migration_sql = include_str!("../migrations/001_init.sql");
-
-    // Clean comments and divide by statements
-    let lines: Vec<&str> = migration_sql.lines().collect();
-    let mut current_statement = String::new();
-
-    for line in lines {
-        let trimmed = line.trim();
-
-        // Ignore comments and empty lines
-        if trimmed.is_empty() || trimmed.starts_with("--") {
-            continue;
-        }
-
-        current_statement.push_str(line);
-        current_statement.push(' ');
-
-        // If the line ends with a semicolon, execute the statement
-        if trimmed.ends_with(';') {
-            let stmt = current_statement.trim().trim_end_matches(';');
-            if !stmt.is_empty() {
-                sqlx::query(stmt).execute(&pool).await?;
-            }
-            current_statement.clear();
-        }
+    for stmt in migration_statements(include_str!("../migrations/001_init.sql")) {
+        sqlx::query(&stmt).execute(&pool).await?;
     }
 
     // Try to add the category_id column if it doesn't exist
@@ -66,25 +43,7 @@ pub async fn init_db() -> Result<SqlitePool, sqlx::Error> {
         .execute(&pool)
         .await; // Ignore the error if the column already exists
 
-    let migration_sql_002 = include_str!("../migrations/002_add_categories.sql");
-    let lines_002: Vec<&str> = migration_sql_002.lines().collect();
-    let mut current_statement_002 = String::new();
-
-    for line in lines_002 {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with("--") {
-            continue;
-        }
-        current_statement_002.push_str(line);
-        current_statement_002.push(' ');
-        if trimmed.ends_with(';') {
-            let stmt = current_statement_002.trim().trim_end_matches(';');
-            if !stmt.is_empty() {
-                let _ = sqlx::query(stmt).execute(&pool).await;
-            }
-            current_statement_002.clear();
-        }
-    }
+    run_migration(&pool, include_str!("../migrations/002_add_categories.sql")).await;
 
     // Migration 003: Add API fields for platforms (one by one because SQLite)
     let _ = sqlx::query("ALTER TABLE platforms ADD COLUMN api_key TEXT").execute(&pool).await;
@@ -96,89 +55,138 @@ pub async fn init_db() -> Result<SqlitePool, sqlx::Error> {
     let _ = sqlx::query("ALTER TABLE platforms ADD COLUMN updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP").execute(&pool).await;
 
     // Migration 004: Add folders table
-    let migration_sql_004 = include_str!("../migrations/004_add_folders_table.sql");
-    let lines_004: Vec<&str> = migration_sql_004.lines().collect();
-    let mut current_statement_004 = String::new();
-
-    for line in lines_004 {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with("--") {
-            continue;
-        }
-        current_statement_004.push_str(line);
-        current_statement_004.push(' ');
-        if trimmed.ends_with(';') {
-            let stmt = current_statement_004.trim().trim_end_matches(';');
-            if !stmt.is_empty() {
-                let _ = sqlx::query(stmt).execute(&pool).await;
-            }
-            current_statement_004.clear();
-        }
-    }
+    run_migration(&pool, include_str!("../migrations/004_add_folders_table.sql")).await;
 
     // Migration 005: Add settings table
-    let migration_sql_005 = include_str!("../migrations/005_add_settings_table.sql");
-    let lines_005: Vec<&str> = migration_sql_005.lines().collect();
-    let mut current_statement_005 = String::new();
-
-    for line in lines_005 {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with("--") {
-            continue;
-        }
-        current_statement_005.push_str(line);
-        current_statement_005.push(' ');
-        if trimmed.ends_with(';') {
-            let stmt = current_statement_005.trim().trim_end_matches(';');
-            if !stmt.is_empty() {
-                let _ = sqlx::query(stmt).execute(&pool).await;
-            }
-            current_statement_005.clear();
-        }
-    }
+    run_migration(&pool, include_str!("../migrations/005_add_settings_table.sql")).await;
 
     // Migration 006: Add text_files table
-    let migration_sql_006 = include_str!("../migrations/006_add_text_files_table.sql");
-    let lines_006: Vec<&str> = migration_sql_006.lines().collect();
-    let mut current_statement_006 = String::new();
+    run_migration(&pool, include_str!("../migrations/006_add_text_files_table.sql")).await;
 
-    for line in lines_006 {
+    // Migration 007: Add is_main field to photos table
+    run_migration(&pool, include_str!("../migrations/007_add_main_photo_field.sql")).await;
+
+    // Migration 008: Add per-platform listing content and platform listing rules
+    run_migration(&pool, include_str!("../migrations/008_add_listing_fields.sql")).await;
+
+    // Migration 009: Add templates table
+    run_migration(&pool, include_str!("../migrations/009_add_templates_table.sql")).await;
+
+    // Migration 010: Add CSV column mapping to platforms
+    run_migration(&pool, include_str!("../migrations/010_add_platform_csv_mapping.sql")).await;
+
+    // Migration 011: Add external SKU to objects
+    run_migration(&pool, include_str!("../migrations/011_add_object_external_sku.sql")).await;
+
+    // Migration 012: Add SKU generator (object SKU, category prefixes, sequences)
+    run_migration(&pool, include_str!("../migrations/012_add_sku_generator.sql")).await;
+
+    // Migration 013: Add storage locations and location history
+    run_migration(&pool, include_str!("../migrations/013_add_locations.sql")).await;
+
+    // Migration 014: Add object quantities, bundles and sales
+    run_migration(&pool, include_str!("../migrations/014_add_quantity_and_bundles.sql")).await;
+
+    // Migration 015: Add object condition, brand, dimensions and custom attributes
+    run_migration(&pool, include_str!("../migrations/015_add_object_attributes.sql")).await;
+
+    // Migration 016: Add nested categories and category platform mappings
+    run_migration(&pool, include_str!("../migrations/016_add_category_hierarchy.sql")).await;
+
+    // Migration 017: Add trash entries
+    run_migration(&pool, include_str!("../migrations/017_add_trash.sql")).await;
+
+    // Migration 018: Add operation journal
+    run_migration(&pool, include_str!("../migrations/018_add_operation_journal.sql")).await;
+
+    // Migration 019: Add change history
+    run_migration(&pool, include_str!("../migrations/019_add_change_history.sql")).await;
+
+    // Migration 020: Store library paths relative to the root folder
+    run_migration(&pool, include_str!("../migrations/020_relative_library_paths.sql")).await;
+
+    // Migration 021: Add photo content hash
+    run_migration(&pool, include_str!("../migrations/021_add_photo_content_hash.sql")).await;
+
+    // Migration 022: Add sync fingerprints
+    run_migration(&pool, include_str!("../migrations/022_add_sync_fingerprints.sql")).await;
+
+    // Migration 023: Add folder metadata
+    run_migration(&pool, include_str!("../migrations/023_add_folder_metadata.sql")).await;
+
+    // Migration 024: Add folder object link
+    run_migration(&pool, include_str!("../migrations/024_add_folder_object_link.sql")).await;
+
+    // Migration 025: Add description sync state
+    run_migration(&pool, include_str!("../migrations/025_add_description_sync_state.sql")).await;
+
+    println!("Database initialized successfully at {}", db_path.display());
+    Ok(pool)
+}
+
+// Split a migration file into statements, skipping comments and empty lines. Trigger bodies
+// (BEGIN ... END;) contain several statements and are kept in one piece
+fn migration_statements(sql: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current_statement = String::new();
+    let mut in_block = false;
+
+    for line in sql.lines() {
         let trimmed = line.trim();
         if trimmed.is_empty() || trimmed.starts_with("--") {
             continue;
         }
-        current_statement_006.push_str(line);
-        current_statement_006.push(' ');
-        if trimmed.ends_with(';') {
-            let stmt = current_statement_006.trim().trim_end_matches(';');
+        current_statement.push_str(line);
+        current_statement.push(' ');
+        if trimmed.eq_ignore_ascii_case("BEGIN") {
+            in_block = true;
+        }
+        if trimmed.ends_with(';') && (!in_block || trimmed.eq_ignore_ascii_case("END;")) {
+            let stmt = current_statement.trim().trim_end_matches(';');
             if !stmt.is_empty() {
-                let _ = sqlx::query(stmt).execute(&pool).await;
+                statements.push(stmt.to_string());
             }
-            current_statement_006.clear();
+            current_statement.clear();
+            in_block = false;
         }
     }
+    statements
+}
 
-    // Migration 007: Add is_main field to photos table
-    let migration_sql_007 = include_str!("../migrations/007_add_main_photo_field.sql");
-    let lines_007: Vec<&str> = migration_sql_007.lines().collect();
-    let mut current_statement_007 = String::new();
+// Run the statements of a migration file. Migrations run at every start: the errors of
+// statements already applied (ALTER TABLE ... ADD COLUMN) are ignored. The statements share one
+// connection: another connection of the pool may not see a trigger dropped just before
+async fn run_migration(pool: &SqlitePool, sql: &str) {
+    let Ok(mut conn) = pool.acquire().await else {
+        return;
+    };
+    for stmt in migration_statements(sql) {
+        let _ = sqlx::query(&stmt).execute(&mut *conn).await;
+    }
+}
 
-    for line in lines_007 {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with("--") {
-            continue;
-        }
-        current_statement_007.push_str(line);
-        current_statement_007.push(' ');
-        if trimmed.ends_with(';') {
-            let stmt = current_statement_007.trim().trim_end_matches(';');
-            if !stmt.is_empty() {
-                let _ = sqlx::query(stmt).execute(&pool).await;
-            }
-            current_statement_007.clear();
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migration_statements_keep_trigger_bodies() {
+        let sql = "-- comment\nCREATE TABLE t (id INTEGER);\n\nCREATE TRIGGER t_insert AFTER INSERT ON t\nBEGIN\n    INSERT INTO log VALUES (NEW.id);\n    DELETE FROM log WHERE id < 0;\nEND;\nDROP TABLE u;\n";
+        let statements = migration_statements(sql);
+        assert_eq!(statements.len(), 3);
+        assert_eq!(statements[0], "CREATE TABLE t (id INTEGER)");
+        assert!(statements[1].starts_with("CREATE TRIGGER t_insert"));
+        assert!(statements[1].contains("DELETE FROM log WHERE id < 0;"));
+        assert!(statements[1].ends_with("END"));
+        assert_eq!(statements[2], "DROP TABLE u");
     }
 
-    println!("Database initialized successfully at {}", db_path.display());
-    Ok(pool)
+    #[test]
+    fn change_history_triggers_are_recreated() {
+        let statements = migration_statements(include_str!("../migrations/019_add_change_history.sql"));
+        let drops = statements.iter().filter(|s| s.starts_with("DROP TRIGGER IF EXISTS")).count();
+        let creates = statements.iter().filter(|s| s.starts_with("CREATE TRIGGER ")).count();
+        assert_eq!(drops, 12);
+        assert_eq!(creates, 12);
+    }
 }
@@ -17,11 +17,15 @@ async fn main() {
         .await
         .expect("Failed to initialize database");
 
+    // Trash entries older than the retention period are purged on startup
+    let _ = commands::trash::purge_expired_entries(&pool).await;
+
     tauri::Builder::default()
         .manage(pool)
         .invoke_handler(tauri::generate_handler![
             commands::list_objects,
             commands::get_object,
+            commands::search_objects,
             commands::create_object,
             commands::update_object,
             commands::delete_object,
@@ -50,23 +54,94 @@ async fn main() {
             commands::get_object_platforms,
             commands::add_object_to_platform,
             commands::remove_object_from_platform,
+            commands::listings::update_object_listing,
+            commands::listings::validate_object_listing,
+            commands::templates::list_templates,
+            commands::templates::create_template,
+            commands::templates::update_template,
+            commands::templates::delete_template,
+            commands::templates::preview_template,
+            commands::templates::apply_template_to_listing,
+            commands::exports::export_listing_package,
+            commands::exports::export_objects_csv,
+            commands::exports::list_csv_sources,
+            commands::exports::get_csv_mapping,
+            commands::exports::set_csv_mapping,
+            commands::imports::preview_spreadsheet,
+            commands::imports::import_objects,
+            commands::skus::generate_object_sku,
+            commands::skus::generate_missing_skus,
+            commands::skus::preview_sku_pattern,
+            commands::skus::set_sku_pattern,
+            commands::skus::set_category_sku_prefix,
+            commands::skus::print_object_labels,
+            commands::locations::list_locations,
+            commands::locations::get_location_path,
+            commands::locations::create_location,
+            commands::locations::update_location,
+            commands::locations::delete_location,
+            commands::locations::move_objects_to_location,
+            commands::locations::get_object_location_history,
+            commands::bundles::list_bundles,
+            commands::bundles::get_bundle_items,
+            commands::bundles::get_object_bundles,
+            commands::bundles::create_bundle,
+            commands::bundles::update_bundle,
+            commands::bundles::delete_bundle,
+            commands::sales::record_object_sale,
+            commands::sales::record_bundle_sale,
+            commands::sales::list_sales,
+            commands::sales::cancel_sale,
+            commands::attributes::list_attribute_definitions,
+            commands::attributes::create_attribute_definition,
+            commands::attributes::update_attribute_definition,
+            commands::attributes::delete_attribute_definition,
+            commands::attributes::get_object_attributes,
+            commands::categories::update_category,
+            commands::categories::get_category_mappings,
+            commands::categories::set_category_mapping,
+            commands::trash::list_trash,
+            commands::trash::restore_trash_entry,
+            commands::trash::purge_trash_entry,
+            commands::trash::empty_trash,
+            commands::trash::purge_expired_trash,
+            commands::journal::undo_last,
+            commands::journal::redo,
+            commands::journal::list_journal,
+            commands::history::get_object_history,
+            commands::history::list_change_history,
             commands::list_categories,
             commands::create_category,
-            commands::delete_category,
+            commands::categories::delete_category,
+            commands::categories::preview_category_deletion,
             commands::list_volumes,
             commands::scan_volume_for_photos,
             commands::import_photos,
-            commands::move_photos_and_folders,
-            commands::copy_photos_and_folders,
+            commands::transfers::move_photos_and_folders,
+            commands::transfers::copy_photos_and_folders,
             commands::list_folders,
             commands::create_folder,
             commands::delete_folder_from_db,
-            commands::sync_database,
+            commands::folders::rename_folder,
+            commands::folders::update_folder_metadata,
+            commands::folders::list_folder_tags,
+            commands::folders::create_object_from_folder,
+            commands::folders::create_folder_for_object,
+            commands::folders::set_folder_object,
+            commands::descriptions::get_description_sync_status,
+            commands::descriptions::sync_descriptions,
+            commands::descriptions::resolve_description_conflict,
+            commands::sync::sync_database,
+            commands::sync::apply_sync_plan,
             commands::get_setting,
             commands::set_setting,
             commands::get_all_settings,
+            commands::ignore::get_ignore_settings,
+            commands::ignore::set_ignore_settings,
             commands::get_root_folder,
             commands::set_root_folder,
+            commands::library::get_library_status,
+            commands::library::relocate_root_folder,
             updater::check_for_updates,
             updater::download_and_install_update,
         ])
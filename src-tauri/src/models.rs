@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use sqlx::FromRow;
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -11,6 +12,17 @@ pub struct Object {
     pub year: Option<i32>,
     pub weight: Option<f64>,
     pub category_id: Option<i64>,
+    pub external_sku: Option<String>,
+    pub sku: Option<String>,
+    pub location_id: Option<i64>,
+    pub quantity: i64,
+    // new, like_new, very_good, good, acceptable, for_parts
+    pub condition: Option<String>,
+    pub brand: Option<String>,
+    pub length: Option<f64>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub dimension_unit: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -22,6 +34,17 @@ pub struct CreateObject {
     pub year: Option<i32>,
     pub weight: Option<f64>,
     pub category_id: Option<i64>,
+    pub external_sku: Option<String>,
+    pub sku: Option<String>,
+    pub quantity: Option<i64>,
+    pub condition: Option<String>,
+    pub brand: Option<String>,
+    pub length: Option<f64>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub dimension_unit: Option<String>,
+    // Custom attribute values by attribute definition id (null removes the value)
+    pub attributes: Option<HashMap<i64, serde_json::Value>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -31,6 +54,43 @@ pub struct UpdateObject {
     pub year: Option<i32>,
     pub weight: Option<f64>,
     pub category_id: Option<i64>,
+    pub external_sku: Option<String>,
+    pub sku: Option<String>,
+    pub quantity: Option<i64>,
+    pub condition: Option<String>,
+    pub brand: Option<String>,
+    pub length: Option<f64>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub dimension_unit: Option<String>,
+    // Set to true to remove the length, width and height not given
+    pub clear_dimensions: Option<bool>,
+    // Custom attribute values by attribute definition id (null removes the value)
+    pub attributes: Option<HashMap<i64, serde_json::Value>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchObjects {
+    pub query: Option<String>,
+    pub category_id: Option<i64>,
+    pub location_id: Option<i64>,
+    // Also match objects stored in the sub-locations of `location_id`
+    pub include_sublocations: Option<bool>,
+    // Only objects without a location
+    pub unlocated: Option<bool>,
+    pub condition: Option<String>,
+    pub brand: Option<String>,
+    pub attributes: Option<Vec<AttributeFilter>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttributeFilter {
+    pub attribute_id: i64,
+    // Exact value (text attributes match when they contain it)
+    pub value: Option<serde_json::Value>,
+    // Range for number attributes
+    pub min: Option<f64>,
+    pub max: Option<f64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -38,11 +98,45 @@ pub struct Category {
     pub id: i64,
     pub name: String,
     pub created_at: String,
+    pub sku_prefix: Option<String>,
+    pub parent_id: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateCategory {
     pub name: String,
+    pub parent_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateCategory {
+    // Renames the category and its folder
+    pub name: Option<String>,
+    // Moves the category (and its folder) under another category
+    pub parent_id: Option<i64>,
+    // Set to true to move the category to the top level
+    pub clear_parent: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteCategory {
+    // 'detach' (default): keep the folder and its files, only remove the category
+    // 'move': move the content of the folder to another category or folder
+    // 'delete': move the folder to the trash and remove its photos and text files
+    pub mode: Option<String>,
+    pub target_category_id: Option<i64>,
+    pub target_folder_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct CategoryPlatformMapping {
+    pub id: i64,
+    pub category_id: i64,
+    pub platform_id: i64,
+    pub platform_category_id: String,
+    pub platform_category_name: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -79,6 +173,13 @@ pub struct Platform {
     pub refresh_token: Option<String>,
     pub token_expires_at: Option<String>,
     pub environment: Option<String>,
+    pub title_max_length: Option<i64>,
+    pub description_max_length: Option<i64>,
+    pub max_photos: Option<i64>,
+    pub max_photo_dimension: Option<i64>,
+    pub requires_category_id: Option<bool>,
+    pub requires_condition: Option<bool>,
+    pub csv_mapping: Option<String>,
     pub created_at: String,
     pub updated_at: Option<String>,
 }
@@ -102,9 +203,15 @@ pub struct UpdatePlatform {
     pub refresh_token: Option<String>,
     pub token_expires_at: Option<String>,
     pub environment: Option<String>,
+    pub title_max_length: Option<i64>,
+    pub description_max_length: Option<i64>,
+    pub max_photos: Option<i64>,
+    pub max_photo_dimension: Option<i64>,
+    pub requires_category_id: Option<bool>,
+    pub requires_condition: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ObjectPlatform {
     pub id: i64,
     pub object_id: i64,
@@ -112,10 +219,39 @@ pub struct ObjectPlatform {
     pub platform_url: Option<String>,
     pub status: String,
     pub listed_at: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub price: Option<f64>,
+    pub currency: Option<String>,
+    pub condition: Option<String>,
+    pub shipping_profile: Option<String>,
+    pub platform_category_id: Option<String>,
+    pub item_specifics: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateObjectListing {
+    pub platform_url: Option<String>,
+    pub status: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub price: Option<f64>,
+    pub currency: Option<String>,
+    pub condition: Option<String>,
+    pub shipping_profile: Option<String>,
+    pub platform_category_id: Option<String>,
+    pub item_specifics: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListingValidation {
+    pub valid: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Folder {
     pub id: i64,
@@ -123,6 +259,21 @@ pub struct Folder {
     pub name: String,
     pub parent_id: Option<i64>,
     pub created_at: String,
+    pub notes: Option<String>,
+    // Colour label (#rrggbb)
+    pub color: Option<String>,
+    // JSON array of tags
+    pub tags: Option<String>,
+    // Object whose photos are in the folder
+    pub object_id: Option<i64>,
+}
+
+// Folder metadata to change: None leaves a field unchanged, an empty value clears it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateFolderMetadata {
+    pub notes: Option<String>,
+    pub color: Option<String>,
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -142,3 +293,269 @@ pub struct TextFile {
     pub created_at: String,
     pub updated_at: String,
 }
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Template {
+    pub id: i64,
+    pub name: String,
+    pub platform_id: Option<i64>,
+    pub title_template: Option<String>,
+    pub body: String,
+    pub is_default: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateTemplate {
+    pub name: String,
+    pub platform_id: Option<i64>,
+    pub title_template: Option<String>,
+    pub body: String,
+    pub is_default: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateTemplate {
+    pub name: Option<String>,
+    pub platform_id: Option<i64>,
+    // Set to true to make the template available for every platform
+    pub clear_platform: Option<bool>,
+    pub title_template: Option<String>,
+    pub body: Option<String>,
+    pub is_default: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RenderedTemplate {
+    pub template_id: Option<i64>,
+    pub title: Option<String>,
+    pub body: String,
+    pub unknown_placeholders: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvColumn {
+    pub header: String,
+    // Field exported in this column (see CSV_SOURCES), or a constant value
+    pub source: Option<String>,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Location {
+    pub id: i64,
+    pub name: String,
+    // warehouse, shelf, box...
+    pub kind: String,
+    pub parent_id: Option<i64>,
+    pub notes: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateLocation {
+    pub name: String,
+    pub kind: Option<String>,
+    pub parent_id: Option<i64>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateLocation {
+    pub name: Option<String>,
+    pub kind: Option<String>,
+    pub parent_id: Option<i64>,
+    // Set to true to move the location to the top level
+    pub clear_parent: Option<bool>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ObjectLocationMove {
+    pub id: i64,
+    pub object_id: i64,
+    pub from_location_id: Option<i64>,
+    pub to_location_id: Option<i64>,
+    pub from_path: Option<String>,
+    pub to_path: Option<String>,
+    pub note: Option<String>,
+    pub moved_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Bundle {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub price: Option<f64>,
+    pub currency: Option<String>,
+    // draft, listed, sold
+    pub status: String,
+    pub platform_id: Option<i64>,
+    pub platform_url: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct BundleItem {
+    pub id: i64,
+    pub bundle_id: i64,
+    pub object_id: i64,
+    pub quantity: i64,
+    pub share: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleItemInput {
+    pub object_id: i64,
+    pub quantity: Option<i64>,
+    pub share: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateBundle {
+    pub name: String,
+    pub description: Option<String>,
+    pub price: Option<f64>,
+    pub currency: Option<String>,
+    pub items: Vec<BundleItemInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateBundle {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub price: Option<f64>,
+    pub currency: Option<String>,
+    pub status: Option<String>,
+    pub platform_id: Option<i64>,
+    pub platform_url: Option<String>,
+    // Replaces the objects of the bundle when set
+    pub items: Option<Vec<BundleItemInput>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Sale {
+    pub id: i64,
+    pub bundle_id: Option<i64>,
+    pub platform_id: Option<i64>,
+    pub total_price: f64,
+    pub currency: String,
+    pub notes: Option<String>,
+    pub sold_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct SaleItem {
+    pub id: i64,
+    pub sale_id: i64,
+    pub object_id: Option<i64>,
+    pub quantity: i64,
+    pub amount: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecordObjectSale {
+    pub object_id: i64,
+    pub quantity: Option<i64>,
+    pub total_price: f64,
+    pub currency: Option<String>,
+    pub platform_id: Option<i64>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecordBundleSale {
+    pub bundle_id: i64,
+    // Defaults to the price of the bundle
+    pub total_price: Option<f64>,
+    pub platform_id: Option<i64>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct AttributeDefinition {
+    pub id: i64,
+    // None for attributes of every object
+    pub category_id: Option<i64>,
+    pub name: String,
+    // text, number, enum, boolean
+    pub attribute_type: String,
+    // JSON array of the allowed values of enum attributes
+    pub options: Option<String>,
+    pub display_order: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateAttributeDefinition {
+    pub category_id: Option<i64>,
+    pub name: String,
+    pub attribute_type: String,
+    pub options: Option<Vec<String>>,
+    pub display_order: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateAttributeDefinition {
+    pub name: Option<String>,
+    pub options: Option<Vec<String>>,
+    pub display_order: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ObjectAttributeValue {
+    pub attribute_id: i64,
+    pub name: String,
+    pub attribute_type: String,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct TrashEntry {
+    pub id: i64,
+    // 'photo', 'text_file', 'folder' or 'category'
+    pub kind: String,
+    pub name: String,
+    pub original_path: Option<String>,
+    pub trash_path: Option<String>,
+    // JSON array of the database rows removed with the entry
+    #[serde(skip_serializing)]
+    pub records: String,
+    pub file_size: Option<i64>,
+    pub deleted_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct JournalEntry {
+    pub id: i64,
+    pub operation: String,
+    pub description: String,
+    // JSON array of the steps needed to undo/redo the operation
+    #[serde(skip_serializing)]
+    pub steps: String,
+    pub undone: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ChangeHistoryEntry {
+    pub id: i64,
+    // 'object', 'object_platform', 'object_photo' or 'category'
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub object_id: Option<i64>,
+    // Name of the object, platform, photo file or category at the time of the change
+    pub label: Option<String>,
+    // 'create', 'update' or 'delete'
+    pub action: String,
+    pub field: Option<String>,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_by: Option<String>,
+    pub changed_at: String,
+}
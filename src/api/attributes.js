@@ -0,0 +1,28 @@
+import { invoke } from '@tauri-apps/api/tauri'
+
+export const attributesAPI = {
+  // Lister les attributs disponibles (globaux + ceux de la catégorie)
+  list: async (categoryId = null) => {
+    return await invoke('list_attribute_definitions', { categoryId })
+  },
+
+  // Créer un attribut (attribute_type: 'text', 'number', 'enum' ou 'boolean')
+  create: async (definition) => {
+    return await invoke('create_attribute_definition', { definition })
+  },
+
+  // Modifier un attribut (nom, options, ordre)
+  update: async (id, definition) => {
+    return await invoke('update_attribute_definition', { id, definition })
+  },
+
+  // Supprimer un attribut et ses valeurs
+  delete: async (id) => {
+    return await invoke('delete_attribute_definition', { id })
+  },
+
+  // Valeurs des attributs d'un objet
+  getObjectValues: async (objectId) => {
+    return await invoke('get_object_attributes', { objectId })
+  },
+}
@@ -0,0 +1,33 @@
+import { invoke } from '@tauri-apps/api/tauri'
+
+export const bundlesAPI = {
+  // Lister tous les lots
+  list: async () => {
+    return await invoke('list_bundles')
+  },
+
+  // Objets d'un lot (quantité et part du prix de vente)
+  getItems: async (bundleId) => {
+    return await invoke('get_bundle_items', { bundleId })
+  },
+
+  // Lots contenant un objet
+  getForObject: async (objectId) => {
+    return await invoke('get_object_bundles', { objectId })
+  },
+
+  // Créer un lot (items: [{ object_id, quantity, share }])
+  create: async (bundle) => {
+    return await invoke('create_bundle', { bundle })
+  },
+
+  // Modifier un lot
+  update: async (id, bundle) => {
+    return await invoke('update_bundle', { id, bundle })
+  },
+
+  // Supprimer un lot (les objets et les ventes sont conservés)
+  delete: async (id) => {
+    return await invoke('delete_bundle', { id })
+  },
+}
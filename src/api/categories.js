@@ -0,0 +1,46 @@
+import { invoke } from '@tauri-apps/api/tauri'
+
+export const categoriesAPI = {
+  // Lister toutes les catégories (parent_id indique la catégorie parente)
+  list: async () => {
+    return await invoke('list_categories')
+  },
+
+  // Créer une catégorie, éventuellement dans une catégorie parente
+  create: async (name, parentId = null) => {
+    return await invoke('create_category', { category: { name, parent_id: parentId } })
+  },
+
+  // Renommer et/ou déplacer une catégorie (le dossier sur disque suit)
+  // category: { name, parent_id, clear_parent }
+  update: async (id, category) => {
+    return await invoke('update_category', { id, category })
+  },
+
+  // Aperçu de ce que la suppression affecterait (dossiers, photos, objets, conflits)
+  previewDelete: async (id, request = null) => {
+    return await invoke('preview_category_deletion', { id, request })
+  },
+
+  // Supprimer une catégorie sans sous-catégories
+  // request: { mode: 'detach' | 'move' | 'delete', target_category_id, target_folder_id }
+  // 'detach' garde les fichiers, 'move' les déplace, 'delete' les met à la corbeille
+  delete: async (id, request = null) => {
+    return await invoke('delete_category', { id, request })
+  },
+
+  // Correspondances avec les catégories des plateformes
+  getMappings: async (categoryId) => {
+    return await invoke('get_category_mappings', { categoryId })
+  },
+
+  // Associer une catégorie à une catégorie de plateforme (null pour supprimer)
+  setMapping: async (categoryId, platformId, platformCategoryId, platformCategoryName = null) => {
+    return await invoke('set_category_mapping', {
+      categoryId,
+      platformId,
+      platformCategoryId,
+      platformCategoryName,
+    })
+  },
+}
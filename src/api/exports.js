@@ -0,0 +1,30 @@
+import { invoke } from '@tauri-apps/api/tauri'
+
+export const exportsAPI = {
+  // Exporter des objets en CSV (format 'ebay', 'shopify' ou 'full')
+  exportCsv: async (objectIds, format, platformId = null, destinationPath = null) => {
+    return await invoke('export_objects_csv', {
+      request: {
+        object_ids: objectIds,
+        format,
+        platform_id: platformId,
+        destination_path: destinationPath
+      }
+    })
+  },
+
+  // Lister les champs disponibles pour les colonnes CSV
+  listSources: async () => {
+    return await invoke('list_csv_sources')
+  },
+
+  // Obtenir le mapping de colonnes d'une plateforme (ou celui du format par défaut)
+  getMapping: async (platformId, format) => {
+    return await invoke('get_csv_mapping', { platformId, format })
+  },
+
+  // Enregistrer le mapping de colonnes d'une plateforme (null pour revenir au format par défaut)
+  setMapping: async (platformId, columns) => {
+    return await invoke('set_csv_mapping', { platformId, columns })
+  },
+}
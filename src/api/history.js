@@ -0,0 +1,13 @@
+import { invoke } from '@tauri-apps/api/tauri'
+
+export const historyAPI = {
+  // Historique d'un objet (modifications, annonces, photos), le plus récent en premier
+  getObjectHistory: async (objectId, limit = null) => {
+    return await invoke('get_object_history', { objectId, limit })
+  },
+
+  // Dernières modifications, éventuellement filtrées (ex: entityType 'category')
+  list: async (entityType = null, entityId = null, limit = 100) => {
+    return await invoke('list_change_history', { entityType, entityId, limit })
+  },
+}
@@ -3,3 +3,13 @@ export { photosAPI } from './photos'
 export { textFilesAPI } from './textFiles'
 export { settingsAPI } from './settings'
 export { platformsAPI } from './platforms'
+export { templatesAPI } from './templates'
+export { exportsAPI } from './exports'
+export { locationsAPI } from './locations'
+export { bundlesAPI } from './bundles'
+export { salesAPI } from './sales'
+export { attributesAPI } from './attributes'
+export { categoriesAPI } from './categories'
+export { trashAPI } from './trash'
+export { journalAPI } from './journal'
+export { historyAPI } from './history'
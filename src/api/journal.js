@@ -0,0 +1,18 @@
+import { invoke } from '@tauri-apps/api/tauri'
+
+export const journalAPI = {
+  // Annuler la dernière opération (déplacement, association, modification, suppression)
+  undo: async () => {
+    return await invoke('undo_last')
+  },
+
+  // Rétablir la dernière opération annulée
+  redo: async () => {
+    return await invoke('redo')
+  },
+
+  // Historique des opérations, la plus récente en premier
+  list: async (limit = 50) => {
+    return await invoke('list_journal', { limit })
+  },
+}
@@ -0,0 +1,38 @@
+import { invoke } from '@tauri-apps/api/tauri'
+
+export const locationsAPI = {
+  // Lister tous les emplacements (entrepôt → étagère → boîte)
+  list: async () => {
+    return await invoke('list_locations')
+  },
+
+  // Chemin complet d'un emplacement (ex: "Garage / Étagère A / Boîte 3")
+  getPath: async (id) => {
+    return await invoke('get_location_path', { id })
+  },
+
+  // Créer un emplacement (kind: 'warehouse', 'room', 'shelf', 'box' ou 'other')
+  create: async (location) => {
+    return await invoke('create_location', { location })
+  },
+
+  // Modifier un emplacement
+  update: async (id, location) => {
+    return await invoke('update_location', { id, location })
+  },
+
+  // Supprimer un emplacement vide
+  delete: async (id) => {
+    return await invoke('delete_location', { id })
+  },
+
+  // Déplacer des objets vers un emplacement (null pour les retirer de leur emplacement)
+  moveObjects: async (objectIds, locationId, note = null) => {
+    return await invoke('move_objects_to_location', { objectIds, locationId, note })
+  },
+
+  // Historique des déplacements d'un objet
+  getObjectHistory: async (objectId) => {
+    return await invoke('get_object_location_history', { objectId })
+  },
+}
@@ -11,6 +11,11 @@ export const objectsAPI = {
     return await invoke('get_object', { id })
   },
 
+  // Rechercher des objets (texte, catégorie, emplacement)
+  search: async (filters) => {
+    return await invoke('search_objects', { filters })
+  },
+
   // Créer un nouvel objet
   create: async (object) => {
     return await invoke('create_object', { object })
@@ -25,4 +30,53 @@ export const objectsAPI = {
   delete: async (id) => {
     return await invoke('delete_object', { id })
   },
+
+  // Lire les en-têtes et premières lignes d'un fichier CSV/XLSX
+  previewSpreadsheet: async (filePath, sheetName = null) => {
+    return await invoke('preview_spreadsheet', { filePath, sheetName })
+  },
+
+  // Importer des objets depuis un fichier CSV/XLSX (dryRun pour prévisualiser)
+  importFromSpreadsheet: async (request) => {
+    return await invoke('import_objects', { request })
+  },
+
+  // Générer le SKU d'un objet (regenerate pour remplacer le SKU existant)
+  generateSku: async (objectId, regenerate = false) => {
+    return await invoke('generate_object_sku', { objectId, regenerate })
+  },
+
+  // Générer un SKU pour tous les objets qui n'en ont pas
+  generateMissingSkus: async () => {
+    return await invoke('generate_missing_skus')
+  },
+
+  // Prévisualiser le prochain SKU donné par un modèle (ex: '{prefix}-{yy}{mm}-{seq:4}')
+  previewSkuPattern: async (pattern, categoryId = null) => {
+    return await invoke('preview_sku_pattern', { pattern, categoryId })
+  },
+
+  // Enregistrer le modèle de SKU, vérifié avant d'être enregistré (vide pour le modèle par défaut)
+  setSkuPattern: async (pattern) => {
+    return await invoke('set_sku_pattern', { pattern })
+  },
+
+  // Définir le préfixe SKU d'une catégorie (null pour utiliser le préfixe par défaut)
+  setCategorySkuPrefix: async (categoryId, prefix) => {
+    return await invoke('set_category_sku_prefix', { categoryId, prefix })
+  },
+
+  // Générer une planche d'étiquettes PDF (SKU, nom, QR code)
+  printLabels: async (objectIds, options = {}) => {
+    return await invoke('print_object_labels', {
+      request: {
+        object_ids: objectIds,
+        columns: options.columns ?? null,
+        rows: options.rows ?? null,
+        skip_labels: options.skipLabels ?? null,
+        copies: options.copies ?? null,
+        destination_path: options.destinationPath ?? null
+      }
+    })
+  },
 }
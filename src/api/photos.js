@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/tauri'
+import { listen } from '@tauri-apps/api/event'
 
 export const photosAPI = {
   // Lister toutes les photos
@@ -89,6 +90,11 @@ export const photosAPI = {
     })
   },
 
+  // Suivre la progression des déplacements/copies (retourne la fonction pour arrêter l'écoute)
+  onTransferProgress: async (callback) => {
+    return await listen('transfer-progress', (event) => callback(event.payload))
+  },
+
   // Créer un nouveau dossier
   createFolder: async (folderPath) => {
     return await invoke('create_folder', {
@@ -108,9 +114,63 @@ export const photosAPI = {
     return await invoke('delete_folder_from_db', { folderId })
   },
 
-  // Synchroniser la base de données avec le système de fichiers
-  syncDatabase: async () => {
-    return await invoke('sync_database')
+  // Renommer un dossier sur le disque (les chemins des sous-dossiers, photos et fichiers texte suivent)
+  renameFolder: async (folderPath, newName) => {
+    return await invoke('rename_folder', { request: { folder_path: folderPath, new_name: newName } })
+  },
+
+  // Modifier les notes, la couleur (#rrggbb) et les tags d'un dossier
+  // Un champ absent n'est pas modifié, une valeur vide l'efface
+  updateFolderMetadata: async (folderId, metadata) => {
+    return await invoke('update_folder_metadata', { folderId, metadata })
+  },
+
+  // Tags déjà utilisés sur les dossiers (suggestions)
+  listFolderTags: async () => {
+    return await invoke('list_folder_tags')
+  },
+
+  // Créer un objet à partir d'un dossier (nom du dossier, description.txt, photos du dossier)
+  createObjectFromFolder: async (folderId) => {
+    return await invoke('create_object_from_folder', { folderId })
+  },
+
+  // Créer un dossier pour un objet (dans IMPORTS par défaut) et y déplacer ses photos
+  createFolderForObject: async (objectId, parentPath = null) => {
+    return await invoke('create_folder_for_object', { objectId, parentPath })
+  },
+
+  // Lier un dossier à un objet (null pour le délier)
+  setFolderObject: async (folderId, objectId) => {
+    return await invoke('set_folder_object', { folderId, objectId })
+  },
+
+  // État des description.txt des dossiers liés à un objet (tous les dossiers liés sans objectId)
+  // state : 'in_sync', 'object_changed', 'file_changed' ou 'conflict'
+  getDescriptionSyncStatus: async (objectId = null) => {
+    return await invoke('get_description_sync_status', { objectId })
+  },
+
+  // Reporter les modifications d'un côté à l'autre (les conflits sont renvoyés sans changement)
+  syncDescriptions: async (objectId = null) => {
+    return await invoke('sync_descriptions', { objectId })
+  },
+
+  // Résoudre un conflit en gardant la description de l'objet ('object') ou du fichier ('file')
+  resolveDescriptionConflict: async (objectId, folderId, keep) => {
+    return await invoke('resolve_description_conflict', { objectId, folderId, keep })
+  },
+
+  // Synchroniser la base de données avec le système de fichiers.
+  // Avec dryRun, rien n'est modifié : le résultat contient le plan des changements (result.plan)
+  // Seuls les dossiers modifiés depuis la dernière synchronisation sont relus, sauf avec full
+  syncDatabase: async (dryRun = false, full = false) => {
+    return await invoke('sync_database', { dryRun, full })
+  },
+
+  // Appliquer un plan de synchronisation vérifié au préalable (obtenu avec syncDatabase(true))
+  applySyncPlan: async (plan) => {
+    return await invoke('apply_sync_plan', { plan })
   },
 
   // Obtenir le dossier racine de la collection
@@ -122,4 +182,15 @@ export const photosAPI = {
   setRootFolder: async (path) => {
     return await invoke('set_root_folder', { path })
   },
+
+  // Indiquer le nouvel emplacement du dossier racine après l'avoir déplacé (seul le réglage change).
+  // Un échantillon de photos est vérifié (taille, empreinte) : sans force, rien n'est changé en cas d'écart
+  relocateRootFolder: async (path, force = false) => {
+    return await invoke('relocate_root_folder', { path, force })
+  },
+
+  // État de la bibliothèque (hors ligne quand le disque du dossier racine est débranché)
+  getLibraryStatus: async () => {
+    return await invoke('get_library_status')
+  },
 }
@@ -20,4 +20,21 @@ export const platformsAPI = {
   remove: async (id) => {
     return await invoke('remove_object_from_platform', { id })
   },
+
+  // Modifier le contenu d'une annonce (titre, description, prix, ...)
+  updateListing: async (id, request) => {
+    return await invoke('update_object_listing', { id, request })
+  },
+
+  // Vérifier une annonce selon les règles de la plateforme
+  validateListing: async (id) => {
+    return await invoke('validate_object_listing', { id })
+  },
+
+  // Exporter un dossier d'annonce (titre, description, photos, checklist) pour une publication manuelle
+  exportPackage: async (objectId, platformId, destinationPath = null) => {
+    return await invoke('export_listing_package', {
+      request: { object_id: objectId, platform_id: platformId, destination_path: destinationPath }
+    })
+  },
 }
@@ -0,0 +1,23 @@
+import { invoke } from '@tauri-apps/api/tauri'
+
+export const salesAPI = {
+  // Enregistrer la vente d'un objet (décrémente le stock)
+  recordObjectSale: async (sale) => {
+    return await invoke('record_object_sale', { sale })
+  },
+
+  // Enregistrer la vente d'un lot (prix réparti entre les objets du lot)
+  recordBundleSale: async (sale) => {
+    return await invoke('record_bundle_sale', { sale })
+  },
+
+  // Lister les ventes (éventuellement celles d'un objet)
+  list: async (objectId = null) => {
+    return await invoke('list_sales', { objectId })
+  },
+
+  // Annuler une vente (remet les objets en stock)
+  cancel: async (id) => {
+    return await invoke('cancel_sale', { id })
+  },
+}
@@ -40,4 +40,15 @@ export const settingsAPI = {
   setLanguage: async (value) => {
     return await invoke('set_setting', { key: 'language', value })
   },
+
+  // Règles d'exclusion des scans, de la synchronisation et des imports :
+  // { patterns, excluded_folders, min_file_size, min_dimension }
+  // (les fichiers .sellerignore des dossiers s'ajoutent aux motifs globaux)
+  getIgnoreSettings: async () => {
+    return await invoke('get_ignore_settings')
+  },
+
+  setIgnoreSettings: async (settings) => {
+    return await invoke('set_ignore_settings', { settings })
+  },
 }
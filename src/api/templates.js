@@ -0,0 +1,34 @@
+import { invoke } from '@tauri-apps/api/tauri'
+
+export const templatesAPI = {
+  // Lister les modèles de description
+  list: async () => {
+    return await invoke('list_templates')
+  },
+
+  // Créer un modèle
+  create: async (template) => {
+    return await invoke('create_template', { template })
+  },
+
+  // Modifier un modèle
+  // template: { name, platform_id, clear_platform, title_template, body, is_default }
+  update: async (id, template) => {
+    return await invoke('update_template', { id, template })
+  },
+
+  // Supprimer un modèle
+  delete: async (id) => {
+    return await invoke('delete_template', { id })
+  },
+
+  // Prévisualiser un modèle pour un objet (et une plateforme)
+  preview: async (request) => {
+    return await invoke('preview_template', { request })
+  },
+
+  // Appliquer un modèle au titre/description d'une annonce
+  applyToListing: async (objectPlatformId, templateId = null) => {
+    return await invoke('apply_template_to_listing', { objectPlatformId, templateId })
+  },
+}
@@ -0,0 +1,28 @@
+import { invoke } from '@tauri-apps/api/tauri'
+
+export const trashAPI = {
+  // Lister les éléments de la corbeille (photos, fichiers texte, dossiers, catégories)
+  list: async () => {
+    return await invoke('list_trash')
+  },
+
+  // Restaurer un élément à son emplacement d'origine, avec ses données
+  restore: async (id) => {
+    return await invoke('restore_trash_entry', { id })
+  },
+
+  // Supprimer définitivement un élément
+  purge: async (id) => {
+    return await invoke('purge_trash_entry', { id })
+  },
+
+  // Vider la corbeille
+  empty: async () => {
+    return await invoke('empty_trash')
+  },
+
+  // Supprimer les éléments plus anciens que la durée de conservation (setting trash_retention_days)
+  purgeExpired: async () => {
+    return await invoke('purge_expired_trash')
+  },
+}